@@ -11,14 +11,13 @@ const OCTAL_DIGITS: &'static str = "01234567";
 const BINARY_DIGITS: &'static str = "01";
 const VERTICAL_LINE: &'static str = "|";
 const WHITESPACE: &'static str = " \t\n\r";
-const DELIMITERS: &'static str = "()\";";
-const RESERVED: &'static str = "[]{}|";
+const DELIMITERS: &'static str = "()\";|";
+const RESERVED: &'static str = "[]{}";
 const EXACTNESS: [&'static str; 2] = ["#i", "#e"];
 const RADIX: [&'static str; 4] = ["#b", "#o", "#d", "#x"];
 const PECULIAR_IDENTIFIERS: [&'static str; 3] = ["...", "+", "-"];
 const SPECIAL_SUBSEQUENTS: &'static str = "+-@.";
 const NEWLINES: &'static str = "\n\r";
-const SIGNS: &'static str = "+-";
 
 fn gen_set_from_iterator<'a, I>(iter: I) -> phf_codegen::Set<&'a str>
 where
@@ -52,7 +51,8 @@ fn gen_token_set_code(out_dir: &Path) -> PathBuf {
     let peculiar_identifier = gen_set_from_iterator(PECULIAR_IDENTIFIERS.into_iter());
     let newline = gen_set_from_slice(&[NEWLINES]);
     let digit = gen_set_from_slice(&[DIGITS]);
-    let sign = gen_set_from_slice(&[SIGNS]);
+    let exactness = gen_set_from_iterator(EXACTNESS.into_iter());
+    let radix = gen_set_from_iterator(RADIX.into_iter());
     write!(
         file,
         "const WHITESPACE: phf::Set<&'static str> = {};\n",
@@ -95,10 +95,16 @@ fn gen_token_set_code(out_dir: &Path) -> PathBuf {
         digit.build()
     )
     .unwrap();
-    write!(
+    writeln!(
+        file,
+        "const EXACTNESS: phf::Set<&'static str> = {};",
+        exactness.build()
+    )
+    .unwrap();
+    writeln!(
         file,
-        "const SIGN: phf::Set<&'static str> = {};\n",
-        sign.build()
+        "const RADIX: phf::Set<&'static str> = {};",
+        radix.build()
     )
     .unwrap();
 
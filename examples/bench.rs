@@ -0,0 +1,30 @@
+use std::time::Instant;
+
+use rust_scheme::Interpreter;
+
+const DEFINITIONS: [&str; 3] = [
+    "(define (fib n) (if (< n 2) n (+ (fib (- n 1)) (fib (- n 2)))))",
+    "(define (make-adder n) (lambda (x) (+ x n)))",
+    "(define (apply-adder f n acc) (if (= n 0) acc (apply-adder f (- n 1) (f acc))))",
+];
+
+const WORKLOADS: [(&str, &str); 2] = [
+    ("fib 25", "(fib 25)"),
+    ("closure calls", "(apply-adder (make-adder 1) 1000 0)"),
+];
+
+const ROUNDS: u32 = 20;
+
+fn main() {
+    let mut interpreter = Interpreter::new();
+    for definition in DEFINITIONS {
+        interpreter.eval(definition).unwrap();
+    }
+    for (name, workload) in WORKLOADS {
+        let start = Instant::now();
+        for _ in 0..ROUNDS {
+            interpreter.eval(workload).unwrap();
+        }
+        println!("{:<16}{:?}", name, start.elapsed() / ROUNDS);
+    }
+}
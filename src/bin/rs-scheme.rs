@@ -1,26 +1,125 @@
-use std::io::{self, Stdin, Stdout, Write};
+use std::{
+    env,
+    fs::File,
+    io::{self, BufRead, BufReader, Write},
+    thread,
+};
 
-use rust_scheme::{create_global_frame, interpret, Value};
+use rust_scheme::{error::Error, InterruptHandle, Interpreter, Limits, Value};
 
-fn read(stdout: &mut Stdout, stdin: &Stdin, buffer: &mut String) -> Result<usize, io::Error> {
-    print!("scm> ");
-    stdout.flush()?;
-    stdin.read_line(buffer)
+/// The stack of the thread the REPL runs on, half of which evaluation may use.
+const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+fn read(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    buffer: &mut String,
+) -> Result<usize, io::Error> {
+    // a datum continued from an earlier line gets a different prompt
+    write!(
+        output,
+        "{}",
+        if buffer.is_empty() { "scm> " } else { "...> " }
+    )?;
+    output.flush()?;
+    input.read_line(buffer)
 }
 
-fn main() {
-    let mut global = create_global_frame();
+/// Reads and evaluates datums from `input` until it ends, keeping lines that
+/// end inside a datum until it is complete.
+fn repl(
+    interpreter: &mut Interpreter,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> Result<(), io::Error> {
     let mut buffer = String::new();
-    let stdin = io::stdin();
-    let mut stdout = io::stdout();
-    while read(&mut stdout, &stdin, &mut buffer).is_ok() {
-        match interpret(&buffer, &mut global) {
+    while read(input, output, &mut buffer)? > 0 {
+        match interpreter.eval(&buffer) {
             Ok(value) => match value {
                 Value::Void => {}
-                _ => println!("{}", value),
+                _ => writeln!(output, "{}", value)?,
             },
-            Err(error) => println!("Error: {}", error),
+            Err(error) if error.is_incomplete() => continue,
+            Err(Error::Interrupted) => writeln!(output, "Interrupted")?,
+            Err(error) => writeln!(output, "Error: {}", error.render())?,
         }
         buffer.clear();
     }
+    writeln!(output)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn install_interrupt_handler(handle: InterruptHandle) {
+    use signal_hook::{consts::SIGINT, low_level};
+
+    // SAFETY: the handler only stores to an atomic flag, which is async-signal-safe.
+    if let Err(error) = unsafe { low_level::register(SIGINT, move || handle.interrupt()) } {
+        eprintln!("Warning: cannot install the SIGINT handler: {}", error);
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn install_interrupt_handler(_: InterruptHandle) {}
+
+fn main() {
+    let repl = thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(run)
+        .expect("cannot start the REPL thread");
+    if repl.join().is_err() {
+        std::process::exit(1);
+    }
+}
+
+fn run() {
+    let mut interpreter = Interpreter::with_limits(Limits {
+        max_stack: Some(STACK_SIZE / 2),
+        ..Limits::default()
+    });
+    if let Some(path) = env::args().nth(1) {
+        let loaded = File::open(&path)
+            .map_err(|error| error.into())
+            .and_then(|file| interpreter.load_image(BufReader::new(file)));
+        if let Err(error) = loaded {
+            eprintln!("Error: cannot load {}: {}", path, error);
+            std::process::exit(1);
+        }
+    }
+    install_interrupt_handler(interpreter.interrupt_handle());
+    let _ = repl(&mut interpreter, &mut io::stdin().lock(), &mut io::stdout());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(input: &str) -> String {
+        let mut output = vec![];
+        repl(&mut Interpreter::new(), &mut input.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_continued_input() {
+        assert_eq!(session("(+ 1\n2)\n#;(3)\n"), "scm> ...> 3\nscm> scm> \n");
+    }
+
+    #[test]
+    fn test_truncated_include() {
+        let path = env::temp_dir().join(format!("truncated-{}.scm", std::process::id()));
+        std::fs::write(&path, "(define x 1)\n(define y").unwrap();
+        let path = path.to_str().unwrap();
+        let transcript = session(&format!("(include {:?})\n(+ 1 2)\n", path));
+        std::fs::remove_file(path).unwrap();
+        assert!(
+            transcript.starts_with(&format!(
+                "scm> Error: unexpected end of file in {}\n --> {}:2:1\n",
+                path, path
+            )),
+            "{}",
+            transcript
+        );
+        assert!(transcript.ends_with("scm> 3\nscm> \n"), "{}", transcript);
+        assert!(!transcript.contains("...>"), "{}", transcript);
+    }
 }
@@ -0,0 +1,134 @@
+use crate::{
+    data_model::{BuiltinProcedure, Value},
+    error::{invalid_number, validate_number_of_arguments, ApplyError, InvalidArgument},
+    number::Number,
+};
+
+/// Folds the arguments with `operation`, starting from `identity`.
+fn fold(
+    args: &[Value],
+    identity: Number,
+    operation: fn(&Number, &Number) -> Result<Number, InvalidArgument>,
+) -> Result<Value, ApplyError> {
+    let mut result = identity;
+    for arg in args {
+        result = operation(&result, arg.as_number().ok_or(invalid_number(arg))?)?;
+    }
+    Ok(result.into())
+}
+
+fn index_argument(value: &Value) -> Result<usize, InvalidArgument> {
+    value.as_number().ok_or(invalid_number(value))?.to_index()
+}
+
+pub(crate) const BITWISE_AND: BuiltinProcedure = BuiltinProcedure {
+    name: "bitwise-and",
+    function: bitwise_and,
+};
+
+fn bitwise_and(args: &[Value]) -> Result<Value, ApplyError> {
+    fold(args, Number::Integer(-1), Number::bitwise_and)
+}
+
+pub(crate) const BITWISE_OR: BuiltinProcedure = BuiltinProcedure {
+    name: "bitwise-or",
+    function: bitwise_or,
+};
+
+fn bitwise_or(args: &[Value]) -> Result<Value, ApplyError> {
+    fold(args, Number::Integer(0), Number::bitwise_or)
+}
+
+pub(crate) const BITWISE_XOR: BuiltinProcedure = BuiltinProcedure {
+    name: "bitwise-xor",
+    function: bitwise_xor,
+};
+
+fn bitwise_xor(args: &[Value]) -> Result<Value, ApplyError> {
+    fold(args, Number::Integer(0), Number::bitwise_xor)
+}
+
+pub(crate) const BITWISE_NOT: BuiltinProcedure = BuiltinProcedure {
+    name: "bitwise-not",
+    function: bitwise_not,
+};
+
+fn bitwise_not(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[bitwise-not]", 1, 1, args.len())?;
+    let number = args[0].as_number().ok_or(invalid_number(&args[0]))?;
+    Ok(number.bitwise_not()?.into())
+}
+
+pub(crate) const ARITHMETIC_SHIFT: BuiltinProcedure = BuiltinProcedure {
+    name: "arithmetic-shift",
+    function: arithmetic_shift,
+};
+
+fn arithmetic_shift(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[arithmetic-shift]", 2, 2, args.len())?;
+    let number = args[0].as_number().ok_or(invalid_number(&args[0]))?;
+    let count = args[1].as_number().ok_or(invalid_number(&args[1]))?;
+    Ok(number.arithmetic_shift(count)?.into())
+}
+
+pub(crate) const BIT_COUNT: BuiltinProcedure = BuiltinProcedure {
+    name: "bit-count",
+    function: bit_count,
+};
+
+fn bit_count(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[bit-count]", 1, 1, args.len())?;
+    let number = args[0].as_number().ok_or(invalid_number(&args[0]))?;
+    Ok(number.bit_count()?.into())
+}
+
+pub(crate) const INTEGER_LENGTH: BuiltinProcedure = BuiltinProcedure {
+    name: "integer-length",
+    function: integer_length,
+};
+
+fn integer_length(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[integer-length]", 1, 1, args.len())?;
+    let number = args[0].as_number().ok_or(invalid_number(&args[0]))?;
+    Ok(number.integer_length()?.into())
+}
+
+pub(crate) const IS_BIT_SET: BuiltinProcedure = BuiltinProcedure {
+    name: "bit-set?",
+    function: is_bit_set,
+};
+
+fn is_bit_set(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[bit-set?]", 2, 2, args.len())?;
+    let index = index_argument(&args[0])?;
+    let number = args[1].as_number().ok_or(invalid_number(&args[1]))?;
+    Ok(number.is_bit_set(index)?.into())
+}
+
+pub(crate) const COPY_BIT: BuiltinProcedure = BuiltinProcedure {
+    name: "copy-bit",
+    function: copy_bit,
+};
+
+fn copy_bit(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[copy-bit]", 3, 3, args.len())?;
+    let index = index_argument(&args[0])?;
+    let number = args[1].as_number().ok_or(invalid_number(&args[1]))?;
+    let bit = args[2]
+        .as_boolean()
+        .ok_or_else(|| InvalidArgument::InvalidType(args[2].to_string(), "boolean".to_string()))?;
+    Ok(number.copy_bit(index, *bit)?.into())
+}
+
+pub(crate) const BIT_FIELD: BuiltinProcedure = BuiltinProcedure {
+    name: "bit-field",
+    function: bit_field,
+};
+
+fn bit_field(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[bit-field]", 3, 3, args.len())?;
+    let number = args[0].as_number().ok_or(invalid_number(&args[0]))?;
+    let start = index_argument(&args[1])?;
+    let end = index_argument(&args[2])?;
+    Ok(number.bit_field(start, end)?.into())
+}
@@ -0,0 +1,135 @@
+use crate::{
+    builtin::numeric_vector::{
+        append, construct, copy, copy_into, get, is_vector, length, make, range_arguments, reserve,
+        set, vector_argument,
+    },
+    data_model::{BuiltinProcedure, Value},
+    error::{validate_number_of_arguments, ApplyError, InvalidArgument},
+    numeric_vector::{NumericKind, NumericVector},
+};
+
+// Bytevectors are SRFI 160 `u8vector`s under their R7RS names.
+
+pub(crate) const IS_BYTEVECTOR: BuiltinProcedure = BuiltinProcedure {
+    name: "bytevector?",
+    function: is_bytevector,
+};
+
+fn is_bytevector(args: &[Value]) -> Result<Value, ApplyError> {
+    is_vector("#[bytevector?]", NumericKind::U8, args)
+}
+
+pub(crate) const BYTEVECTOR: BuiltinProcedure = BuiltinProcedure {
+    name: "bytevector",
+    function: bytevector,
+};
+
+fn bytevector(args: &[Value]) -> Result<Value, ApplyError> {
+    construct("#[bytevector]", NumericKind::U8, args)
+}
+
+pub(crate) const MAKE_BYTEVECTOR: BuiltinProcedure = BuiltinProcedure {
+    name: "make-bytevector",
+    function: make_bytevector,
+};
+
+fn make_bytevector(args: &[Value]) -> Result<Value, ApplyError> {
+    make("#[make-bytevector]", NumericKind::U8, args)
+}
+
+pub(crate) const BYTEVECTOR_LENGTH: BuiltinProcedure = BuiltinProcedure {
+    name: "bytevector-length",
+    function: bytevector_length,
+};
+
+fn bytevector_length(args: &[Value]) -> Result<Value, ApplyError> {
+    length("#[bytevector-length]", NumericKind::U8, args)
+}
+
+pub(crate) const BYTEVECTOR_U8_REF: BuiltinProcedure = BuiltinProcedure {
+    name: "bytevector-u8-ref",
+    function: bytevector_u8_ref,
+};
+
+fn bytevector_u8_ref(args: &[Value]) -> Result<Value, ApplyError> {
+    get("#[bytevector-u8-ref]", NumericKind::U8, args)
+}
+
+pub(crate) const BYTEVECTOR_U8_SET: BuiltinProcedure = BuiltinProcedure {
+    name: "bytevector-u8-set!",
+    function: bytevector_u8_set,
+};
+
+fn bytevector_u8_set(args: &[Value]) -> Result<Value, ApplyError> {
+    set("#[bytevector-u8-set!]", NumericKind::U8, args)
+}
+
+pub(crate) const BYTEVECTOR_COPY: BuiltinProcedure = BuiltinProcedure {
+    name: "bytevector-copy",
+    function: bytevector_copy,
+};
+
+fn bytevector_copy(args: &[Value]) -> Result<Value, ApplyError> {
+    copy("#[bytevector-copy]", NumericKind::U8, args)
+}
+
+pub(crate) const BYTEVECTOR_COPY_INTO: BuiltinProcedure = BuiltinProcedure {
+    name: "bytevector-copy!",
+    function: bytevector_copy_into,
+};
+
+fn bytevector_copy_into(args: &[Value]) -> Result<Value, ApplyError> {
+    copy_into("#[bytevector-copy!]", NumericKind::U8, args)
+}
+
+pub(crate) const BYTEVECTOR_APPEND: BuiltinProcedure = BuiltinProcedure {
+    name: "bytevector-append",
+    function: bytevector_append,
+};
+
+fn bytevector_append(args: &[Value]) -> Result<Value, ApplyError> {
+    append("#[bytevector-append]", NumericKind::U8, args)
+}
+
+pub(crate) const UTF8_TO_STRING: BuiltinProcedure = BuiltinProcedure {
+    name: "utf8->string",
+    function: utf8_to_string,
+};
+
+fn utf8_to_string(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[utf8->string]", 1, 3, args.len())?;
+    let vector = vector_argument(&args[0], NumericKind::U8)?.borrow();
+    let (start, end) = range_arguments(&args[1..], vector.len())?;
+    let NumericVector::U8(bytes) = &*vector else {
+        unreachable!("checked to be a bytevector")
+    };
+    reserve(end - start, 1)?;
+    let string = std::str::from_utf8(&bytes[start..end]).map_err(|_| {
+        InvalidArgument::InvalidType(args[0].to_string(), "UTF-8 bytevector".to_string())
+    })?;
+    Ok(string.into())
+}
+
+pub(crate) const STRING_TO_UTF8: BuiltinProcedure = BuiltinProcedure {
+    name: "string->utf8",
+    function: string_to_utf8,
+};
+
+fn string_to_utf8(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[string->utf8]", 1, 3, args.len())?;
+    let string = args[0]
+        .as_string()
+        .ok_or_else(|| InvalidArgument::InvalidType(args[0].to_string(), "string".to_string()))?;
+    // The range counts characters, so it is mapped to byte offsets.
+    let (start, end) = range_arguments(&args[1..], string.chars().count())?;
+    let offset = |index: usize| {
+        string
+            .char_indices()
+            .nth(index)
+            .map_or(string.len(), |(offset, _)| offset)
+    };
+    let (start, end) = (offset(start), offset(end));
+    reserve(end - start, 1)?;
+    let bytes = string.as_bytes()[start..end].to_vec();
+    Ok(NumericVector::U8(bytes).into())
+}
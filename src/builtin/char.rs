@@ -0,0 +1,284 @@
+use std::cmp::Ordering;
+
+use crate::{
+    data_model::{BuiltinProcedure, Value},
+    error::{invalid_number, validate_number_of_arguments, ApplyError, InvalidArgument},
+    number::Number,
+};
+
+/// The zero of every run of ten decimal digits (general category Nd) in
+/// Unicode 14.0. Each run holds the digits zero to nine in order.
+const DECIMAL_ZEROS: [u32; 66] = [
+    0x30, 0x660, 0x6F0, 0x7C0, 0x966, 0x9E6, 0xA66, 0xAE6, 0xB66, 0xBE6, 0xC66, 0xCE6, 0xD66,
+    0xDE6, 0xE50, 0xED0, 0xF20, 0x1040, 0x1090, 0x17E0, 0x1810, 0x1946, 0x19D0, 0x1A80, 0x1A90,
+    0x1B50, 0x1BB0, 0x1C40, 0x1C50, 0xA620, 0xA8D0, 0xA900, 0xA9D0, 0xA9F0, 0xAA50, 0xABF0, 0xFF10,
+    0x104A0, 0x10D30, 0x11066, 0x110F0, 0x11136, 0x111D0, 0x112F0, 0x11450, 0x114D0, 0x11650,
+    0x116C0, 0x11730, 0x118E0, 0x11950, 0x11C50, 0x11D50, 0x11DA0, 0x16A60, 0x16AC0, 0x16B50,
+    0x1D7CE, 0x1D7D8, 0x1D7E2, 0x1D7EC, 0x1D7F6, 0x1E140, 0x1E2F0, 0x1E950, 0x1FBF0,
+];
+
+/// The value of a decimal digit in any script.
+pub(crate) fn digit_value(character: char) -> Option<u32> {
+    let code = character as u32;
+    let index = DECIMAL_ZEROS.partition_point(|zero| *zero <= code);
+    let offset = code - DECIMAL_ZEROS[index.checked_sub(1)?];
+    (offset < 10).then_some(offset)
+}
+
+/// Keeps `character` when its case mapping is more than one character, as
+/// `char-upcase` of `ß` would be `SS`.
+fn single(mut mapping: impl Iterator<Item = char>, character: char) -> char {
+    match (mapping.next(), mapping.next()) {
+        (Some(mapped), None) => mapped,
+        _ => character,
+    }
+}
+
+pub(crate) fn upcase(character: char) -> char {
+    single(character.to_uppercase(), character)
+}
+
+pub(crate) fn downcase(character: char) -> char {
+    single(character.to_lowercase(), character)
+}
+
+/// Simple case folding, so that `ς` and `σ` fold to the same character.
+pub(crate) fn foldcase(character: char) -> char {
+    downcase(upcase(character))
+}
+
+fn character_argument(value: &Value) -> Result<char, InvalidArgument> {
+    value
+        .as_character()
+        .ok_or_else(|| InvalidArgument::InvalidType(value.to_string(), "character".to_string()))
+}
+
+fn compare(
+    name: &str,
+    args: &[Value],
+    key: fn(char) -> char,
+    expected: fn(Ordering) -> bool,
+) -> Result<Value, ApplyError> {
+    validate_number_of_arguments(name, 2, usize::MAX, args.len())?;
+    let characters = args
+        .iter()
+        .map(|arg| character_argument(arg).map(key))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(characters
+        .windows(2)
+        .all(|pair| expected(pair[0].cmp(&pair[1])))
+        .into())
+}
+
+fn identity(character: char) -> char {
+    character
+}
+
+/// Defines a one-argument character procedure from `$body`, which has the
+/// character bound to `$character`.
+macro_rules! unary {
+    ($constant:ident, $name:literal, $function:ident, |$character:ident| $body:expr) => {
+        pub(crate) const $constant: BuiltinProcedure = BuiltinProcedure {
+            name: $name,
+            function: $function,
+        };
+
+        fn $function(args: &[Value]) -> Result<Value, ApplyError> {
+            validate_number_of_arguments(concat!("#[", $name, "]"), 1, 1, args.len())?;
+            let $character = character_argument(&args[0])?;
+            Ok($body.into())
+        }
+    };
+}
+
+/// Defines a character comparison; `$key` maps each character before the
+/// comparison.
+macro_rules! comparison {
+    ($constant:ident, $name:literal, $function:ident, $key:expr, $expected:expr) => {
+        pub(crate) const $constant: BuiltinProcedure = BuiltinProcedure {
+            name: $name,
+            function: $function,
+        };
+
+        fn $function(args: &[Value]) -> Result<Value, ApplyError> {
+            compare(concat!("#[", $name, "]"), args, $key, $expected)
+        }
+    };
+}
+
+pub(crate) const IS_CHAR: BuiltinProcedure = BuiltinProcedure {
+    name: "char?",
+    function: is_char,
+};
+
+fn is_char(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[char?]", 1, 1, args.len())?;
+    Ok(args[0].as_character().is_some().into())
+}
+
+unary!(
+    CHAR_TO_INTEGER,
+    "char->integer",
+    char_to_integer,
+    |character| { Number::Integer(character as i64) }
+);
+
+pub(crate) const INTEGER_TO_CHAR: BuiltinProcedure = BuiltinProcedure {
+    name: "integer->char",
+    function: integer_to_char,
+};
+
+fn integer_to_char(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[integer->char]", 1, 1, args.len())?;
+    let number = args[0].as_number().ok_or(invalid_number(&args[0]))?;
+    let code = number.to_index()?;
+    u32::try_from(code)
+        .ok()
+        .and_then(char::from_u32)
+        .map(Value::from)
+        .ok_or_else(|| InvalidArgument::OutOfRange(number.to_string()).into())
+}
+
+comparison!(CHAR_EQUAL, "char=?", char_equal, identity, Ordering::is_eq);
+comparison!(
+    CHAR_LESS_THAN,
+    "char<?",
+    char_less_than,
+    identity,
+    Ordering::is_lt
+);
+comparison!(
+    CHAR_GREATER_THAN,
+    "char>?",
+    char_greater_than,
+    identity,
+    Ordering::is_gt
+);
+comparison!(
+    CHAR_LESS_THAN_OR_EQUAL,
+    "char<=?",
+    char_less_than_or_equal,
+    identity,
+    Ordering::is_le
+);
+comparison!(
+    CHAR_GREATER_THAN_OR_EQUAL,
+    "char>=?",
+    char_greater_than_or_equal,
+    identity,
+    Ordering::is_ge
+);
+comparison!(
+    CHAR_CI_EQUAL,
+    "char-ci=?",
+    char_ci_equal,
+    foldcase,
+    Ordering::is_eq
+);
+comparison!(
+    CHAR_CI_LESS_THAN,
+    "char-ci<?",
+    char_ci_less_than,
+    foldcase,
+    Ordering::is_lt
+);
+comparison!(
+    CHAR_CI_GREATER_THAN,
+    "char-ci>?",
+    char_ci_greater_than,
+    foldcase,
+    Ordering::is_gt
+);
+comparison!(
+    CHAR_CI_LESS_THAN_OR_EQUAL,
+    "char-ci<=?",
+    char_ci_less_than_or_equal,
+    foldcase,
+    Ordering::is_le
+);
+comparison!(
+    CHAR_CI_GREATER_THAN_OR_EQUAL,
+    "char-ci>=?",
+    char_ci_greater_than_or_equal,
+    foldcase,
+    Ordering::is_ge
+);
+
+unary!(
+    IS_CHAR_ALPHABETIC,
+    "char-alphabetic?",
+    is_char_alphabetic,
+    |character| { character.is_alphabetic() }
+);
+unary!(
+    IS_CHAR_NUMERIC,
+    "char-numeric?",
+    is_char_numeric,
+    |character| { digit_value(character).is_some() }
+);
+unary!(
+    IS_CHAR_WHITESPACE,
+    "char-whitespace?",
+    is_char_whitespace,
+    |character| { character.is_whitespace() }
+);
+unary!(
+    IS_CHAR_UPPER_CASE,
+    "char-upper-case?",
+    is_char_upper_case,
+    |character| { character.is_uppercase() }
+);
+unary!(
+    IS_CHAR_LOWER_CASE,
+    "char-lower-case?",
+    is_char_lower_case,
+    |character| { character.is_lowercase() }
+);
+unary!(
+    DIGIT_VALUE,
+    "digit-value",
+    digit_value_procedure,
+    |character| {
+        match digit_value(character) {
+            Some(value) => Value::from(Number::Integer(value.into())),
+            None => Value::from(false),
+        }
+    }
+);
+unary!(CHAR_UPCASE, "char-upcase", char_upcase, |character| {
+    upcase(character)
+});
+unary!(CHAR_DOWNCASE, "char-downcase", char_downcase, |character| {
+    downcase(character)
+});
+unary!(CHAR_FOLDCASE, "char-foldcase", char_foldcase, |character| {
+    foldcase(character)
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digit_value() {
+        assert_eq!(digit_value('0'), Some(0));
+        assert_eq!(digit_value('9'), Some(9));
+        assert_eq!(digit_value('\u{664}'), Some(4));
+        assert_eq!(digit_value('\u{FF19}'), Some(9));
+        assert_eq!(digit_value('\u{1D7FF}'), Some(9));
+        assert_eq!(digit_value('/'), None);
+        assert_eq!(digit_value('a'), None);
+        assert_eq!(digit_value('\u{B2}'), None);
+        assert_eq!(digit_value('\u{2460}'), None);
+    }
+
+    #[test]
+    fn test_case() {
+        assert_eq!(upcase('a'), 'A');
+        assert_eq!(upcase('ß'), 'ß');
+        assert_eq!(downcase('Λ'), 'λ');
+        assert_eq!(foldcase('ς'), 'σ');
+        assert_eq!(foldcase('Σ'), 'σ');
+        assert_eq!(foldcase('1'), '1');
+    }
+}
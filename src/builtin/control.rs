@@ -0,0 +1,40 @@
+use std::iter;
+
+use crate::{
+    data_model::{BuiltinProcedure, EnvironmentProcedure, Frame, Value},
+    error::{validate_number_of_arguments, ApplyError, InvalidArgument},
+    evaluator::Operands,
+};
+
+pub(crate) const VALUES: BuiltinProcedure = BuiltinProcedure {
+    name: "values",
+    function: values,
+};
+
+fn values(args: &[Value]) -> Result<Value, ApplyError> {
+    match args {
+        [value] => Ok(value.clone()),
+        _ => Ok(Value::Values(args.to_vec())),
+    }
+}
+
+pub(crate) const CALL_WITH_VALUES: EnvironmentProcedure = EnvironmentProcedure {
+    name: "call-with-values",
+    function: call_with_values,
+};
+
+fn call_with_values(args: &[Value], frame: &mut Frame) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[call-with-values]", 2, 2, args.len())?;
+    let procedure = |value: &Value| {
+        value
+            .as_procedure()
+            .cloned()
+            .ok_or_else(|| InvalidArgument::InvalidType(value.to_string(), "procedure".to_string()))
+    };
+    let mut producer = procedure(&args[0])?;
+    let mut consumer = procedure(&args[1])?;
+    match producer.apply(Operands::new(), frame)? {
+        Value::Values(values) => Ok(consumer.apply(values.into(), frame)?),
+        value => Ok(consumer.apply(iter::once(value).collect(), frame)?),
+    }
+}
@@ -8,35 +8,35 @@ use crate::{
 // Turtle motion
 // move and draw
 
-pub fn forward(args: Vec<Value>, canvas: &mut Canvas) -> Result<Value, ApplyError> {
+pub fn forward(args: &[Value], canvas: &mut Canvas) -> Result<Value, ApplyError> {
     validate_number_of_arguments("#[forward]", 1, 1, args.len())?;
     let distance = args[0].as_number().ok_or(invalid_number(&args[0]))?;
     canvas.forward(distance.try_into()?);
     Ok(Value::Void)
 }
 
-pub fn backward(args: Vec<Value>, canvas: &mut Canvas) -> Result<Value, ApplyError> {
+pub fn backward(args: &[Value], canvas: &mut Canvas) -> Result<Value, ApplyError> {
     validate_number_of_arguments("#[backward]", 1, 1, args.len())?;
     let distance = args[0].as_number().ok_or(invalid_number(&args[0]))?;
     canvas.forward(-distance.try_into()?);
     Ok(Value::Void)
 }
 
-pub fn right(args: Vec<Value>, canvas: &mut Canvas) -> Result<Value, ApplyError> {
+pub fn right(args: &[Value], canvas: &mut Canvas) -> Result<Value, ApplyError> {
     validate_number_of_arguments("#[right]", 1, 1, args.len())?;
     let angle = args[0].as_number().ok_or(invalid_number(&args[0]))?;
     canvas.rotate(-angle.try_into()?);
     Ok(Value::Void)
 }
 
-pub fn left(args: Vec<Value>, canvas: &mut Canvas) -> Result<Value, ApplyError> {
+pub fn left(args: &[Value], canvas: &mut Canvas) -> Result<Value, ApplyError> {
     validate_number_of_arguments("#[left]", 1, 1, args.len())?;
     let angle = args[0].as_number().ok_or(invalid_number(&args[0]))?;
     canvas.rotate(angle.try_into()?);
     Ok(Value::Void)
 }
 
-pub fn setposition(args: Vec<Value>, canvas: &mut Canvas) -> Result<Value, ApplyError> {
+pub fn setposition(args: &[Value], canvas: &mut Canvas) -> Result<Value, ApplyError> {
     validate_number_of_arguments("#[setposition]", 2, 2, args.len())?;
     let x = args[0].as_number().ok_or(invalid_number(&args[0]))?;
     let y = args[1].as_number().ok_or(invalid_number(&args[1]))?;
@@ -44,7 +44,7 @@ pub fn setposition(args: Vec<Value>, canvas: &mut Canvas) -> Result<Value, Apply
     Ok(Value::Void)
 }
 
-pub fn setheading(args: Vec<Value>, canvas: &mut Canvas) -> Result<Value, ApplyError> {
+pub fn setheading(args: &[Value], canvas: &mut Canvas) -> Result<Value, ApplyError> {
     validate_number_of_arguments("#[setheading]", 1, 1, args.len())?;
     let angle = args[0].as_number().ok_or(invalid_number(&args[0]))?;
     let angle: f64 = angle.try_into()?;
@@ -54,7 +54,7 @@ pub fn setheading(args: Vec<Value>, canvas: &mut Canvas) -> Result<Value, ApplyE
 
 // tell turtle state
 
-pub fn position(args: Vec<Value>, canvas: &mut Canvas) -> Result<Value, ApplyError> {
+pub fn position(args: &[Value], canvas: &mut Canvas) -> Result<Value, ApplyError> {
     validate_number_of_arguments("#[position]", 0, 0, args.len())?;
     let x: Number = canvas.content.borrow().x.into();
     let y: Number = (-canvas.content.borrow().y).into();
@@ -62,7 +62,7 @@ pub fn position(args: Vec<Value>, canvas: &mut Canvas) -> Result<Value, ApplyErr
     Ok(pair_link.into())
 }
 
-pub fn heading(args: Vec<Value>, canvas: &mut Canvas) -> Result<Value, ApplyError> {
+pub fn heading(args: &[Value], canvas: &mut Canvas) -> Result<Value, ApplyError> {
     validate_number_of_arguments("#[heading]", 0, 0, args.len())?;
     let angle: Number = (90.0 + canvas.content.borrow().angle).into();
     Ok(angle.into())
@@ -70,31 +70,31 @@ pub fn heading(args: Vec<Value>, canvas: &mut Canvas) -> Result<Value, ApplyErro
 
 // Pen control
 
-pub fn begin_fill(args: Vec<Value>, canvas: &mut Canvas) -> Result<Value, ApplyError> {
+pub fn begin_fill(args: &[Value], canvas: &mut Canvas) -> Result<Value, ApplyError> {
     validate_number_of_arguments("#[begin-fill]", 0, 0, args.len())?;
     canvas.begin_fill();
     Ok(Value::Void)
 }
 
-pub fn end_fill(args: Vec<Value>, canvas: &mut Canvas) -> Result<Value, ApplyError> {
+pub fn end_fill(args: &[Value], canvas: &mut Canvas) -> Result<Value, ApplyError> {
     validate_number_of_arguments("#[end-fill]", 0, 0, args.len())?;
     canvas.end_fill();
     Ok(Value::Void)
 }
 
-pub fn penup(args: Vec<Value>, canvas: &mut Canvas) -> Result<Value, ApplyError> {
+pub fn penup(args: &[Value], canvas: &mut Canvas) -> Result<Value, ApplyError> {
     validate_number_of_arguments("#[penup]", 0, 0, args.len())?;
     canvas.penup();
     Ok(Value::Void)
 }
 
-pub fn pendown(args: Vec<Value>, canvas: &mut Canvas) -> Result<Value, ApplyError> {
+pub fn pendown(args: &[Value], canvas: &mut Canvas) -> Result<Value, ApplyError> {
     validate_number_of_arguments("#[pendown]", 0, 0, args.len())?;
     canvas.pendown();
     Ok(Value::Void)
 }
 
-pub fn reset(args: Vec<Value>, canvas: &mut Canvas) -> Result<Value, ApplyError> {
+pub fn reset(args: &[Value], canvas: &mut Canvas) -> Result<Value, ApplyError> {
     validate_number_of_arguments("#[reset]", 0, 0, args.len())?;
     canvas.reset();
     Ok(Value::Void)
@@ -102,19 +102,19 @@ pub fn reset(args: Vec<Value>, canvas: &mut Canvas) -> Result<Value, ApplyError>
 
 // turtle state
 
-pub fn show_turtle(args: Vec<Value>, canvas: &mut Canvas) -> Result<Value, ApplyError> {
+pub fn show_turtle(args: &[Value], canvas: &mut Canvas) -> Result<Value, ApplyError> {
     validate_number_of_arguments("#[showturtle]", 0, 0, args.len())?;
     canvas.show_turtle();
     Ok(Value::Void)
 }
 
-pub fn hide_turtle(args: Vec<Value>, canvas: &mut Canvas) -> Result<Value, ApplyError> {
+pub fn hide_turtle(args: &[Value], canvas: &mut Canvas) -> Result<Value, ApplyError> {
     validate_number_of_arguments("#[hideturtle]", 0, 0, args.len())?;
     canvas.hide_turtle();
     Ok(Value::Void)
 }
 
-pub fn is_visible(args: Vec<Value>, canvas: &mut Canvas) -> Result<Value, ApplyError> {
+pub fn is_visible(args: &[Value], canvas: &mut Canvas) -> Result<Value, ApplyError> {
     validate_number_of_arguments("#[visible?]", 0, 0, args.len())?;
     Ok(canvas.is_visible().into())
 }
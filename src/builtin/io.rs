@@ -0,0 +1,176 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::{fs::File, io::BufWriter};
+
+use crate::{
+    data_model::{BuiltinProcedure, Sharing, Style, Value, Written},
+    error::{validate_number_of_arguments, ApplyError, InvalidArgument},
+    output,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{
+    data_model::{EnvironmentProcedure, Frame},
+    error::ImageError,
+    image,
+};
+
+pub(crate) const DISPLAY: BuiltinProcedure = BuiltinProcedure {
+    name: "display",
+    function: display,
+};
+
+fn display(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[display]", 1, 1, args.len())?;
+    write_value(&args[0], Sharing::Cycles, Style::Display);
+    Ok(Value::Void)
+}
+
+pub(crate) const NEWLINE: BuiltinProcedure = BuiltinProcedure {
+    name: "newline",
+    function: newline,
+};
+
+fn newline(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[newline]", 0, 0, args.len())?;
+    output::write("\n");
+    Ok(Value::Void)
+}
+
+pub(crate) const WRITE: BuiltinProcedure = BuiltinProcedure {
+    name: "write",
+    function: write,
+};
+
+fn write(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[write]", 1, 1, args.len())?;
+    write_value(&args[0], Sharing::Cycles, Style::Write);
+    Ok(Value::Void)
+}
+
+pub(crate) const WRITE_SHARED: BuiltinProcedure = BuiltinProcedure {
+    name: "write-shared",
+    function: write_shared,
+};
+
+fn write_shared(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[write-shared]", 1, 1, args.len())?;
+    write_value(&args[0], Sharing::All, Style::Write);
+    Ok(Value::Void)
+}
+
+pub(crate) const WRITE_SIMPLE: BuiltinProcedure = BuiltinProcedure {
+    name: "write-simple",
+    function: write_simple,
+};
+
+fn write_simple(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[write-simple]", 1, 1, args.len())?;
+    // written without labels, it would never end
+    if let Value::Expression(expression) = &args[0] {
+        if expression.content.is_circular() {
+            return Err(InvalidArgument::InvalidType(
+                args[0].to_string(),
+                "datum without cycles".to_string(),
+            )
+            .into());
+        }
+    }
+    write_value(&args[0], Sharing::None, Style::Write);
+    Ok(Value::Void)
+}
+
+fn write_value(value: &Value, sharing: Sharing, style: Style) {
+    match value {
+        Value::Expression(expression) => output::write(
+            &Written {
+                datum: &expression.content,
+                sharing,
+                style,
+            }
+            .to_string(),
+        ),
+        value => output::write(&value.to_string()),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) const SAVE_IMAGE: EnvironmentProcedure = EnvironmentProcedure {
+    name: "save-image",
+    function: save_image,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_image(args: &[Value], frame: &mut Frame) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[save-image]", 1, 1, args.len())?;
+    let path = args[0].as_string().ok_or_else(|| {
+        InvalidArgument::InvalidType(args[0].to_string(), "string".to_string())
+    })?;
+    let file = File::create(path).map_err(ImageError::from)?;
+    image::save(frame, BufWriter::new(file))?;
+    Ok(Value::Void)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{create_global_frame, interpret, output};
+
+    fn written(input: &str) -> String {
+        let mut frame = create_global_frame();
+        let (result, output) = output::capture(|| interpret(input, &mut frame));
+        result.unwrap();
+        output
+    }
+
+    #[test]
+    fn test_write() {
+        assert_eq!(written("(write '(1 \"a\" #\\b c))"), "(1 \"a\" #\\b c)");
+        let shared = "(define x '(#0=(a b) #0# . #0#))";
+        assert_eq!(
+            written(&format!("{} (write x)", shared)),
+            "((a b) (a b) a b)"
+        );
+        assert_eq!(
+            written(&format!("{} (write-shared x)", shared)),
+            "(#0=(a b) #0# . #0#)"
+        );
+        assert_eq!(
+            written(&format!("{} (write-simple x)", shared)),
+            "((a b) (a b) a b)"
+        );
+        assert_eq!(
+            written("(write-shared '#(#0=#(1) #1=(2) #0# #1# (3)))"),
+            "#(#0=#(1) #1=(2) #0# #1# (3))"
+        );
+    }
+
+    #[test]
+    fn test_write_cycles() {
+        let cycle = "(define x '#0=(a . #0#))";
+        assert_eq!(written(&format!("{} (write x)", cycle)), "#0=(a . #0#)");
+        assert_eq!(
+            written(&format!("{} (write-shared x)", cycle)),
+            "#0=(a . #0#)"
+        );
+        assert_eq!(written(&format!("{} (display x)", cycle)), "#0=(a . #0#)");
+        assert_eq!(
+            written("(write '(#0=#(b #0#) . #1=(c #1#)))"),
+            "(#0=#(b #0#) . #1=(c #1#))"
+        );
+        assert_eq!(written("(write (car '#0=(#0# . #0#)))"), "#0=(#0# . #0#)");
+        let mut frame = create_global_frame();
+        assert!(interpret(&format!("{} (write-simple x)", cycle), &mut frame).is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        let datum = "'(\"a \\\"b\\\"\\n\" #\\c |d e| #\\space)";
+        assert_eq!(
+            written(&format!("(write {})", datum)),
+            "(\"a \\\"b\\\"\\n\" #\\c |d e| #\\space)"
+        );
+        assert_eq!(
+            written(&format!("(display {}) (newline)", datum)),
+            "(a \"b\"\n c d e  )\n"
+        );
+        assert_eq!(written("(display \"tab\\there\")"), "tab\there");
+    }
+}
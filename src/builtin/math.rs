@@ -3,7 +3,7 @@ use std::cmp::Ordering;
 use crate::{
     data_model::{BuiltinProcedure, Value},
     error::{invalid_number, validate_number_of_arguments, ApplyError, InvalidArgument},
-    number::Number,
+    number::{Division, Number, Rounding},
 };
 
 fn split_value(args: &[Value]) -> (&Value, &[Value]) {
@@ -15,9 +15,9 @@ pub(crate) const ADD: BuiltinProcedure = BuiltinProcedure {
     function: add,
 };
 
-fn add(args: Vec<Value>) -> Result<Value, ApplyError> {
+fn add(args: &[Value]) -> Result<Value, ApplyError> {
     let mut sum = Number::Integer(0);
-    for arg in &args {
+    for arg in args {
         if let Some(number) = arg.as_number() {
             sum = sum + number;
         } else {
@@ -32,16 +32,16 @@ pub(crate) const SUB: BuiltinProcedure = BuiltinProcedure {
     function: sub,
 };
 
-fn sub(args: Vec<Value>) -> Result<Value, ApplyError> {
+fn sub(args: &[Value]) -> Result<Value, ApplyError> {
     validate_number_of_arguments("#[-]", 1, usize::MAX, args.len())?;
-    let (first, rest) = split_value(&args);
-    let mut difference = *first.as_number().ok_or(invalid_number(&first))?;
-    if rest.len() > 0 {
+    let (first, rest) = split_value(args);
+    let mut difference = first.as_number().ok_or(invalid_number(&first))?.clone();
+    if !rest.is_empty() {
         for arg in rest {
             if let Some(number) = arg.as_number() {
                 difference = difference - number;
             } else {
-                return Err(invalid_number(&arg))?;
+                return Err(invalid_number(&arg).into());
             }
         }
     } else {
@@ -55,9 +55,9 @@ pub(crate) const MUL: BuiltinProcedure = BuiltinProcedure {
     function: mul,
 };
 
-fn mul(args: Vec<Value>) -> Result<Value, ApplyError> {
+fn mul(args: &[Value]) -> Result<Value, ApplyError> {
     let mut product = Number::Integer(1);
-    for arg in &args {
+    for arg in args {
         if let Some(number) = arg.as_number() {
             product = product * number;
         } else {
@@ -72,39 +72,33 @@ pub(crate) const DIV: BuiltinProcedure = BuiltinProcedure {
     function: div,
 };
 
-fn div(args: Vec<Value>) -> Result<Value, ApplyError> {
+fn div(args: &[Value]) -> Result<Value, ApplyError> {
     validate_number_of_arguments("#[/]", 1, usize::MAX, args.len())?;
-    let (first, rest) = split_value(&args);
-    let mut quotient = *first.as_number().ok_or(invalid_number(&first))?;
-    if rest.len() > 0 {
+    let (first, rest) = split_value(args);
+    let mut quotient = first.as_number().ok_or(invalid_number(&first))?.clone();
+    if !rest.is_empty() {
         for arg in rest {
             if let Some(number) = arg.as_number() {
-                if *number == Number::Integer(0)
-                    || *number == Number::Real(0.0)
-                    || *number == Number::Complex(0.0, 0.0)
-                {
-                    return Err(InvalidArgument::ZeroDivisor)?;
+                if number.is_exact() && number.is_zero() {
+                    return Err(InvalidArgument::ZeroDivisor.into());
                 }
                 quotient = quotient / number;
             } else {
-                return Err(invalid_number(&arg))?;
+                return Err(invalid_number(&arg).into());
             }
         }
     } else {
-        if quotient == Number::Integer(0)
-            || quotient == Number::Real(0.0)
-            || quotient == Number::Complex(0.0, 0.0)
-        {
-            return Err(InvalidArgument::ZeroDivisor)?;
+        if quotient.is_exact() && quotient.is_zero() {
+            return Err(InvalidArgument::ZeroDivisor.into());
         }
         quotient = Number::Integer(1) / quotient;
     }
     Ok(quotient.into())
 }
 
-fn equal(args: Vec<Value>) -> Result<Value, ApplyError> {
+fn equal(args: &[Value]) -> Result<Value, ApplyError> {
     validate_number_of_arguments("#[=]", 2, usize::MAX, args.len())?;
-    let (first, rest) = split_value(&args);
+    let (first, rest) = split_value(args);
     let first = first.as_number().ok_or(invalid_number(&first))?;
     for arg in rest {
         let number = arg.as_number().ok_or(invalid_number(&arg))?;
@@ -120,19 +114,39 @@ pub const MATH_EQUAL: BuiltinProcedure = BuiltinProcedure {
     function: equal,
 };
 
-fn less_than(args: Vec<Value>) -> Result<Value, ApplyError> {
-    validate_number_of_arguments("#[<]", 2, usize::MAX, args.len())?;
-    let (first, rest) = split_value(&args);
-    let mut first = first.as_number().ok_or(invalid_number(&first))?;
-    for arg in rest {
-        let number = arg.as_number().ok_or(invalid_number(&arg))?;
-        if let Some(Ordering::Less) = first.partial_cmp(number) {
-            first = number;
-        } else {
-            return Ok(false.into());
-        }
+/// Checks that a value is a real number, for procedures that order numbers.
+fn real_argument(value: &Value) -> Result<&Number, InvalidArgument> {
+    match value.as_number() {
+        Some(number) if number.is_real() => Ok(number),
+        Some(number) => Err(InvalidArgument::InvalidType(
+            number.to_string(),
+            "real".to_string(),
+        )),
+        None => Err(invalid_number(value)),
     }
-    Ok(true.into())
+}
+
+/// Checks that every adjacent pair of arguments is ordered as `expected`
+/// accepts. All arguments are checked to be real first, so a non-real
+/// argument is an error even after the result is known.
+fn compare(
+    name: &str,
+    args: &[Value],
+    expected: fn(Ordering) -> bool,
+) -> Result<Value, ApplyError> {
+    validate_number_of_arguments(name, 2, usize::MAX, args.len())?;
+    let numbers = args
+        .iter()
+        .map(real_argument)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(numbers
+        .windows(2)
+        .all(|pair| pair[0].partial_cmp(pair[1]).is_some_and(expected))
+        .into())
+}
+
+fn less_than(args: &[Value]) -> Result<Value, ApplyError> {
+    compare("#[<]", args, Ordering::is_lt)
 }
 
 pub const LESS_THAN: BuiltinProcedure = BuiltinProcedure {
@@ -140,19 +154,8 @@ pub const LESS_THAN: BuiltinProcedure = BuiltinProcedure {
     function: less_than,
 };
 
-fn less_than_or_equal(args: Vec<Value>) -> Result<Value, ApplyError> {
-    validate_number_of_arguments("#[<=]", 2, usize::MAX, args.len())?;
-    let (first, rest) = split_value(&args);
-    let mut first = first.as_number().ok_or(invalid_number(&first))?;
-    for arg in rest {
-        let number = arg.as_number().ok_or(invalid_number(&arg))?;
-        if let Some(Ordering::Less | Ordering::Equal) = first.partial_cmp(number) {
-            first = number;
-        } else {
-            return Ok(false.into());
-        }
-    }
-    Ok(true.into())
+fn less_than_or_equal(args: &[Value]) -> Result<Value, ApplyError> {
+    compare("#[<=]", args, Ordering::is_le)
 }
 
 pub const LESS_THAN_OR_EQUAL: BuiltinProcedure = BuiltinProcedure {
@@ -160,19 +163,8 @@ pub const LESS_THAN_OR_EQUAL: BuiltinProcedure = BuiltinProcedure {
     function: less_than_or_equal,
 };
 
-fn greater_than(args: Vec<Value>) -> Result<Value, ApplyError> {
-    validate_number_of_arguments("#[>]", 2, usize::MAX, args.len())?;
-    let (first, rest) = split_value(&args);
-    let mut first = first.as_number().ok_or(invalid_number(&first))?;
-    for arg in rest {
-        let number = arg.as_number().ok_or(invalid_number(&arg))?;
-        if let Some(Ordering::Greater) = first.partial_cmp(number) {
-            first = number;
-        } else {
-            return Ok(false.into());
-        }
-    }
-    Ok(true.into())
+fn greater_than(args: &[Value]) -> Result<Value, ApplyError> {
+    compare("#[>]", args, Ordering::is_gt)
 }
 
 pub const GREATER_THAN: BuiltinProcedure = BuiltinProcedure {
@@ -180,22 +172,490 @@ pub const GREATER_THAN: BuiltinProcedure = BuiltinProcedure {
     function: greater_than,
 };
 
-fn greater_than_or_equal(args: Vec<Value>) -> Result<Value, ApplyError> {
-    validate_number_of_arguments("#[>=]", 2, usize::MAX, args.len())?;
-    let (first, rest) = split_value(&args);
-    let mut first = first.as_number().ok_or(invalid_number(&first))?;
-    for arg in rest {
-        let number = arg.as_number().ok_or(invalid_number(&arg))?;
-        if let Some(Ordering::Greater | Ordering::Equal) = first.partial_cmp(number) {
-            first = number;
-        } else {
-            return Ok(false.into());
-        }
-    }
-    Ok(true.into())
+fn greater_than_or_equal(args: &[Value]) -> Result<Value, ApplyError> {
+    compare("#[>=]", args, Ordering::is_ge)
 }
 
 pub const GREATER_THAN_OR_EQUAL: BuiltinProcedure = BuiltinProcedure {
     name: ">=",
     function: greater_than_or_equal,
 };
+
+fn numerator(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[numerator]", 1, 1, args.len())?;
+    let number = args[0].as_number().ok_or(invalid_number(&args[0]))?;
+    let numerator = number.numerator().ok_or(InvalidArgument::InvalidType(
+        number.to_string(),
+        "rational".to_string(),
+    ))?;
+    Ok(numerator.into())
+}
+
+pub const NUMERATOR: BuiltinProcedure = BuiltinProcedure {
+    name: "numerator",
+    function: numerator,
+};
+
+fn denominator(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[denominator]", 1, 1, args.len())?;
+    let number = args[0].as_number().ok_or(invalid_number(&args[0]))?;
+    let denominator = number.denominator().ok_or(InvalidArgument::InvalidType(
+        number.to_string(),
+        "rational".to_string(),
+    ))?;
+    Ok(denominator.into())
+}
+
+pub const DENOMINATOR: BuiltinProcedure = BuiltinProcedure {
+    name: "denominator",
+    function: denominator,
+};
+
+fn is_exact(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[exact?]", 1, 1, args.len())?;
+    let number = args[0].as_number().ok_or(invalid_number(&args[0]))?;
+    Ok(number.is_exact().into())
+}
+
+pub const IS_EXACT: BuiltinProcedure = BuiltinProcedure {
+    name: "exact?",
+    function: is_exact,
+};
+
+fn is_inexact(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[inexact?]", 1, 1, args.len())?;
+    let number = args[0].as_number().ok_or(invalid_number(&args[0]))?;
+    Ok((!number.is_exact()).into())
+}
+
+pub const IS_INEXACT: BuiltinProcedure = BuiltinProcedure {
+    name: "inexact?",
+    function: is_inexact,
+};
+
+fn exact(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[exact]", 1, 1, args.len())?;
+    let number = args[0].as_number().ok_or(invalid_number(&args[0]))?;
+    Ok(number.to_exact()?.into())
+}
+
+pub const EXACT: BuiltinProcedure = BuiltinProcedure {
+    name: "exact",
+    function: exact,
+};
+
+pub const INEXACT_TO_EXACT: BuiltinProcedure = BuiltinProcedure {
+    name: "inexact->exact",
+    function: exact,
+};
+
+fn inexact(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[inexact]", 1, 1, args.len())?;
+    let number = args[0].as_number().ok_or(invalid_number(&args[0]))?;
+    Ok(number.to_inexact().into())
+}
+
+pub const INEXACT: BuiltinProcedure = BuiltinProcedure {
+    name: "inexact",
+    function: inexact,
+};
+
+pub const EXACT_TO_INEXACT: BuiltinProcedure = BuiltinProcedure {
+    name: "exact->inexact",
+    function: inexact,
+};
+
+/// Defines a one-argument built-in whose result is `$body`, evaluated with
+/// the argument bound to `$number`.
+macro_rules! unary {
+    ($constant:ident, $name:literal, $function:ident, |$number:ident| $body:expr) => {
+        fn $function(args: &[Value]) -> Result<Value, ApplyError> {
+            validate_number_of_arguments(concat!("#[", $name, "]"), 1, 1, args.len())?;
+            let $number = args[0].as_number().ok_or(invalid_number(&args[0]))?;
+            Ok(Value::from($body))
+        }
+
+        pub const $constant: BuiltinProcedure = BuiltinProcedure {
+            name: $name,
+            function: $function,
+        };
+    };
+}
+
+fn is_number(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[number?]", 1, 1, args.len())?;
+    Ok(args[0].as_number().is_some().into())
+}
+
+pub const IS_NUMBER: BuiltinProcedure = BuiltinProcedure {
+    name: "number?",
+    function: is_number,
+};
+
+pub const IS_COMPLEX: BuiltinProcedure = BuiltinProcedure {
+    name: "complex?",
+    function: is_number,
+};
+
+fn is_real(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[real?]", 1, 1, args.len())?;
+    Ok(args[0].as_number().is_some_and(Number::is_real).into())
+}
+
+pub const IS_REAL: BuiltinProcedure = BuiltinProcedure {
+    name: "real?",
+    function: is_real,
+};
+
+fn is_rational(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[rational?]", 1, 1, args.len())?;
+    Ok(args[0].as_number().is_some_and(Number::is_rational).into())
+}
+
+pub const IS_RATIONAL: BuiltinProcedure = BuiltinProcedure {
+    name: "rational?",
+    function: is_rational,
+};
+
+fn is_integer(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[integer?]", 1, 1, args.len())?;
+    Ok(args[0].as_number().is_some_and(Number::is_integer).into())
+}
+
+pub const IS_INTEGER: BuiltinProcedure = BuiltinProcedure {
+    name: "integer?",
+    function: is_integer,
+};
+
+fn is_exact_integer(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[exact-integer?]", 1, 1, args.len())?;
+    Ok(args[0]
+        .as_number()
+        .is_some_and(|number| number.is_exact() && number.is_integer())
+        .into())
+}
+
+pub const IS_EXACT_INTEGER: BuiltinProcedure = BuiltinProcedure {
+    name: "exact-integer?",
+    function: is_exact_integer,
+};
+
+unary!(IS_NAN, "nan?", is_nan, |number| number.is_nan());
+unary!(IS_INFINITE, "infinite?", is_infinite, |number| number
+    .is_infinite());
+unary!(IS_FINITE, "finite?", is_finite, |number| !number.is_nan()
+    && !number.is_infinite());
+unary!(IS_ZERO, "zero?", is_zero, |number| number.is_zero());
+unary!(IS_POSITIVE, "positive?", is_positive, |number| number
+    .signum()?
+    == Some(Ordering::Greater));
+unary!(IS_NEGATIVE, "negative?", is_negative, |number| number
+    .signum()?
+    == Some(Ordering::Less));
+unary!(IS_ODD, "odd?", is_odd, |number| !number.is_even()?);
+unary!(IS_EVEN, "even?", is_even, |number| number.is_even()?);
+
+unary!(ABS, "abs", abs, |number| number.abs()?);
+unary!(FLOOR, "floor", floor, |number| number
+    .round(Rounding::Floor)?);
+unary!(CEILING, "ceiling", ceiling, |number| number
+    .round(Rounding::Ceiling)?);
+unary!(TRUNCATE, "truncate", truncate, |number| number
+    .round(Rounding::Truncate)?);
+unary!(ROUND, "round", round, |number| number
+    .round(Rounding::Round)?);
+unary!(EXP, "exp", exp, |number| number.exp());
+unary!(SIN, "sin", sin, |number| number.sin());
+unary!(COS, "cos", cos, |number| number.cos());
+unary!(TAN, "tan", tan, |number| number.tan());
+unary!(ASIN, "asin", asin, |number| number.asin());
+unary!(ACOS, "acos", acos, |number| number.acos());
+unary!(SQRT, "sqrt", sqrt, |number| number.sqrt());
+unary!(SQUARE, "square", square, |number| number * number);
+
+unary!(REAL_PART, "real-part", real_part, |number| number
+    .real_part());
+unary!(IMAG_PART, "imag-part", imag_part, |number| number
+    .imag_part());
+unary!(MAGNITUDE, "magnitude", magnitude, |number| number
+    .magnitude());
+unary!(ANGLE, "angle", angle, |number| number.angle());
+
+fn make_rectangular(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[make-rectangular]", 2, 2, args.len())?;
+    let real = args[0].as_number().ok_or(invalid_number(&args[0]))?;
+    let im = args[1].as_number().ok_or(invalid_number(&args[1]))?;
+    Ok(Number::make_rectangular(real, im)?.into())
+}
+
+pub const MAKE_RECTANGULAR: BuiltinProcedure = BuiltinProcedure {
+    name: "make-rectangular",
+    function: make_rectangular,
+};
+
+fn make_polar(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[make-polar]", 2, 2, args.len())?;
+    let magnitude = args[0].as_number().ok_or(invalid_number(&args[0]))?;
+    let angle = args[1].as_number().ok_or(invalid_number(&args[1]))?;
+    Ok(Number::make_polar(magnitude, angle)?.into())
+}
+
+pub const MAKE_POLAR: BuiltinProcedure = BuiltinProcedure {
+    name: "make-polar",
+    function: make_polar,
+};
+
+fn integer_divide(
+    name: &str,
+    args: &[Value],
+    division: Division,
+) -> Result<(Number, Number), ApplyError> {
+    validate_number_of_arguments(name, 2, 2, args.len())?;
+    let dividend = args[0].as_number().ok_or(invalid_number(&args[0]))?;
+    let divisor = args[1].as_number().ok_or(invalid_number(&args[1]))?;
+    Ok(dividend.integer_divide(divisor, division)?)
+}
+
+fn floor_divide(args: &[Value]) -> Result<Value, ApplyError> {
+    let (quotient, remainder) = integer_divide("#[floor/]", args, Division::Floor)?;
+    Ok(Value::Values(vec![quotient.into(), remainder.into()]))
+}
+
+pub const FLOOR_DIVIDE: BuiltinProcedure = BuiltinProcedure {
+    name: "floor/",
+    function: floor_divide,
+};
+
+fn floor_quotient(args: &[Value]) -> Result<Value, ApplyError> {
+    Ok(integer_divide("#[floor-quotient]", args, Division::Floor)?
+        .0
+        .into())
+}
+
+pub const FLOOR_QUOTIENT: BuiltinProcedure = BuiltinProcedure {
+    name: "floor-quotient",
+    function: floor_quotient,
+};
+
+fn floor_remainder(args: &[Value]) -> Result<Value, ApplyError> {
+    Ok(integer_divide("#[floor-remainder]", args, Division::Floor)?
+        .1
+        .into())
+}
+
+pub const FLOOR_REMAINDER: BuiltinProcedure = BuiltinProcedure {
+    name: "floor-remainder",
+    function: floor_remainder,
+};
+
+pub const MODULO: BuiltinProcedure = BuiltinProcedure {
+    name: "modulo",
+    function: floor_remainder,
+};
+
+fn truncate_divide(args: &[Value]) -> Result<Value, ApplyError> {
+    let (quotient, remainder) = integer_divide("#[truncate/]", args, Division::Truncate)?;
+    Ok(Value::Values(vec![quotient.into(), remainder.into()]))
+}
+
+pub const TRUNCATE_DIVIDE: BuiltinProcedure = BuiltinProcedure {
+    name: "truncate/",
+    function: truncate_divide,
+};
+
+fn truncate_quotient(args: &[Value]) -> Result<Value, ApplyError> {
+    Ok(
+        integer_divide("#[truncate-quotient]", args, Division::Truncate)?
+            .0
+            .into(),
+    )
+}
+
+pub const TRUNCATE_QUOTIENT: BuiltinProcedure = BuiltinProcedure {
+    name: "truncate-quotient",
+    function: truncate_quotient,
+};
+
+pub const QUOTIENT: BuiltinProcedure = BuiltinProcedure {
+    name: "quotient",
+    function: truncate_quotient,
+};
+
+fn truncate_remainder(args: &[Value]) -> Result<Value, ApplyError> {
+    Ok(
+        integer_divide("#[truncate-remainder]", args, Division::Truncate)?
+            .1
+            .into(),
+    )
+}
+
+pub const TRUNCATE_REMAINDER: BuiltinProcedure = BuiltinProcedure {
+    name: "truncate-remainder",
+    function: truncate_remainder,
+};
+
+pub const REMAINDER: BuiltinProcedure = BuiltinProcedure {
+    name: "remainder",
+    function: truncate_remainder,
+};
+
+fn gcd(args: &[Value]) -> Result<Value, ApplyError> {
+    let mut result = Number::Integer(0);
+    for arg in args {
+        result = result.gcd(arg.as_number().ok_or(invalid_number(arg))?)?;
+    }
+    Ok(result.into())
+}
+
+pub const GCD: BuiltinProcedure = BuiltinProcedure {
+    name: "gcd",
+    function: gcd,
+};
+
+fn lcm(args: &[Value]) -> Result<Value, ApplyError> {
+    let mut result = Number::Integer(1);
+    for arg in args {
+        result = result.lcm(arg.as_number().ok_or(invalid_number(arg))?)?;
+    }
+    Ok(result.into())
+}
+
+pub const LCM: BuiltinProcedure = BuiltinProcedure {
+    name: "lcm",
+    function: lcm,
+};
+
+/// Picks the argument for which `pick(candidate, best)` holds; the result is
+/// inexact if any argument is.
+fn extremum(name: &str, args: &[Value], pick: Ordering) -> Result<Value, ApplyError> {
+    validate_number_of_arguments(name, 1, usize::MAX, args.len())?;
+    let mut best: Option<&Number> = None;
+    let mut exact = true;
+    for arg in args {
+        let number = real_argument(arg)?;
+        exact &= number.is_exact();
+        best = match best {
+            Some(best) if best.is_nan() => Some(best),
+            Some(best) if number.partial_cmp(best) != Some(pick) && !number.is_nan() => Some(best),
+            _ => Some(number),
+        };
+    }
+    let best = best.unwrap().clone();
+    Ok(if exact { best } else { best.to_inexact() }.into())
+}
+
+fn max(args: &[Value]) -> Result<Value, ApplyError> {
+    extremum("#[max]", args, Ordering::Greater)
+}
+
+pub const MAX: BuiltinProcedure = BuiltinProcedure {
+    name: "max",
+    function: max,
+};
+
+fn min(args: &[Value]) -> Result<Value, ApplyError> {
+    extremum("#[min]", args, Ordering::Less)
+}
+
+pub const MIN: BuiltinProcedure = BuiltinProcedure {
+    name: "min",
+    function: min,
+};
+
+fn log(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[log]", 1, 2, args.len())?;
+    let number = args[0].as_number().ok_or(invalid_number(&args[0]))?;
+    let result = number.ln();
+    match args.get(1) {
+        Some(base) => {
+            let base = base.as_number().ok_or(invalid_number(base))?;
+            Ok((result / base.ln()).into())
+        }
+        None => Ok(result.into()),
+    }
+}
+
+pub const LOG: BuiltinProcedure = BuiltinProcedure {
+    name: "log",
+    function: log,
+};
+
+fn atan(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[atan]", 1, 2, args.len())?;
+    let number = args[0].as_number().ok_or(invalid_number(&args[0]))?;
+    match args.get(1) {
+        Some(x) => Ok(number
+            .atan2(x.as_number().ok_or(invalid_number(x))?)?
+            .into()),
+        None => Ok(number.atan().into()),
+    }
+}
+
+pub const ATAN: BuiltinProcedure = BuiltinProcedure {
+    name: "atan",
+    function: atan,
+};
+
+fn exact_integer_sqrt(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[exact-integer-sqrt]", 1, 1, args.len())?;
+    let number = args[0].as_number().ok_or(invalid_number(&args[0]))?;
+    let (root, remainder) = number.exact_integer_sqrt()?;
+    Ok(Value::Values(vec![root.into(), remainder.into()]))
+}
+
+pub const EXACT_INTEGER_SQRT: BuiltinProcedure = BuiltinProcedure {
+    name: "exact-integer-sqrt",
+    function: exact_integer_sqrt,
+};
+
+fn expt(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[expt]", 2, 2, args.len())?;
+    let base = args[0].as_number().ok_or(invalid_number(&args[0]))?;
+    let power = args[1].as_number().ok_or(invalid_number(&args[1]))?;
+    Ok(base.expt(power)?.into())
+}
+
+pub const EXPT: BuiltinProcedure = BuiltinProcedure {
+    name: "expt",
+    function: expt,
+};
+
+fn radix_argument(value: Option<&Value>) -> Result<u32, InvalidArgument> {
+    let Some(value) = value else {
+        return Ok(10);
+    };
+    let radix = value.as_number().ok_or(invalid_number(value))?.to_index()?;
+    match radix {
+        2 | 8 | 10 | 16 => Ok(radix as u32),
+        _ => Err(InvalidArgument::OutOfRange(radix.to_string())),
+    }
+}
+
+fn number_to_string(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[number->string]", 1, 2, args.len())?;
+    let number = args[0].as_number().ok_or(invalid_number(&args[0]))?;
+    let radix = radix_argument(args.get(1))?;
+    Ok(number.to_string_radix(radix)?.into())
+}
+
+pub const NUMBER_TO_STRING: BuiltinProcedure = BuiltinProcedure {
+    name: "number->string",
+    function: number_to_string,
+};
+
+fn string_to_number(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[string->number]", 1, 2, args.len())?;
+    let string = args[0]
+        .as_string()
+        .ok_or_else(|| InvalidArgument::InvalidType(args[0].to_string(), "string".to_string()))?;
+    let radix = radix_argument(args.get(1))?;
+    match Number::parse(string, radix) {
+        Ok(number) => Ok(number.into()),
+        Err(_) => Ok(false.into()),
+    }
+}
+
+pub const STRING_TO_NUMBER: BuiltinProcedure = BuiltinProcedure {
+    name: "string->number",
+    function: string_to_number,
+};
@@ -1,10 +1,15 @@
+pub(crate) mod bitwise;
+pub(crate) mod bytevector;
+pub(crate) mod char;
 pub(crate) mod control;
 #[cfg(target_arch = "wasm32")]
 pub(crate) mod graphic;
 pub(crate) mod io;
 pub(crate) mod math;
+pub(crate) mod numeric_vector;
 pub(crate) mod pair;
 pub(crate) mod predicate;
+pub(crate) mod random;
 pub(crate) mod string;
 pub(crate) mod symbol;
 pub(crate) mod vector;
@@ -0,0 +1,320 @@
+use std::cell::RefCell;
+
+use crate::{
+    data_model::{BuiltinProcedure, ExpressionContent, Link, Value},
+    error::{invalid_number, validate_number_of_arguments, ApplyError, EvalError, InvalidArgument},
+    limits,
+    number::Number,
+    numeric_vector::{NumericKind, NumericVector},
+};
+
+pub(crate) fn vector_argument(
+    value: &Value,
+    kind: NumericKind,
+) -> Result<&RefCell<NumericVector>, InvalidArgument> {
+    value
+        .as_numeric_vector()
+        .filter(|vector| vector.borrow().kind() == kind)
+        .ok_or_else(|| {
+            InvalidArgument::InvalidType(value.to_string(), format!("{}vector", kind.tag()))
+        })
+}
+
+fn index_argument(value: &Value) -> Result<usize, InvalidArgument> {
+    value.as_number().ok_or(invalid_number(value))?.to_index()
+}
+
+/// Reads the optional `start` and `end` arguments that bound a range of a
+/// sequence of `length` elements.
+pub(crate) fn range_arguments(
+    args: &[Value],
+    length: usize,
+) -> Result<(usize, usize), InvalidArgument> {
+    let start = args.first().map(index_argument).transpose()?.unwrap_or(0);
+    let end = args
+        .get(1)
+        .map(index_argument)
+        .transpose()?
+        .unwrap_or(length);
+    if end > length {
+        return Err(InvalidArgument::OutOfRange(end.to_string()));
+    }
+    if start > end {
+        return Err(InvalidArgument::OutOfRange(start.to_string()));
+    }
+    Ok((start, end))
+}
+
+/// Checks that `count` elements of `size` bytes each fit in the heap budget,
+/// before they are allocated.
+pub(crate) fn reserve(count: usize, size: usize) -> Result<(), EvalError> {
+    Ok(limits::reserve(count.saturating_mul(size))?)
+}
+
+pub(crate) fn make(name: &str, kind: NumericKind, args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments(name, 1, 2, args.len())?;
+    let length = index_argument(&args[0])?;
+    let fill = match args.get(1) {
+        Some(fill) => fill.as_number().ok_or(invalid_number(fill))?,
+        None => &Number::Integer(0),
+    };
+    reserve(length, kind.element_size())?;
+    Ok(NumericVector::new(kind, length, fill)?.into())
+}
+
+pub(crate) fn construct(_: &str, kind: NumericKind, args: &[Value]) -> Result<Value, ApplyError> {
+    reserve(args.len(), kind.element_size())?;
+    let mut numbers = vec![];
+    for arg in args {
+        numbers.push(arg.as_number().ok_or(invalid_number(arg))?);
+    }
+    Ok(NumericVector::from_numbers(kind, numbers)?.into())
+}
+
+pub(crate) fn is_vector(
+    name: &str,
+    kind: NumericKind,
+    args: &[Value],
+) -> Result<Value, ApplyError> {
+    validate_number_of_arguments(name, 1, 1, args.len())?;
+    Ok(vector_argument(&args[0], kind).is_ok().into())
+}
+
+fn is_element(name: &str, kind: NumericKind, args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments(name, 1, 1, args.len())?;
+    Ok(args[0]
+        .as_number()
+        .is_some_and(|number| NumericVector::new(kind, 0, number).is_ok())
+        .into())
+}
+
+fn is_empty(name: &str, kind: NumericKind, args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments(name, 1, 1, args.len())?;
+    Ok(vector_argument(&args[0], kind)?.borrow().is_empty().into())
+}
+
+pub(crate) fn length(name: &str, kind: NumericKind, args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments(name, 1, 1, args.len())?;
+    let length = vector_argument(&args[0], kind)?.borrow().len();
+    Ok(Number::from(length as i64).into())
+}
+
+pub(crate) fn get(name: &str, kind: NumericKind, args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments(name, 2, 2, args.len())?;
+    let vector = vector_argument(&args[0], kind)?.borrow();
+    let index = index_argument(&args[1])?;
+    let number = vector
+        .get(index)
+        .ok_or_else(|| InvalidArgument::OutOfRange(index.to_string()))?;
+    Ok(number.into())
+}
+
+pub(crate) fn set(name: &str, kind: NumericKind, args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments(name, 3, 3, args.len())?;
+    let vector = vector_argument(&args[0], kind)?;
+    let index = index_argument(&args[1])?;
+    let number = args[2].as_number().ok_or(invalid_number(&args[2]))?;
+    vector.borrow_mut().set(index, number)?;
+    Ok(Value::Void)
+}
+
+fn to_list(name: &str, kind: NumericKind, args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments(name, 1, 3, args.len())?;
+    let vector = vector_argument(&args[0], kind)?.borrow();
+    let (start, end) = range_arguments(&args[1..], vector.len())?;
+    reserve(end - start, 2 * std::mem::size_of::<ExpressionContent>())?;
+    let list = vector
+        .numbers(start, end)
+        .into_iter()
+        .rev()
+        .fold(Link::Nil, |cdr, number| Link::new_pair(number.into(), cdr));
+    Ok(list.into())
+}
+
+fn from_list(name: &str, kind: NumericKind, args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments(name, 1, 1, args.len())?;
+    let list = match &args[0] {
+        Value::Expression(expression)
+            if expression.is_pair() || matches!(expression.as_link(), Link::Nil) =>
+        {
+            expression.as_link()
+        }
+        value => Err(InvalidArgument::InvalidType(
+            value.to_string(),
+            "list".to_string(),
+        ))?,
+    };
+    reserve(list.len(), kind.element_size())?;
+    let mut numbers = vec![];
+    for link in list.iter() {
+        numbers.push(link.as_number().ok_or(invalid_number(link))?);
+    }
+    Ok(NumericVector::from_numbers(kind, numbers)?.into())
+}
+
+fn to_vector(name: &str, kind: NumericKind, args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments(name, 1, 3, args.len())?;
+    let vector = vector_argument(&args[0], kind)?.borrow();
+    let (start, end) = range_arguments(&args[1..], vector.len())?;
+    reserve(
+        end - start,
+        std::mem::size_of::<Link>() + std::mem::size_of::<ExpressionContent>(),
+    )?;
+    let links: Vec<Link> = vector
+        .numbers(start, end)
+        .into_iter()
+        .map(Link::from)
+        .collect();
+    Ok(links.into())
+}
+
+fn from_vector(name: &str, kind: NumericKind, args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments(name, 1, 3, args.len())?;
+    let vector = args[0]
+        .as_vector()
+        .ok_or_else(|| InvalidArgument::InvalidType(args[0].to_string(), "vector".to_string()))?;
+    let (start, end) = range_arguments(&args[1..], vector.len())?;
+    reserve(end - start, kind.element_size())?;
+    let mut numbers = vec![];
+    for link in &vector[start..end] {
+        numbers.push(link.as_number().ok_or(invalid_number(link))?);
+    }
+    Ok(NumericVector::from_numbers(kind, numbers)?.into())
+}
+
+pub(crate) fn copy(name: &str, kind: NumericKind, args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments(name, 1, 3, args.len())?;
+    let vector = vector_argument(&args[0], kind)?.borrow();
+    let (start, end) = range_arguments(&args[1..], vector.len())?;
+    reserve(end - start, kind.element_size())?;
+    Ok(vector.copy(start, end).into())
+}
+
+pub(crate) fn copy_into(
+    name: &str,
+    kind: NumericKind,
+    args: &[Value],
+) -> Result<Value, ApplyError> {
+    validate_number_of_arguments(name, 3, 5, args.len())?;
+    let target = vector_argument(&args[0], kind)?;
+    let at = index_argument(&args[1])?;
+    // Copy the source range out first, since it may be the target itself.
+    let source = {
+        let source = vector_argument(&args[2], kind)?.borrow();
+        let (start, end) = range_arguments(&args[3..], source.len())?;
+        reserve(end - start, kind.element_size())?;
+        source.copy(start, end)
+    };
+    let mut target = target.borrow_mut();
+    if at > target.len() || source.len() > target.len() - at {
+        return Err(InvalidArgument::OutOfRange(at.to_string()).into());
+    }
+    target.copy_from(at, &source);
+    Ok(Value::Void)
+}
+
+pub(crate) fn append(_: &str, kind: NumericKind, args: &[Value]) -> Result<Value, ApplyError> {
+    let mut vectors = vec![];
+    for arg in args {
+        vectors.push(vector_argument(arg, kind)?.borrow());
+    }
+    let length = vectors
+        .iter()
+        .fold(0usize, |length, vector| length.saturating_add(vector.len()));
+    reserve(length, kind.element_size())?;
+    let mut result = NumericVector::new(kind, 0, &Number::Integer(0))?;
+    for vector in vectors {
+        result.extend(&vector);
+    }
+    Ok(result.into())
+}
+
+fn fill(name: &str, kind: NumericKind, args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments(name, 2, 4, args.len())?;
+    let mut vector = vector_argument(&args[0], kind)?.borrow_mut();
+    let number = args[1].as_number().ok_or(invalid_number(&args[1]))?;
+    let (start, end) = range_arguments(&args[2..], vector.len())?;
+    vector.fill(number, start, end)?;
+    Ok(Value::Void)
+}
+
+fn equal(_: &str, kind: NumericKind, args: &[Value]) -> Result<Value, ApplyError> {
+    let mut vectors = vec![];
+    for arg in args {
+        vectors.push(vector_argument(arg, kind)?.borrow());
+    }
+    Ok(vectors.windows(2).all(|pair| *pair[0] == *pair[1]).into())
+}
+
+/// Defines a module holding the SRFI 160 procedures of one element kind,
+/// each forwarding to the generic implementation above with its own name.
+macro_rules! numeric_vector_procedures {
+    ($module:ident, $kind:ident, $tag:literal) => {
+        mod $module {
+            use super::{ApplyError, BuiltinProcedure, NumericKind, Value};
+
+            numeric_vector_procedures!(@procedures $kind, $tag, [
+                "make-" "vector" => make,
+                "" "vector" => construct,
+                "" "vector?" => is_vector,
+                "" "?" => is_element,
+                "" "vector-empty?" => is_empty,
+                "" "vector-length" => length,
+                "" "vector-ref" => get,
+                "" "vector-set!" => set,
+                "" "vector->list" => to_list,
+                "list->" "vector" => from_list,
+                "" "vector->vector" => to_vector,
+                "vector->" "vector" => from_vector,
+                "" "vector-copy" => copy,
+                "" "vector-copy!" => copy_into,
+                "" "vector-append" => append,
+                "" "vector-fill!" => fill,
+                "" "vector=" => equal,
+            ]);
+        }
+    };
+    (@procedures $kind:ident, $tag:literal, [$($prefix:literal $suffix:literal => $function:ident,)*]) => {
+        $(
+            fn $function(args: &[Value]) -> Result<Value, ApplyError> {
+                super::$function(
+                    concat!("#[", $prefix, $tag, $suffix, "]"),
+                    NumericKind::$kind,
+                    args,
+                )
+            }
+        )*
+
+        pub(crate) const PROCEDURES: &[BuiltinProcedure] = &[$(
+            BuiltinProcedure {
+                name: concat!($prefix, $tag, $suffix),
+                function: $function,
+            },
+        )*];
+    };
+}
+
+numeric_vector_procedures!(u8vector, U8, "u8");
+numeric_vector_procedures!(s8vector, S8, "s8");
+numeric_vector_procedures!(u16vector, U16, "u16");
+numeric_vector_procedures!(s16vector, S16, "s16");
+numeric_vector_procedures!(u32vector, U32, "u32");
+numeric_vector_procedures!(s32vector, S32, "s32");
+numeric_vector_procedures!(u64vector, U64, "u64");
+numeric_vector_procedures!(s64vector, S64, "s64");
+numeric_vector_procedures!(f32vector, F32, "f32");
+numeric_vector_procedures!(f64vector, F64, "f64");
+
+/// The procedures of every element kind, to be registered together.
+pub(crate) const NUMERIC_VECTOR_PROCEDURES: [&[BuiltinProcedure]; 10] = [
+    u8vector::PROCEDURES,
+    s8vector::PROCEDURES,
+    u16vector::PROCEDURES,
+    s16vector::PROCEDURES,
+    u32vector::PROCEDURES,
+    s32vector::PROCEDURES,
+    u64vector::PROCEDURES,
+    s64vector::PROCEDURES,
+    f32vector::PROCEDURES,
+    f64vector::PROCEDURES,
+];
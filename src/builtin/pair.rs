@@ -8,7 +8,7 @@ pub(crate) const IS_PAIR: BuiltinProcedure = BuiltinProcedure {
     function: is_pair,
 };
 
-fn is_pair(args: Vec<Value>) -> Result<Value, ApplyError> {
+fn is_pair(args: &[Value]) -> Result<Value, ApplyError> {
     validate_number_of_arguments("#[pair?]", 1, 1, args.len())?;
     Ok(args[0].as_pair().is_some().into())
 }
@@ -18,7 +18,7 @@ pub(crate) const CAR: BuiltinProcedure = BuiltinProcedure {
     function: car,
 };
 
-fn car(args: Vec<Value>) -> Result<Value, ApplyError> {
+fn car(args: &[Value]) -> Result<Value, ApplyError> {
     validate_number_of_arguments("#[car]", 1, 1, args.len())?;
     args[0]
         .as_pair()
@@ -0,0 +1,165 @@
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive};
+
+use crate::{
+    data_model::{BuiltinProcedure, Link, RandomProcedure, Value},
+    error::{invalid_number, validate_number_of_arguments, ApplyError, InvalidArgument},
+    number::Number,
+    random::RandomSource,
+};
+
+fn source_argument(value: &Value) -> Result<&RandomSource, InvalidArgument> {
+    value
+        .as_random_source()
+        .ok_or_else(|| InvalidArgument::InvalidType(value.to_string(), "random source".to_string()))
+}
+
+pub fn random_integer(args: &[Value], source: &RandomSource) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[random-integer]", 1, 1, args.len())?;
+    let number = args[0].as_number().ok_or(invalid_number(&args[0]))?;
+    let bound = number.exact_integer()?;
+    if !bound.is_positive() {
+        return Err(InvalidArgument::OutOfRange(number.to_string()).into());
+    }
+    Ok(Number::from(source.integer(&bound)).into())
+}
+
+pub fn random_real(args: &[Value], source: &RandomSource) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[random-real]", 0, 0, args.len())?;
+    Ok(Number::Real(source.real()).into())
+}
+
+pub(crate) const MAKE_RANDOM_SOURCE: BuiltinProcedure = BuiltinProcedure {
+    name: "make-random-source",
+    function: make_random_source,
+};
+
+fn make_random_source(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[make-random-source]", 0, 0, args.len())?;
+    Ok(RandomSource::new().into())
+}
+
+pub(crate) const IS_RANDOM_SOURCE: BuiltinProcedure = BuiltinProcedure {
+    name: "random-source?",
+    function: is_random_source,
+};
+
+fn is_random_source(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[random-source?]", 1, 1, args.len())?;
+    Ok(args[0].as_random_source().is_some().into())
+}
+
+pub(crate) const RANDOM_SOURCE_STATE_REF: BuiltinProcedure = BuiltinProcedure {
+    name: "random-source-state-ref",
+    function: random_source_state_ref,
+};
+
+/// The state is a vector of the four generator words as exact integers.
+fn random_source_state_ref(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[random-source-state-ref]", 1, 1, args.len())?;
+    let source = source_argument(&args[0])?;
+    Ok(source
+        .get_state()
+        .into_iter()
+        .map(|word| Link::from(Number::from(BigInt::from(word))))
+        .collect::<Vec<_>>()
+        .into())
+}
+
+pub(crate) const RANDOM_SOURCE_STATE_SET: BuiltinProcedure = BuiltinProcedure {
+    name: "random-source-state-set!",
+    function: random_source_state_set,
+};
+
+fn random_source_state_set(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[random-source-state-set!]", 2, 2, args.len())?;
+    let source = source_argument(&args[0])?;
+    let invalid_state =
+        || InvalidArgument::InvalidType(args[1].to_string(), "random state".to_string());
+    let vector = args[1].as_vector().ok_or_else(invalid_state)?;
+    let mut state = [0; 4];
+    if vector.len() != state.len() {
+        return Err(invalid_state().into());
+    }
+    for (word, link) in state.iter_mut().zip(vector) {
+        *word = link
+            .as_number()
+            .and_then(|number| number.exact_integer().ok()?.to_u64())
+            .ok_or_else(invalid_state)?;
+    }
+    source.set_state(state).map_err(|_| invalid_state())?;
+    Ok(Value::Void)
+}
+
+pub(crate) const RANDOM_SOURCE_RANDOMIZE: BuiltinProcedure = BuiltinProcedure {
+    name: "random-source-randomize!",
+    function: random_source_randomize,
+};
+
+fn random_source_randomize(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[random-source-randomize!]", 1, 1, args.len())?;
+    source_argument(&args[0])?.randomize();
+    Ok(Value::Void)
+}
+
+pub(crate) const RANDOM_SOURCE_PSEUDO_RANDOMIZE: BuiltinProcedure = BuiltinProcedure {
+    name: "random-source-pseudo-randomize!",
+    function: random_source_pseudo_randomize,
+};
+
+fn random_source_pseudo_randomize(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[random-source-pseudo-randomize!]", 3, 3, args.len())?;
+    let source = source_argument(&args[0])?;
+    let mut seeds = vec![];
+    for arg in &args[1..] {
+        let number = arg.as_number().ok_or(invalid_number(arg))?;
+        let seed = number.exact_integer()?;
+        if seed.is_negative() {
+            return Err(InvalidArgument::OutOfRange(number.to_string()).into());
+        }
+        seeds.push(seed);
+    }
+    source.pseudo_randomize(&seeds[0], &seeds[1]);
+    Ok(Value::Void)
+}
+
+pub(crate) const RANDOM_SOURCE_MAKE_INTEGERS: BuiltinProcedure = BuiltinProcedure {
+    name: "random-source-make-integers",
+    function: random_source_make_integers,
+};
+
+fn random_source_make_integers(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[random-source-make-integers]", 1, 1, args.len())?;
+    Ok(RandomProcedure {
+        name: "random-integer",
+        function: random_integer,
+        source: source_argument(&args[0])?.clone(),
+    }
+    .into())
+}
+
+pub(crate) const RANDOM_SOURCE_MAKE_REALS: BuiltinProcedure = BuiltinProcedure {
+    name: "random-source-make-reals",
+    function: random_source_make_reals,
+};
+
+/// The optional unit only bounds the spacing of the results, and `random-real`
+/// already uses the full precision of a double, so it is checked and ignored.
+fn random_source_make_reals(args: &[Value]) -> Result<Value, ApplyError> {
+    validate_number_of_arguments("#[random-source-make-reals]", 1, 2, args.len())?;
+    let source = source_argument(&args[0])?;
+    if let Some(unit) = args.get(1) {
+        let number = unit.as_number().ok_or(invalid_number(unit))?;
+        let in_range =
+            number.is_real() && number > &Number::Integer(0) && number < &Number::Integer(1);
+        if !in_range {
+            return Err(InvalidArgument::OutOfRange(number.to_string()).into());
+        }
+    }
+    Ok(RandomProcedure {
+        name: "random-real",
+        function: random_real,
+        source: source.clone(),
+    }
+    .into())
+}
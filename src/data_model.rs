@@ -1,13 +1,19 @@
-use std::cell::Cell;
+use std::cell::{Cell, OnceCell, Ref, RefCell, RefMut};
+use std::collections::HashSet;
 use std::fmt::Debug;
 use std::ops::Deref;
 use std::ptr::NonNull;
 use std::{collections::HashMap, fmt::Display, rc::Rc};
 
+use crate::builtin::char::digit_value;
 #[cfg(target_arch = "wasm32")]
 use crate::canvas::Canvas;
 use crate::error::ApplyError;
+use crate::limits;
 use crate::number::Number;
+use crate::numeric_vector::{NumericElement, NumericVector};
+use crate::random::RandomSource;
+use crate::span::Location;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Expression {
@@ -31,6 +37,10 @@ impl Expression {
         self.content.is_boolean()
     }
 
+    pub fn is_character(&self) -> bool {
+        self.content.is_character()
+    }
+
     pub fn is_pair(&self) -> bool {
         self.content.is_pair()
     }
@@ -63,6 +73,10 @@ impl Expression {
         self.content.as_boolean()
     }
 
+    pub fn as_character(&self) -> Option<char> {
+        self.content.as_character()
+    }
+
     pub fn as_symbol(&self) -> Option<&str> {
         self.content.as_symbol()
     }
@@ -78,6 +92,10 @@ impl Expression {
     pub(crate) fn as_promise(&self) -> Option<&Promise> {
         self.content.as_promise()
     }
+
+    pub(crate) fn as_numeric_vector(&self) -> Option<&RefCell<NumericVector>> {
+        self.content.as_numeric_vector()
+    }
 }
 
 impl Display for Expression {
@@ -110,6 +128,14 @@ impl From<&str> for Expression {
     }
 }
 
+impl From<char> for Expression {
+    fn from(character: char) -> Self {
+        Self {
+            content: Link::from(character),
+        }
+    }
+}
+
 impl From<bool> for Expression {
     fn from(boolean: bool) -> Self {
         Self {
@@ -134,6 +160,14 @@ impl From<Vec<Link>> for Expression {
     }
 }
 
+impl From<NumericVector> for Expression {
+    fn from(vector: NumericVector) -> Self {
+        Self {
+            content: Link::from(vector),
+        }
+    }
+}
+
 impl From<Promise> for Expression {
     fn from(promise: Promise) -> Self {
         Self {
@@ -150,24 +184,177 @@ impl From<ExpressionContent> for Expression {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub(crate) enum Link {
     More(Rc<ExpressionContent>),
+    /// A reference `#n#` read inside the datum labelled `#n=`, which is only
+    /// filled in once that datum is read. This is how data read with datum
+    /// labels contain themselves; like any cycle of `Rc`s, they are never
+    /// freed.
+    Label(Rc<Placeholder>),
     Nil,
 }
 
+/// The datum a [`Link::Label`] refers to.
+#[derive(Default)]
+pub(crate) struct Placeholder(OnceCell<Link>);
+
+impl Debug for Placeholder {
+    // the datum referred to usually contains the placeholder
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Placeholder")
+    }
+}
+
+impl PartialEq for Link {
+    fn eq(&self, other: &Self) -> bool {
+        equal(vec![(
+            self.as_expression_content(),
+            other.as_expression_content(),
+        )])
+    }
+}
+
+impl Drop for Link {
+    // Dropping the last reference to a long list would otherwise recurse once
+    // per element, so children are moved onto a heap-allocated stack instead.
+    fn drop(&mut self) {
+        let mut stack = vec![];
+        take_children(self, &mut stack);
+        while let Some(mut link) = stack.pop() {
+            take_children(&mut link, &mut stack);
+        }
+    }
+}
+
+fn take_children(link: &mut Link, stack: &mut Vec<Link>) {
+    let content = match link {
+        Link::More(content) => content,
+        Link::Label(placeholder) => {
+            if let Some(placeholder) = Rc::get_mut(placeholder) {
+                stack.extend(placeholder.0.take());
+            }
+            return;
+        }
+        Link::Nil => return,
+    };
+    match Rc::get_mut(content) {
+        Some(ExpressionContent::PairLink(pair)) => {
+            stack.push(std::mem::replace(&mut pair.car, Link::Nil));
+            stack.push(std::mem::replace(&mut pair.cdr, Link::Nil));
+        }
+        Some(ExpressionContent::VectorLink(vector)) => stack.append(vector),
+        Some(ExpressionContent::Promise(promise)) => {
+            stack.push(std::mem::replace(&mut promise.content, Link::Nil));
+        }
+        _ => {}
+    }
+}
+
+/// Structural equality over an explicit stack of pending comparisons. Pairs
+/// and vectors already being compared are taken to be equal, so that
+/// comparing data that contain themselves ends.
+fn equal<'a>(
+    mut stack: Vec<(Option<&'a ExpressionContent>, Option<&'a ExpressionContent>)>,
+) -> bool {
+    use ExpressionContent::*;
+
+    let mut compared = HashSet::new();
+    while let Some(pair) = stack.pop() {
+        if let (Some(lhs @ (PairLink(_) | VectorLink(_))), Some(rhs)) = pair {
+            if !compared.insert((lhs as *const ExpressionContent, rhs as *const _)) {
+                continue;
+            }
+        }
+        match pair {
+            (None, None) => {}
+            (Some(PairLink(lhs)), Some(PairLink(rhs))) => {
+                stack.push((
+                    lhs.cdr.as_expression_content(),
+                    rhs.cdr.as_expression_content(),
+                ));
+                stack.push((
+                    lhs.car.as_expression_content(),
+                    rhs.car.as_expression_content(),
+                ));
+            }
+            (Some(VectorLink(lhs)), Some(VectorLink(rhs))) => {
+                if lhs.len() != rhs.len() {
+                    return false;
+                }
+                stack.extend(
+                    lhs.iter().zip(rhs).rev().map(|(lhs, rhs)| {
+                        (lhs.as_expression_content(), rhs.as_expression_content())
+                    }),
+                );
+            }
+            (Some(Promise(lhs)), Some(Promise(rhs))) => {
+                if lhs.frame != rhs.frame {
+                    return false;
+                }
+                stack.push((
+                    lhs.content.as_expression_content(),
+                    rhs.content.as_expression_content(),
+                ));
+            }
+            (Some(Number(lhs)), Some(Number(rhs))) if lhs == rhs => {}
+            (Some(String(lhs)), Some(String(rhs))) if lhs == rhs => {}
+            (Some(Boolean(lhs)), Some(Boolean(rhs))) if lhs == rhs => {}
+            (Some(Character(lhs)), Some(Character(rhs))) if lhs == rhs => {}
+            (Some(Symbol(lhs)), Some(Symbol(rhs))) if lhs == rhs => {}
+            (Some(NumericVector(lhs)), Some(NumericVector(rhs))) if lhs == rhs => {}
+            _ => return false,
+        }
+    }
+    true
+}
+
 impl Link {
     pub(crate) fn new_pair(car: Link, cdr: Link) -> Self {
-        Self::More(Rc::new(ExpressionContent::PairLink(Pair { car, cdr })))
+        Self::new_located_pair(car, cdr, None)
     }
 
-    pub fn as_expression_content(&self) -> Option<&ExpressionContent> {
-        match self {
-            Self::More(expression) => Some(expression.deref()),
-            Self::Nil => None,
+    pub(crate) fn new_located_pair(car: Link, cdr: Link, location: Option<Rc<Location>>) -> Self {
+        Self::from(ExpressionContent::PairLink(Pair { car, cdr, location }))
+    }
+
+    /// A placeholder for a datum label reference, to be filled in with
+    /// [`fill`](Self::fill).
+    pub(crate) fn placeholder() -> Self {
+        Self::Label(Rc::default())
+    }
+
+    /// Makes the placeholder `self` refer to `datum`.
+    pub(crate) fn fill(&self, datum: Link) {
+        if let Self::Label(placeholder) = self {
+            let _ = placeholder.0.set(datum);
         }
     }
 
+    /// The content linked to, through any placeholders; a placeholder not
+    /// filled in yet links to nothing.
+    pub(crate) fn resolve(&self) -> Option<&Rc<ExpressionContent>> {
+        let mut link = self;
+        loop {
+            match link {
+                Self::More(content) => return Some(content),
+                Self::Label(placeholder) => link = placeholder.0.get()?,
+                Self::Nil => return None,
+            }
+        }
+    }
+
+    pub fn as_expression_content(&self) -> Option<&ExpressionContent> {
+        self.resolve().map(Rc::deref)
+    }
+
+    /// Whether a pair or vector of the datum contains itself.
+    pub(crate) fn is_circular(&self) -> bool {
+        !Labels::find(self.as_expression_content(), Sharing::Cycles)
+            .labelled
+            .is_empty()
+    }
+
     pub fn iter(&self) -> Iter<'_> {
         Iter { next: Some(&self) }
     }
@@ -204,6 +391,13 @@ impl Link {
         )
     }
 
+    pub fn is_character(&self) -> bool {
+        matches!(
+            self.as_expression_content(),
+            Some(ExpressionContent::Character(_))
+        )
+    }
+
     pub fn is_pair(&self) -> bool {
         matches!(
             self.as_expression_content(),
@@ -237,6 +431,10 @@ impl Link {
         self.as_expression_content()?.as_boolean()
     }
 
+    pub fn as_character(&self) -> Option<char> {
+        self.as_expression_content()?.as_character()
+    }
+
     pub fn as_symbol(&self) -> Option<&str> {
         self.as_expression_content()?.as_symbol()
     }
@@ -252,10 +450,15 @@ impl Link {
     pub fn as_promise(&self) -> Option<&Promise> {
         self.as_expression_content()?.as_promise()
     }
+
+    pub fn as_numeric_vector(&self) -> Option<&RefCell<NumericVector>> {
+        self.as_expression_content()?.as_numeric_vector()
+    }
 }
 
 impl From<ExpressionContent> for Link {
     fn from(expression_content: ExpressionContent) -> Self {
+        limits::allocate(limits::footprint(&expression_content));
         Self::More(Rc::new(expression_content))
     }
 }
@@ -284,6 +487,12 @@ impl From<Pair> for Link {
     }
 }
 
+impl From<char> for Link {
+    fn from(character: char) -> Self {
+        Self::from(ExpressionContent::from(character))
+    }
+}
+
 impl From<bool> for Link {
     fn from(boolean: bool) -> Self {
         Self::from(ExpressionContent::from(boolean))
@@ -296,6 +505,12 @@ impl From<Vec<Link>> for Link {
     }
 }
 
+impl From<NumericVector> for Link {
+    fn from(vector: NumericVector) -> Self {
+        Self::from(ExpressionContent::from(vector))
+    }
+}
+
 impl From<Promise> for Link {
     fn from(promise: Promise) -> Self {
         Self::from(ExpressionContent::from(promise))
@@ -310,22 +525,227 @@ impl From<Link> for Expression {
 
 impl Display for Link {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::More(expression) => write!(f, "{}", expression),
-            Self::Nil => write!(f, "()"),
+        let root = self.as_expression_content();
+        print(
+            f,
+            vec![Print::Datum(root)],
+            &mut Labels::find(root, Sharing::Cycles),
+            Style::Write,
+        )
+    }
+}
+
+/// How strings, characters and symbols are written.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Style {
+    /// So that they read back as the same datum, as `write` does.
+    Write,
+    /// As their plain text, as `display` does.
+    Display,
+}
+
+/// Which pairs and vectors `write` marks with datum labels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Sharing {
+    /// None, as `write-simple` does.
+    None,
+    /// Those that contain themselves, as `write` does.
+    Cycles,
+    /// Those reached more than once, as `write-shared` does.
+    All,
+}
+
+/// A datum written in `style` with the datum labels chosen by `sharing`.
+pub(crate) struct Written<'a> {
+    pub(crate) datum: &'a Link,
+    pub(crate) sharing: Sharing,
+    pub(crate) style: Style,
+}
+
+impl Display for Written<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let root = self.datum.as_expression_content();
+        print(
+            f,
+            vec![Print::Datum(root)],
+            &mut Labels::find(root, self.sharing),
+            self.style,
+        )
+    }
+}
+
+/// The pairs and vectors that are written with a datum label, by address, and
+/// the numbers of those whose label has been written.
+#[derive(Default)]
+struct Labels {
+    labelled: HashMap<*const ExpressionContent, Option<usize>>,
+    next: usize,
+}
+
+impl Labels {
+    /// Walks `root` depth first. With [`Sharing::Cycles`] a datum is labelled
+    /// when it is reached again while its own elements are being walked; with
+    /// [`Sharing::All`], whenever it is reached again.
+    fn find(root: Option<&ExpressionContent>, sharing: Sharing) -> Self {
+        let mut labels = Self::default();
+        if sharing == Sharing::None {
+            return labels;
+        }
+        // whether each datum reached is still being walked
+        let mut walking = HashMap::new();
+        let mut stack = vec![(root, true)];
+        while let Some((content, enter)) = stack.pop() {
+            let Some(content @ (ExpressionContent::PairLink(_) | ExpressionContent::VectorLink(_))) =
+                content
+            else {
+                continue;
+            };
+            let key = content as *const ExpressionContent;
+            if !enter {
+                walking.insert(key, false);
+                continue;
+            }
+            if let Some(&on_path) = walking.get(&key) {
+                if on_path || sharing == Sharing::All {
+                    labels.labelled.insert(key, None);
+                }
+                continue;
+            }
+            walking.insert(key, true);
+            stack.push((Some(content), false));
+            match content {
+                ExpressionContent::PairLink(pair) => {
+                    stack.push((pair.cdr.as_expression_content(), true));
+                    stack.push((pair.car.as_expression_content(), true));
+                }
+                ExpressionContent::VectorLink(vector) => stack.extend(
+                    vector
+                        .iter()
+                        .rev()
+                        .map(|item| (item.as_expression_content(), true)),
+                ),
+                _ => unreachable!(),
+            }
+        }
+        labels
+    }
+
+    fn contains(&self, content: &ExpressionContent) -> bool {
+        self.labelled.contains_key(&(content as *const _))
+    }
+
+    /// Writes the label of `content`, if it has one: `#n=` the first time and
+    /// `#n#` after. Returns whether `content` itself is still to be written.
+    fn write(
+        &mut self,
+        f: &mut std::fmt::Formatter<'_>,
+        content: &ExpressionContent,
+    ) -> Result<bool, std::fmt::Error> {
+        match self.labelled.get_mut(&(content as *const _)) {
+            None => Ok(true),
+            Some(Some(number)) => write!(f, "#{}#", number).map(|_| false),
+            Some(number @ None) => {
+                *number = Some(self.next);
+                self.next += 1;
+                write!(f, "#{}=", self.next - 1).map(|_| true)
+            }
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// Pending output of the printer.
+enum Print<'a> {
+    Datum(Option<&'a ExpressionContent>),
+    /// A datum whose label, if it has one, has been written.
+    Unlabelled(&'a ExpressionContent),
+    ListTail(Option<&'a ExpressionContent>),
+    VectorTail(&'a [Link]),
+    Text(&'static str),
+}
+
+/// Writes data using an explicit stack, so that long lists and deep nesting
+/// cannot overflow the call stack. A pair in `labels` is written as the cdr of
+/// a dotted pair, so that its label can be written.
+fn print(
+    f: &mut std::fmt::Formatter<'_>,
+    mut stack: Vec<Print<'_>>,
+    labels: &mut Labels,
+    style: Style,
+) -> std::fmt::Result {
+    while let Some(task) = stack.pop() {
+        match task {
+            Print::Text(text) => f.write_str(text)?,
+            Print::Datum(None) => f.write_str("()")?,
+            Print::Datum(Some(content)) => {
+                if labels.write(f, content)? {
+                    stack.push(Print::Unlabelled(content));
+                }
+            }
+            Print::Unlabelled(ExpressionContent::PairLink(pair)) => {
+                f.write_str("(")?;
+                stack.push(Print::ListTail(pair.cdr.as_expression_content()));
+                stack.push(Print::Datum(pair.car.as_expression_content()));
+            }
+            Print::Unlabelled(ExpressionContent::VectorLink(vector)) => {
+                f.write_str("#(")?;
+                match vector.split_first() {
+                    Some((first, rest)) => {
+                        stack.push(Print::VectorTail(rest));
+                        stack.push(Print::Datum(first.as_expression_content()));
+                    }
+                    None => f.write_str(")")?,
+                }
+            }
+            Print::Unlabelled(atom) => atom.fmt_atom(f, style)?,
+            Print::ListTail(None) => f.write_str(")")?,
+            Print::ListTail(Some(content @ ExpressionContent::PairLink(pair)))
+                if !labels.contains(content) =>
+            {
+                f.write_str(" ")?;
+                stack.push(Print::ListTail(pair.cdr.as_expression_content()));
+                stack.push(Print::Datum(pair.car.as_expression_content()));
+            }
+            Print::ListTail(Some(rest)) => {
+                f.write_str(" . ")?;
+                stack.push(Print::Text(")"));
+                stack.push(Print::Datum(Some(rest)));
+            }
+            Print::VectorTail([]) => f.write_str(")")?,
+            Print::VectorTail([first, rest @ ..]) => {
+                f.write_str(" ")?;
+                stack.push(Print::VectorTail(rest));
+                stack.push(Print::Datum(first.as_expression_content()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The R7RS character names, as read after `#\\` and written back.
+pub(crate) const CHARACTER_NAMES: [(&str, char); 9] = [
+    ("alarm", '\u{7}'),
+    ("backspace", '\u{8}'),
+    ("delete", '\u{7f}'),
+    ("escape", '\u{1b}'),
+    ("newline", '\n'),
+    ("null", '\0'),
+    ("return", '\r'),
+    ("space", ' '),
+    ("tab", '\t'),
+];
+
+#[derive(Debug, Clone)]
 pub(crate) enum ExpressionContent {
     Number(Number),
     String(String),
     Boolean(bool),
+    Character(char),
     Symbol(String),
     PairLink(Pair),
     VectorLink(Vec<Link>),
     Promise(Promise),
+    /// Homogeneous vectors are the only mutable data.
+    NumericVector(RefCell<NumericVector>),
 }
 
 impl ExpressionContent {
@@ -350,6 +770,13 @@ impl ExpressionContent {
         }
     }
 
+    pub fn as_character(&self) -> Option<char> {
+        match self {
+            Self::Character(character) => Some(*character),
+            _ => None,
+        }
+    }
+
     pub fn as_symbol(&self) -> Option<&str> {
         match self {
             Self::Symbol(symbol) => Some(symbol),
@@ -377,6 +804,13 @@ impl ExpressionContent {
             _ => None,
         }
     }
+
+    pub fn as_numeric_vector(&self) -> Option<&RefCell<NumericVector>> {
+        match self {
+            Self::NumericVector(vector) => Some(vector),
+            _ => None,
+        }
+    }
 }
 
 impl From<Number> for ExpressionContent {
@@ -397,6 +831,12 @@ impl From<&str> for ExpressionContent {
     }
 }
 
+impl From<char> for ExpressionContent {
+    fn from(character: char) -> Self {
+        Self::Character(character)
+    }
+}
+
 impl From<bool> for ExpressionContent {
     fn from(boolean: bool) -> Self {
         Self::Boolean(boolean)
@@ -415,17 +855,75 @@ impl From<Vec<Link>> for ExpressionContent {
     }
 }
 
+impl From<NumericVector> for ExpressionContent {
+    fn from(vector: NumericVector) -> Self {
+        Self::NumericVector(RefCell::new(vector))
+    }
+}
+
 impl From<Promise> for ExpressionContent {
     fn from(promise: Promise) -> Self {
         Self::Promise(promise)
     }
 }
 
-impl<'a> Display for ExpressionContent {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl PartialEq for ExpressionContent {
+    fn eq(&self, other: &Self) -> bool {
+        equal(vec![(Some(self), Some(other))])
+    }
+}
+
+/// Whether `character` may start an identifier: a letter, one of
+/// `!$%&*/:<=>?^_~`, or a character beyond ASCII but for digits, whitespace
+/// and control characters.
+fn is_initial(character: char) -> bool {
+    character.is_ascii_alphabetic()
+        || "!$%&*/:<=>?^_~".contains(character)
+        || (is_unicode_subsequent(character) && digit_value(character).is_none())
+}
+
+fn is_subsequent(character: char) -> bool {
+    character.is_ascii_alphanumeric()
+        || "!$%&*/:<=>?^_~+-.@".contains(character)
+        || is_unicode_subsequent(character)
+}
+
+fn is_unicode_subsequent(character: char) -> bool {
+    !character.is_ascii() && !character.is_whitespace() && !character.is_control()
+}
+
+/// Whether `symbol` reads back as itself when written without vertical lines,
+/// rather than as a number, several tokens or not at all. Besides ordinary
+/// identifiers, these are the peculiar ones: `+`, `-`, `...`, `->list`,
+/// `.foo`, `+.a` and the like, but for numbers such as `+i` and `-inf.0`.
+fn reads_as_symbol(symbol: &str) -> bool {
+    let mut chars = symbol.chars();
+    match (chars.next(), chars.next()) {
+        (Some(first), _) if is_initial(first) => symbol.chars().all(is_subsequent),
+        (Some('+' | '-'), None) => true,
+        // a sign and a dot start a number unless what follows cannot
+        (Some('+' | '-'), Some('.')) => {
+            chars
+                .next()
+                .is_some_and(|third| is_initial(third) || "+-@.".contains(third))
+                && chars.all(is_subsequent)
+        }
+        (Some('+' | '-'), Some(second)) if is_initial(second) || "+-@".contains(second) => {
+            chars.all(is_subsequent) && Number::try_from(symbol).is_err()
+        }
+        (Some('.'), Some(second)) if is_initial(second) || "+-@.".contains(second) => {
+            chars.all(is_subsequent)
+        }
+        _ => false,
+    }
+}
+
+impl ExpressionContent {
+    fn fmt_atom(&self, f: &mut std::fmt::Formatter<'_>, style: Style) -> std::fmt::Result {
         match self {
             Self::Number(number) => write!(f, "{}", number),
-            Self::String(string) => write!(f, "\"{}\"", string),
+            Self::String(string) if style == Style::Display => f.write_str(string),
+            Self::String(string) => write_escaped(f, string, '"'),
             Self::Boolean(boolean) => {
                 if *boolean {
                     write!(f, "#t")
@@ -433,21 +931,57 @@ impl<'a> Display for ExpressionContent {
                     write!(f, "#f")
                 }
             }
-            Self::Symbol(symbol) => write!(f, "{}", symbol),
-            Self::PairLink(pair) => write!(f, "{}", pair),
-            Self::VectorLink(vector) => {
-                write!(f, "#(")?;
-                for (index, link) in vector.iter().enumerate() {
-                    write!(f, "{}", link)?;
-                    if index != vector.len() - 1 {
-                        write!(f, " ")?;
-                    }
+            Self::Character(character) if style == Style::Display => write!(f, "{}", character),
+            Self::Character(character) => {
+                match CHARACTER_NAMES.iter().find(|(_, named)| named == character) {
+                    Some((name, _)) => write!(f, "#\\{}", name),
+                    None if character.is_control() => write!(f, "#\\x{:x}", *character as u32),
+                    None => write!(f, "#\\{}", character),
                 }
-                write!(f, ")")
             }
+            Self::Symbol(symbol) if style == Style::Display || reads_as_symbol(symbol) => {
+                f.write_str(symbol)
+            }
+            Self::Symbol(symbol) => write_escaped(f, symbol, '|'),
             Self::Promise(_) => write!(f, "#[promise]"),
+            Self::NumericVector(vector) => write!(f, "{}", vector.borrow()),
+            Self::PairLink(_) | Self::VectorLink(_) => print(
+                f,
+                vec![Print::Datum(Some(self))],
+                &mut Labels::find(Some(self), Sharing::Cycles),
+                style,
+            ),
+        }
+    }
+}
+
+impl Display for ExpressionContent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        print(
+            f,
+            vec![Print::Datum(Some(self))],
+            &mut Labels::find(Some(self), Sharing::Cycles),
+            Style::Write,
+        )
+    }
+}
+
+/// Writes `text` between `delimiter`s, escaping the delimiter, backslashes and
+/// control characters so that it reads back as written.
+fn write_escaped(f: &mut std::fmt::Formatter<'_>, text: &str, delimiter: char) -> std::fmt::Result {
+    write!(f, "{}", delimiter)?;
+    for character in text.chars() {
+        match character {
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\t' => f.write_str("\\t")?,
+            '\r' => f.write_str("\\r")?,
+            _ if character == delimiter => write!(f, "\\{}", character)?,
+            _ if character.is_control() => write!(f, "\\x{:x};", character as u32)?,
+            _ => write!(f, "{}", character)?,
         }
     }
+    write!(f, "{}", delimiter)
 }
 
 pub(crate) trait AsSymbol {
@@ -460,10 +994,13 @@ impl AsSymbol for str {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub(crate) struct Pair {
     pub(crate) car: Link,
     pub(crate) cdr: Link,
+    /// Where the form was read from, kept for locating errors. It takes no
+    /// part in equality.
+    pub(crate) location: Option<Rc<Location>>,
 }
 
 impl Pair {
@@ -476,24 +1013,18 @@ impl Pair {
     }
 }
 
-impl Display for Pair {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}", &self.car)?;
-        let mut cdr = &self.cdr;
-        loop {
-            if let Link::More(expression) = &cdr {
-                if let ExpressionContent::PairLink(pair) = expression.as_ref() {
-                    write!(f, " {}", pair.car)?;
-                    cdr = &pair.cdr;
-                } else {
-                    write!(f, " . {}", cdr)?;
-                    break;
-                }
-            } else {
-                break;
-            }
-        }
-        write!(f, ")")
+impl PartialEq for Pair {
+    fn eq(&self, other: &Self) -> bool {
+        equal(vec![
+            (
+                self.cdr.as_expression_content(),
+                other.cdr.as_expression_content(),
+            ),
+            (
+                self.car.as_expression_content(),
+                other.car.as_expression_content(),
+            ),
+        ])
     }
 }
 
@@ -518,13 +1049,13 @@ impl<'a> Iterator for Iter<'a> {
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) struct Promise {
     pub(crate) content: Link,
-    pub(crate) frame: FrameLink,
+    pub(crate) frame: Frame,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Thunk {
     pub(crate) content: Link,
-    pub(crate) frame: FrameLink,
+    pub(crate) frame: Frame,
 }
 
 #[derive(PartialEq)]
@@ -563,7 +1094,7 @@ impl Debug for Frame {
 
 #[derive(PartialEq)]
 pub(crate) struct FrameNode {
-    data: HashMap<String, Value>,
+    data: HashMap<Rc<str>, Value>,
     parent: Option<FrameLink>,
     ref_count: Cell<usize>,
 }
@@ -587,6 +1118,7 @@ impl Debug for FrameNode {
 
 impl Frame {
     pub fn new() -> Self {
+        limits::allocate(std::mem::size_of::<FrameNode>());
         unsafe {
             Self {
                 content: NonNull::new_unchecked(Box::into_raw(Box::new(FrameNode {
@@ -599,6 +1131,7 @@ impl Frame {
     }
 
     pub fn new_with_parent(parent: &Self) -> Self {
+        limits::allocate(std::mem::size_of::<FrameNode>());
         unsafe {
             parent
                 .content
@@ -616,6 +1149,7 @@ impl Frame {
     }
 
     pub fn make_child(&self) -> Self {
+        limits::allocate(std::mem::size_of::<FrameNode>());
         unsafe {
             self.content
                 .as_ref()
@@ -632,8 +1166,12 @@ impl Frame {
     }
 
     pub fn define(&mut self, name: &str, value: Value) {
+        self.bind(Rc::from(name), value);
+    }
+
+    pub(crate) fn bind(&mut self, name: Rc<str>, value: Value) {
         unsafe {
-            (*self.content.as_ptr()).define(name, value);
+            (*self.content.as_ptr()).bind(name, value);
         }
     }
 
@@ -645,6 +1183,39 @@ impl Frame {
         self.define(builtin.name, builtin.into());
     }
 
+    pub(crate) fn add_environment_procedure(&mut self, procedure: EnvironmentProcedure) {
+        self.define(procedure.name, procedure.into());
+    }
+
+    pub(crate) fn add_random(&mut self, random: RandomProcedure) {
+        self.define(random.name, random.into());
+    }
+
+    pub(crate) fn from_link(link: FrameLink) -> Self {
+        unsafe {
+            link.as_ref()
+                .ref_count
+                .set(link.as_ref().ref_count.get() + 1);
+        }
+        Self { content: link }
+    }
+
+    pub(crate) fn parent(&self) -> Option<Frame> {
+        unsafe { self.content.as_ref().parent.map(Self::from_link) }
+    }
+
+    pub(crate) fn global(&self) -> Frame {
+        let mut frame = self.clone();
+        while let Some(parent) = frame.parent() {
+            frame = parent;
+        }
+        frame
+    }
+
+    pub(crate) fn bindings(&self) -> impl Iterator<Item = (&Rc<str>, &Value)> {
+        unsafe { (*self.content.as_ptr()).data.iter() }
+    }
+
     #[cfg(target_arch = "wasm32")]
     pub(crate) fn add_graphic(&mut self, graphic: GraphicProcedure, aliases: &[&str]) {
         self.define(graphic.name, graphic.clone().into());
@@ -669,8 +1240,9 @@ impl Drop for Frame {
 }
 
 impl FrameNode {
-    pub fn define(&mut self, name: &str, value: Value) {
-        self.data.insert(name.to_string(), value);
+    pub fn bind(&mut self, name: Rc<str>, value: Value) {
+        limits::allocate(name.len() + std::mem::size_of::<Value>());
+        self.data.insert(name, value);
     }
 
     pub fn lookup(&self, name: &str) -> Option<&Value> {
@@ -696,6 +1268,8 @@ pub(crate) enum SpecialForm {
     Delay,
     Do,
     If,
+    Include,
+    IncludeCi,
     Lambda,
     Let,
     LetRec,
@@ -714,18 +1288,22 @@ pub(crate) enum SpecialForm {
 #[derive(Debug, Clone, PartialEq)]
 pub enum Procedure {
     Builtin(BuiltinProcedure),
+    Environment(EnvironmentProcedure),
     #[cfg(target_arch = "wasm32")]
     Graphic(GraphicProcedure),
-    Lambda(LambdaProcedure),
+    Lambda(Rc<LambdaProcedure>),
+    Random(RandomProcedure),
 }
 
 impl Display for Procedure {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Builtin(builtin) => write!(f, "{}", builtin),
+            Self::Environment(environment) => write!(f, "{}", environment),
             Self::Lambda(lambda) => write!(f, "{}", lambda),
             #[cfg(target_arch = "wasm32")]
             Self::Graphic(graphic) => write!(f, "{}", graphic),
+            Self::Random(random) => write!(f, "{}", random),
         }
     }
 }
@@ -733,7 +1311,7 @@ impl Display for Procedure {
 #[derive(Debug, Clone, PartialEq)]
 pub struct BuiltinProcedure {
     pub(crate) name: &'static str,
-    pub(crate) function: fn(Vec<Value>) -> Result<Value, ApplyError>,
+    pub(crate) function: fn(&[Value]) -> Result<Value, ApplyError>,
 }
 
 impl Display for BuiltinProcedure {
@@ -748,11 +1326,29 @@ impl From<BuiltinProcedure> for Procedure {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvironmentProcedure {
+    pub(crate) name: &'static str,
+    pub(crate) function: fn(&[Value], &mut Frame) -> Result<Value, ApplyError>,
+}
+
+impl Display for EnvironmentProcedure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#[{}]", self.name)
+    }
+}
+
+impl From<EnvironmentProcedure> for Procedure {
+    fn from(environment: EnvironmentProcedure) -> Self {
+        Self::Environment(environment)
+    }
+}
+
 #[cfg(target_arch = "wasm32")]
 #[derive(Debug, Clone, PartialEq)]
 pub struct GraphicProcedure {
     pub(crate) name: &'static str,
-    pub(crate) function: fn(Vec<Value>, &mut Canvas) -> Result<Value, ApplyError>,
+    pub(crate) function: fn(&[Value], &mut Canvas) -> Result<Value, ApplyError>,
     pub(crate) canvas: Canvas,
 }
 
@@ -770,10 +1366,36 @@ impl From<GraphicProcedure> for Procedure {
     }
 }
 
+/// A procedure that draws from the random source it was made from.
+#[derive(Debug, Clone)]
+pub struct RandomProcedure {
+    pub(crate) name: &'static str,
+    pub(crate) function: fn(&[Value], &RandomSource) -> Result<Value, ApplyError>,
+    pub(crate) source: RandomSource,
+}
+
+impl PartialEq for RandomProcedure {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && Rc::ptr_eq(&self.source.state, &other.source.state)
+    }
+}
+
+impl Display for RandomProcedure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#[{}]", self.name)
+    }
+}
+
+impl From<RandomProcedure> for Procedure {
+    fn from(random: RandomProcedure) -> Self {
+        Self::Random(random)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct LambdaProcedure {
     pub(crate) name: Option<String>,
-    pub(crate) formals: Vec<String>,
+    pub(crate) formals: Vec<Rc<str>>,
     pub(crate) body: Link,
     pub(crate) frame: Frame,
 }
@@ -786,7 +1408,7 @@ impl Display for LambdaProcedure {
 
 impl From<LambdaProcedure> for Procedure {
     fn from(lambda: LambdaProcedure) -> Self {
-        Self::Lambda(lambda)
+        Self::Lambda(Rc::new(lambda))
     }
 }
 
@@ -795,6 +1417,9 @@ pub enum Value {
     Expression(Expression),
     Procedure(Procedure),
     Thunk(Thunk),
+    /// The results of `values`, when there are not exactly one.
+    Values(Vec<Value>),
+    RandomSource(RandomSource),
     Void,
 }
 
@@ -820,6 +1445,13 @@ impl Value {
         }
     }
 
+    pub fn as_random_source(&self) -> Option<&RandomSource> {
+        match self {
+            Self::RandomSource(source) => Some(source),
+            _ => None,
+        }
+    }
+
     pub fn as_number(&self) -> Option<&Number> {
         match self {
             Self::Expression(expression) => expression.as_number(),
@@ -841,6 +1473,13 @@ impl Value {
         }
     }
 
+    pub fn as_character(&self) -> Option<char> {
+        match self {
+            Self::Expression(expression) => expression.as_character(),
+            _ => None,
+        }
+    }
+
     pub fn as_symbol(&self) -> Option<&str> {
         match self {
             Self::Expression(expression) => expression.as_symbol(),
@@ -868,6 +1507,25 @@ impl Value {
             _ => None,
         }
     }
+
+    pub(crate) fn as_numeric_vector(&self) -> Option<&RefCell<NumericVector>> {
+        match self {
+            Self::Expression(expression) => expression.as_numeric_vector(),
+            _ => None,
+        }
+    }
+
+    /// Borrows the elements of a homogeneous vector of `T`, for example
+    /// `value.as_numeric_slice::<f64>()` for an `f64vector`. Gives `None`
+    /// for anything else.
+    pub fn as_numeric_slice<T: NumericElement>(&self) -> Option<Ref<'_, [T]>> {
+        Ref::filter_map(self.as_numeric_vector()?.borrow(), T::slice).ok()
+    }
+
+    /// Like `as_numeric_slice`, but the elements can be changed in place.
+    pub fn as_numeric_slice_mut<T: NumericElement>(&self) -> Option<RefMut<'_, [T]>> {
+        RefMut::filter_map(self.as_numeric_vector()?.borrow_mut(), T::slice_mut).ok()
+    }
 }
 
 impl From<Value> for bool {
@@ -888,6 +1546,16 @@ impl Display for Value {
             Self::Expression(expression) => write!(f, "{}", expression),
             Self::Procedure(procedure) => write!(f, "{}", procedure),
             Self::Thunk(_) => write!(f, "#[thunk]"),
+            Self::RandomSource(_) => write!(f, "#[random-source]"),
+            Self::Values(values) => {
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                Ok(())
+            }
             Self::Void => write!(f, ""),
         }
     }
@@ -923,6 +1591,12 @@ impl From<Link> for Value {
     }
 }
 
+impl From<char> for Value {
+    fn from(character: char) -> Self {
+        Self::from(Expression::from(character))
+    }
+}
+
 impl From<bool> for Value {
     fn from(boolean: bool) -> Self {
         Self::from(Expression::from(boolean))
@@ -941,6 +1615,19 @@ impl From<Vec<Link>> for Value {
     }
 }
 
+impl From<NumericVector> for Value {
+    fn from(vector: NumericVector) -> Self {
+        Self::from(Expression::from(vector))
+    }
+}
+
+/// Wraps the elements in a homogeneous vector of the matching kind.
+impl<T: NumericElement> From<Vec<T>> for Value {
+    fn from(elements: Vec<T>) -> Self {
+        Self::from(T::wrap(elements))
+    }
+}
+
 impl From<Promise> for Value {
     fn from(promise: Promise) -> Self {
         Self::from(Expression::from(promise))
@@ -959,6 +1646,12 @@ impl From<BuiltinProcedure> for Value {
     }
 }
 
+impl From<EnvironmentProcedure> for Value {
+    fn from(environment: EnvironmentProcedure) -> Self {
+        Self::from(Procedure::from(environment))
+    }
+}
+
 impl From<LambdaProcedure> for Value {
     fn from(lambda: LambdaProcedure) -> Self {
         Self::from(Procedure::from(lambda))
@@ -972,12 +1665,24 @@ impl From<GraphicProcedure> for Value {
     }
 }
 
+impl From<RandomProcedure> for Value {
+    fn from(random: RandomProcedure) -> Self {
+        Self::from(Procedure::from(random))
+    }
+}
+
 impl From<Thunk> for Value {
     fn from(thunk: Thunk) -> Self {
         Self::Thunk(thunk)
     }
 }
 
+impl From<RandomSource> for Value {
+    fn from(source: RandomSource) -> Self {
+        Self::RandomSource(source)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1015,4 +1720,27 @@ mod test {
         );
         println!("size of Value: {}", std::mem::size_of::<Value>());
     }
+
+    #[test]
+    fn test_reads_as_symbol() {
+        use crate::lexer::{tokenize, Token};
+
+        for symbol in [
+            "x", "λ", "a.b", "->list", "+", "-", "...", "..", ".foo", ".+", "+a", "-@x", "--",
+            "+i", "-inf.0", "+nan.0", "1+", "+.a", "-.foo", "+..", "-.@", "+.", "+.5", "-.5e1",
+            "+5", ".", ".5", "", "a b", "a|b", "#t", "٣x", "x٣", "a;b",
+        ] {
+            // the symbol reads back as written exactly when it is left bare
+            let tokens = tokenize(symbol);
+            let round_trips = tokens.is_ok_and(|mut tokens| {
+                !tokens.is_empty()
+                    && matches!(*tokens.pop(), Token::Identifier(read) if read == symbol)
+                    && tokens.is_empty()
+            });
+            assert_eq!(reads_as_symbol(symbol), round_trips, "{:?}", symbol);
+        }
+        for symbol in ["+.a", "-.foo", "+.."] {
+            assert!(reads_as_symbol(symbol), "{:?}", symbol);
+        }
+    }
 }
@@ -1,5 +1,9 @@
+use std::time::Duration;
+
 use thiserror::Error;
 
+use crate::span::Location;
+
 #[derive(Debug, Error, PartialEq)]
 pub enum TokenError {
     #[error("invalid character: {0}")]
@@ -16,6 +20,12 @@ pub enum TokenError {
 
     #[error("string missing closing quote")]
     MissingCloseQuote,
+
+    #[error("block comment missing closing |#")]
+    MissingCloseComment,
+
+    #[error("identifier missing closing |")]
+    MissingCloseVerticalLine,
 }
 
 #[derive(Debug, Error, PartialEq)]
@@ -23,8 +33,8 @@ pub enum ParseError {
     #[error("unexpected end of file")]
     EOF,
 
-    #[error("invalid number: {0}")]
-    InvalidNumber(String),
+    #[error("invalid number {0}: {1}")]
+    InvalidNumber(String, &'static str),
 
     #[error("missing opening parenthesis")]
     MissingOpenParenthesis,
@@ -32,6 +42,9 @@ pub enum ParseError {
     #[error("missing closing parenthesis")]
     MissingCLoseParenthesis,
 
+    #[error("invalid character literal: {0}")]
+    InvalidCharacter(String),
+
     #[error("invalid escape in string: {0}")]
     InvalidCharacterEscape(String),
 
@@ -40,6 +53,21 @@ pub enum ParseError {
 
     #[error("dot is only allowed in the pair")]
     InvalidDot,
+
+    #[error("{0} is not followed by a datum")]
+    MissingDatum(String),
+
+    #[error("{0} is not a valid {1}vector element")]
+    InvalidVectorElement(String, &'static str),
+
+    #[error("datum label #{0}# is not defined")]
+    UnknownLabel(String),
+
+    #[error("datum label #{0}= is defined twice")]
+    DuplicateLabel(String),
+
+    #[error("datum label #{0}# refers to the datum it labels, but not from inside a pair or vector of it")]
+    CircularLabel(String),
 }
 
 #[derive(Debug, Error, PartialEq)]
@@ -49,6 +77,33 @@ pub enum EvalError {
 
     #[error("{0}")]
     ApplyError(ApplyError),
+
+    #[error("{0}")]
+    LimitExceeded(LimitError),
+
+    #[error("evaluation interrupted")]
+    Interrupted,
+
+    /// An error reading a file for `include`.
+    #[error("{0}")]
+    Include(Box<Error>),
+
+    /// An error raised while evaluating the form at the location.
+    #[error("{1}: {0}")]
+    Located(Box<EvalError>, Location),
+}
+
+impl EvalError {
+    /// Locates the error at the form `location` unless it is located already
+    /// or concerns the evaluation as a whole, as limits and interrupts do.
+    pub(crate) fn at(self, location: Option<&Location>) -> Self {
+        match (self, location) {
+            (error @ (Self::Located(..) | Self::LimitExceeded(_) | Self::Interrupted), _)
+            | (error, None) => error,
+            (Self::Include(error), _) if error.location().is_some() => Self::Include(error),
+            (error, Some(location)) => Self::Located(Box::new(error), location.clone()),
+        }
+    }
 }
 
 #[derive(Debug, Error, PartialEq)]
@@ -58,11 +113,18 @@ pub enum ApplyError {
 
     #[error("{0} is not a procedure")]
     InvalidProcedure(String),
+
+    #[error("{0}")]
+    ImageError(ImageError),
+
+    /// An error raised while a built-in called back into Scheme code.
+    #[error("{0}")]
+    EvalError(Box<EvalError>),
 }
 
 #[derive(Debug, Error, PartialEq)]
 pub enum InvalidArgument {
-    #[error("{0} is not a {1}")]
+    #[error("{0} is not of type {1}")]
     InvalidType(String, String),
 
     #[error("{0} expects at least {1} arguments, but got {2} arguments")]
@@ -76,6 +138,48 @@ pub enum InvalidArgument {
 
     #[error("division by zero")]
     ZeroDivisor,
+
+    #[error("{0} is out of range")]
+    OutOfRange(String),
+
+    #[error("{0} has no exact representation")]
+    NoExactRepresentation(String),
+}
+
+#[derive(Debug, Error, PartialEq, Clone, Copy)]
+pub enum LimitError {
+    #[error("step limit of {0} exceeded")]
+    Steps(u64),
+
+    #[error("maximum recursion depth of {0} exceeded")]
+    Depth(usize),
+
+    #[error("heap limit of {0} bytes exceeded")]
+    Heap(usize),
+
+    #[error("stack limit of {0} bytes exceeded")]
+    Stack(usize),
+
+    #[error("evaluation timed out after {0:?}")]
+    Timeout(Duration),
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ImageError {
+    #[error("cannot access image: {0}")]
+    Io(String),
+
+    #[error("not a scheme image")]
+    InvalidMagic,
+
+    #[error("unsupported image version: {0}")]
+    UnsupportedVersion(u8),
+
+    #[error("corrupt image: {0}")]
+    Corrupt(String),
+
+    #[error("image refers to unknown primitive: {0}")]
+    UnknownPrimitive(String),
 }
 
 pub(crate) fn invalid_number<T: ToString>(value: &T) -> InvalidArgument {
@@ -127,9 +231,39 @@ impl From<InvalidArgument> for ApplyError {
     }
 }
 
+impl From<std::io::Error> for ImageError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error.to_string())
+    }
+}
+
+impl From<ImageError> for ApplyError {
+    fn from(error: ImageError) -> Self {
+        Self::ImageError(error)
+    }
+}
+
 impl From<ApplyError> for EvalError {
     fn from(error: ApplyError) -> Self {
-        Self::ApplyError(error)
+        match error {
+            ApplyError::EvalError(error) => *error,
+            error => Self::ApplyError(error),
+        }
+    }
+}
+
+impl From<EvalError> for ApplyError {
+    fn from(error: EvalError) -> Self {
+        match error {
+            EvalError::ApplyError(error) => error,
+            error => Self::EvalError(Box::new(error)),
+        }
+    }
+}
+
+impl From<LimitError> for EvalError {
+    fn from(error: LimitError) -> Self {
+        Self::LimitExceeded(error)
     }
 }
 
@@ -152,6 +286,74 @@ pub enum Error {
 
     #[error("{0}")]
     EvalError(EvalError),
+
+    #[error("{0}")]
+    LimitExceeded(LimitError),
+
+    #[error("evaluation interrupted")]
+    Interrupted,
+
+    #[error("cannot read input: {0}")]
+    Io(String),
+
+    /// A file that ended inside a datum. Unlike input ending at a prompt, it
+    /// cannot be continued.
+    #[error("unexpected end of file in {0}")]
+    Truncated(String),
+
+    #[error("{1}: {0}")]
+    Located(Box<Error>, Location),
+}
+
+impl Error {
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            Self::Located(_, location) => Some(location),
+            _ => None,
+        }
+    }
+
+    /// Whether the input ended inside a datum, so that more input could
+    /// complete it.
+    pub fn is_incomplete(&self) -> bool {
+        match self {
+            Self::Located(error, _) => error.is_incomplete(),
+            Self::TokenError(
+                TokenError::MissingCloseQuote
+                | TokenError::MissingCloseComment
+                | TokenError::MissingCloseVerticalLine,
+            )
+            | Self::ParseError(ParseError::EOF | ParseError::MissingCLoseParenthesis) => true,
+            _ => false,
+        }
+    }
+
+    /// Makes an error of input ending inside a datum final, as it is at the
+    /// end of the file `path`, keeping its location.
+    pub(crate) fn at_end_of(self, path: &str) -> Self {
+        match self {
+            Self::Located(error, location) if error.is_incomplete() => {
+                Self::Located(Box::new(Self::Truncated(path.to_string())), location)
+            }
+            error if error.is_incomplete() => Self::Truncated(path.to_string()),
+            error => error,
+        }
+    }
+
+    /// Renders the error for display to a user. A located error is followed by
+    /// a snippet of the source with the failing token or form underlined.
+    pub fn render(&self) -> String {
+        match self {
+            Self::Located(error, location) => format!("{}\n{}", error, location.snippet()),
+            error => error.to_string(),
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error.to_string())
+    }
 }
 
 impl From<TokenError> for Error {
@@ -168,6 +370,14 @@ impl From<ParseError> for Error {
 
 impl From<EvalError> for Error {
     fn from(error: EvalError) -> Self {
-        Self::EvalError(error)
+        match error {
+            EvalError::LimitExceeded(error) => Self::LimitExceeded(error),
+            EvalError::Interrupted => Self::Interrupted,
+            EvalError::Include(error) => *error,
+            EvalError::Located(error, location) => {
+                Self::Located(Box::new(Self::from(*error)), location)
+            }
+            error => Self::EvalError(error),
+        }
     }
 }
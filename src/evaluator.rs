@@ -1,13 +1,23 @@
+use std::{fs::File, io::BufReader, rc::Rc};
+
 #[cfg(target_arch = "wasm32")]
 use crate::data_model::GraphicProcedure;
 use crate::{
     data_model::{
-        BuiltinProcedure, Expression, ExpressionContent, Frame, LambdaProcedure, Link, Procedure,
-        SpecialForm, Thunk, Value,
+        BuiltinProcedure, EnvironmentProcedure, Expression, ExpressionContent, Frame,
+        LambdaProcedure, Link, Pair, Procedure, RandomProcedure, SpecialForm, Thunk, Value,
+    },
+    error::{
+        invalid_symbol, validate_number_of_arguments, ApplyError, Error, EvalError, InvalidArgument,
     },
-    error::{invalid_symbol, validate_number_of_arguments, ApplyError, EvalError, InvalidArgument},
+    limits,
+    reader::Reader,
 };
 use phf::phf_map;
+use smallvec::SmallVec;
+
+/// Operands of a procedure call; small arities stay on the stack.
+pub(crate) type Operands = SmallVec<[Value; 4]>;
 
 const SPECIAL_FORMS: phf::Map<&str, SpecialForm> = phf_map! {
     "and"    => SpecialForm::And,
@@ -18,6 +28,8 @@ const SPECIAL_FORMS: phf::Map<&str, SpecialForm> = phf_map! {
     "delay"  => SpecialForm::Delay,
     "do"     => SpecialForm::Do,
     "if"     => SpecialForm::If,
+    "include" => SpecialForm::Include,
+    "include-ci" => SpecialForm::IncludeCi,
     "lambda" => SpecialForm::Lambda,
     "let-syntax" => SpecialForm::LetSyntax,
     "let"    => SpecialForm::Let,
@@ -38,31 +50,17 @@ pub fn eval(
     frame: &mut Frame,
     tail_content: bool,
 ) -> Result<Value, EvalError> {
+    limits::step()?;
     match expression.content.as_expression_content() {
         Some(ExpressionContent::PairLink(pair)) => {
             // TODO: Implement tail call optimization
             // if tail_content {
             //     return Ok(Value::Thunk(Thunk {
             //         content: expression.content.clone(),
-            //         frame: frame.content,
+            //         frame: frame.clone(),
             //     }));
             // }
-            if let Some(symbol) = pair.car.as_symbol() {
-                if SPECIAL_FORMS.contains_key(symbol) {
-                    let special_form = SPECIAL_FORMS[symbol];
-                    return Ok(special_form.apply(pair.cdr(), frame)?);
-                }
-            }
-            let operator = eval(pair.car().into(), frame, false)?;
-            if let Value::Procedure(mut procedure) = operator {
-                let mut operands = vec![];
-                for expression_content in pair.cdr.iter() {
-                    operands.push(eval(expression_content.clone().into(), frame, false)?);
-                }
-                Ok(procedure.apply(operands, frame)?)
-            } else {
-                Err(ApplyError::InvalidProcedure(operator.to_string()))?
-            }
+            eval_form(pair, frame).map_err(|error| error.at(pair.location.as_deref()))
         }
         Some(ExpressionContent::Promise(promise)) => {
             unimplemented!()
@@ -75,6 +73,23 @@ pub fn eval(
     }
 }
 
+/// Evaluates a special form or a procedure call.
+fn eval_form(pair: &Pair, frame: &mut Frame) -> Result<Value, EvalError> {
+    if let Some(special_form) = pair.car.as_symbol().and_then(|s| SPECIAL_FORMS.get(s)) {
+        return special_form.apply(pair.cdr(), frame);
+    }
+    let operator = eval(pair.car().into(), frame, false)?;
+    if let Value::Procedure(mut procedure) = operator {
+        let mut operands = Operands::new();
+        for expression_content in pair.cdr.iter() {
+            operands.push(eval(expression_content.clone().into(), frame, false)?);
+        }
+        Ok(procedure.apply(operands, frame)?)
+    } else {
+        Err(ApplyError::InvalidProcedure(operator.to_string()))?
+    }
+}
+
 fn eval_all(expressions: Link, frame: &mut Frame) -> Result<Value, EvalError> {
     let mut result = Value::Void;
     let mut it = expressions.iter().peekable();
@@ -95,6 +110,8 @@ impl SpecialForm {
             Self::Begin => do_begin_form(args, frame),
             Self::Define => do_define_form(args, frame),
             Self::If => do_if_form(args, frame),
+            Self::Include => do_include_form(args, frame, false),
+            Self::IncludeCi => do_include_form(args, frame, true),
             Self::Lambda => do_lambda_form(args, frame, None),
             Self::Or => do_or_form(args, frame),
             Self::Quote => do_quote_form(args, frame),
@@ -187,17 +204,36 @@ fn do_if_form(args: Link, frame: &mut Frame) -> Result<Value, EvalError> {
     }
 }
 
+/// Reads the files named by `args`, relative to the working directory, and
+/// evaluates their contents in order, as `begin` would.
+fn do_include_form(args: Link, frame: &mut Frame, fold_case: bool) -> Result<Value, EvalError> {
+    validate_number_of_arguments("include", 1, usize::MAX, args.len())?;
+    let include_error = |error: Error| EvalError::Include(Box::new(error));
+    let mut expressions = vec![];
+    for arg in args.iter() {
+        let path = arg
+            .as_string()
+            .ok_or_else(|| InvalidArgument::InvalidType(arg.to_string(), "string".to_string()))?;
+        let file = File::open(path)
+            .map_err(|error| include_error(Error::Io(format!("{}: {}", path, error))))?;
+        for expression in Reader::new(path, BufReader::new(file)).with_fold_case(fold_case) {
+            expressions.push(expression.map_err(|error| include_error(error.at_end_of(path)))?);
+        }
+    }
+    let mut result = Value::Void;
+    for expression in expressions {
+        result = eval(expression, frame, false)?;
+    }
+    Ok(result)
+}
+
 fn do_lambda_form(args: Link, frame: &mut Frame, name: Option<&str>) -> Result<Value, EvalError> {
     validate_number_of_arguments("lambda", 2, usize::MAX, args.len())?;
     let params = args.as_pair().unwrap().car();
     let body = args.as_pair().unwrap().cdr();
     let formals: Result<Vec<_>, _> = params
         .iter()
-        .map(|x| {
-            x.as_symbol()
-                .ok_or(invalid_symbol(x))
-                .map(|s| s.to_string())
-        })
+        .map(|x| x.as_symbol().ok_or(invalid_symbol(x)).map(Rc::from))
         .collect();
     Ok(LambdaProcedure {
         name: name.map(|s| s.to_string()),
@@ -231,41 +267,57 @@ fn do_quote_form(args: Link, _: &mut Frame) -> Result<Value, EvalError> {
 }
 
 impl BuiltinProcedure {
-    pub fn apply(&self, args: Vec<Value>, _: &mut Frame) -> Result<Value, ApplyError> {
+    pub fn apply(&self, args: &[Value], _: &mut Frame) -> Result<Value, ApplyError> {
         (self.function)(args)
     }
 }
 
+impl EnvironmentProcedure {
+    pub fn apply(&self, args: &[Value], frame: &mut Frame) -> Result<Value, ApplyError> {
+        (self.function)(args, frame)
+    }
+}
+
 impl LambdaProcedure {
-    pub fn apply(&self, args: Vec<Value>, _: &mut Frame) -> Result<Value, EvalError> {
+    pub fn apply(&self, args: Operands, _: &mut Frame) -> Result<Value, EvalError> {
         validate_number_of_arguments(
             self.name.as_ref().map_or("#[lambda]", |s| s),
             self.formals.len(),
             self.formals.len(),
             args.len(),
         )?;
+        let _depth = limits::enter()?;
         let mut frame = self.frame.make_child();
         for (formal, actual) in self.formals.iter().zip(args) {
-            frame.define(formal, actual);
+            frame.bind(formal.clone(), actual);
         }
         eval_all(self.body.clone(), &mut frame)
     }
 }
 
+impl RandomProcedure {
+    pub fn apply(&self, args: &[Value], _: &mut Frame) -> Result<Value, ApplyError> {
+        (self.function)(args, &self.source)
+    }
+}
+
 #[cfg(target_arch = "wasm32")]
 impl GraphicProcedure {
-    pub fn apply(&mut self, args: Vec<Value>, _: &mut Frame) -> Result<Value, ApplyError> {
+    pub fn apply(&mut self, args: &[Value], _: &mut Frame) -> Result<Value, ApplyError> {
         (self.function)(args, &mut self.canvas)
     }
 }
 
 impl Procedure {
-    pub fn apply(&mut self, args: Vec<Value>, frame: &mut Frame) -> Result<Value, EvalError> {
+    /// Calls the procedure with `args`, which a lambda binds without copying.
+    pub fn apply(&mut self, args: Operands, frame: &mut Frame) -> Result<Value, EvalError> {
         match self {
-            Procedure::Builtin(builtin) => Ok(builtin.apply(args, frame)?),
+            Procedure::Builtin(builtin) => Ok(builtin.apply(&args, frame)?),
+            Procedure::Environment(environment) => Ok(environment.apply(&args, frame)?),
             Procedure::Lambda(lambda) => lambda.apply(args, frame),
             #[cfg(target_arch = "wasm32")]
-            Procedure::Graphic(graphic) => Ok(graphic.apply(args, frame)?),
+            Procedure::Graphic(graphic) => Ok(graphic.apply(&args, frame)?),
+            Procedure::Random(random) => Ok(random.apply(&args, frame)?),
         }
     }
 }
@@ -1,12 +1,48 @@
+#[cfg(not(target_arch = "wasm32"))]
+use crate::builtin::io::SAVE_IMAGE;
 use crate::{
     builtin::{
+        bitwise::{
+            ARITHMETIC_SHIFT, BITWISE_AND, BITWISE_NOT, BITWISE_OR, BITWISE_XOR, BIT_COUNT,
+            BIT_FIELD, COPY_BIT, INTEGER_LENGTH, IS_BIT_SET,
+        },
+        bytevector::{
+            BYTEVECTOR, BYTEVECTOR_APPEND, BYTEVECTOR_COPY, BYTEVECTOR_COPY_INTO,
+            BYTEVECTOR_LENGTH, BYTEVECTOR_U8_REF, BYTEVECTOR_U8_SET, IS_BYTEVECTOR,
+            MAKE_BYTEVECTOR, STRING_TO_UTF8, UTF8_TO_STRING,
+        },
+        char::{
+            CHAR_CI_EQUAL, CHAR_CI_GREATER_THAN, CHAR_CI_GREATER_THAN_OR_EQUAL, CHAR_CI_LESS_THAN,
+            CHAR_CI_LESS_THAN_OR_EQUAL, CHAR_DOWNCASE, CHAR_EQUAL, CHAR_FOLDCASE,
+            CHAR_GREATER_THAN, CHAR_GREATER_THAN_OR_EQUAL, CHAR_LESS_THAN, CHAR_LESS_THAN_OR_EQUAL,
+            CHAR_TO_INTEGER, CHAR_UPCASE, DIGIT_VALUE, INTEGER_TO_CHAR, IS_CHAR,
+            IS_CHAR_ALPHABETIC, IS_CHAR_LOWER_CASE, IS_CHAR_NUMERIC, IS_CHAR_UPPER_CASE,
+            IS_CHAR_WHITESPACE,
+        },
+        control::{CALL_WITH_VALUES, VALUES},
+        io::{DISPLAY, NEWLINE, WRITE, WRITE_SHARED, WRITE_SIMPLE},
         math::{
-            ADD, DIV, GREATER_THAN, GREATER_THAN_OR_EQUAL, LESS_THAN, LESS_THAN_OR_EQUAL,
-            MATH_EQUAL, MUL, SUB,
+            ABS, ACOS, ADD, ANGLE, ASIN, ATAN, CEILING, COS, DENOMINATOR, DIV, EXACT,
+            EXACT_INTEGER_SQRT, EXACT_TO_INEXACT, EXP, EXPT, FLOOR, FLOOR_DIVIDE, FLOOR_QUOTIENT,
+            FLOOR_REMAINDER, GCD, GREATER_THAN, GREATER_THAN_OR_EQUAL, IMAG_PART, INEXACT,
+            INEXACT_TO_EXACT, IS_COMPLEX, IS_EVEN, IS_EXACT, IS_EXACT_INTEGER, IS_FINITE,
+            IS_INEXACT, IS_INFINITE, IS_INTEGER, IS_NAN, IS_NEGATIVE, IS_NUMBER, IS_ODD,
+            IS_POSITIVE, IS_RATIONAL, IS_REAL, IS_ZERO, LCM, LESS_THAN, LESS_THAN_OR_EQUAL, LOG,
+            MAGNITUDE, MAKE_POLAR, MAKE_RECTANGULAR, MATH_EQUAL, MAX, MIN, MODULO, MUL,
+            NUMBER_TO_STRING, NUMERATOR, QUOTIENT, REAL_PART, REMAINDER, ROUND, SIN, SQRT, SQUARE,
+            STRING_TO_NUMBER, SUB, TAN, TRUNCATE, TRUNCATE_DIVIDE, TRUNCATE_QUOTIENT,
+            TRUNCATE_REMAINDER,
         },
+        numeric_vector::NUMERIC_VECTOR_PROCEDURES,
         pair::{CAR, IS_PAIR},
+        random::{
+            random_integer, random_real, IS_RANDOM_SOURCE, MAKE_RANDOM_SOURCE,
+            RANDOM_SOURCE_MAKE_INTEGERS, RANDOM_SOURCE_MAKE_REALS, RANDOM_SOURCE_PSEUDO_RANDOMIZE,
+            RANDOM_SOURCE_RANDOMIZE, RANDOM_SOURCE_STATE_REF, RANDOM_SOURCE_STATE_SET,
+        },
     },
-    data_model::Frame,
+    data_model::{Frame, RandomProcedure},
+    random::RandomSource,
 };
 
 pub fn create_global_frame() -> Frame {
@@ -22,10 +58,163 @@ pub fn create_global_frame() -> Frame {
     frame.add_builtin(LESS_THAN_OR_EQUAL);
     frame.add_builtin(GREATER_THAN);
     frame.add_builtin(GREATER_THAN_OR_EQUAL);
+    frame.add_builtin(NUMERATOR);
+    frame.add_builtin(DENOMINATOR);
+    frame.add_builtin(IS_EXACT);
+    frame.add_builtin(IS_INEXACT);
+    frame.add_builtin(EXACT);
+    frame.add_builtin(INEXACT);
+    frame.add_builtin(EXACT_TO_INEXACT);
+    frame.add_builtin(INEXACT_TO_EXACT);
+    frame.add_builtin(IS_NUMBER);
+    frame.add_builtin(IS_COMPLEX);
+    frame.add_builtin(IS_REAL);
+    frame.add_builtin(IS_RATIONAL);
+    frame.add_builtin(IS_INTEGER);
+    frame.add_builtin(IS_EXACT_INTEGER);
+    frame.add_builtin(IS_NAN);
+    frame.add_builtin(IS_INFINITE);
+    frame.add_builtin(IS_FINITE);
+    frame.add_builtin(IS_ZERO);
+    frame.add_builtin(IS_POSITIVE);
+    frame.add_builtin(IS_NEGATIVE);
+    frame.add_builtin(IS_ODD);
+    frame.add_builtin(IS_EVEN);
+    frame.add_builtin(MAX);
+    frame.add_builtin(MIN);
+    frame.add_builtin(ABS);
+    frame.add_builtin(QUOTIENT);
+    frame.add_builtin(REMAINDER);
+    frame.add_builtin(MODULO);
+    frame.add_builtin(FLOOR_DIVIDE);
+    frame.add_builtin(FLOOR_QUOTIENT);
+    frame.add_builtin(FLOOR_REMAINDER);
+    frame.add_builtin(TRUNCATE_DIVIDE);
+    frame.add_builtin(TRUNCATE_QUOTIENT);
+    frame.add_builtin(TRUNCATE_REMAINDER);
+    frame.add_builtin(GCD);
+    frame.add_builtin(LCM);
+    frame.add_builtin(FLOOR);
+    frame.add_builtin(CEILING);
+    frame.add_builtin(TRUNCATE);
+    frame.add_builtin(ROUND);
+    frame.add_builtin(EXP);
+    frame.add_builtin(LOG);
+    frame.add_builtin(SIN);
+    frame.add_builtin(COS);
+    frame.add_builtin(TAN);
+    frame.add_builtin(ASIN);
+    frame.add_builtin(ACOS);
+    frame.add_builtin(ATAN);
+    frame.add_builtin(SQUARE);
+    frame.add_builtin(SQRT);
+    frame.add_builtin(EXACT_INTEGER_SQRT);
+    frame.add_builtin(EXPT);
+    frame.add_builtin(NUMBER_TO_STRING);
+    frame.add_builtin(STRING_TO_NUMBER);
+    frame.add_builtin(MAKE_RECTANGULAR);
+    frame.add_builtin(MAKE_POLAR);
+    frame.add_builtin(REAL_PART);
+    frame.add_builtin(IMAG_PART);
+    frame.add_builtin(MAGNITUDE);
+    frame.add_builtin(ANGLE);
+
+    // bitwise builtins
+    frame.add_builtin(BITWISE_AND);
+    frame.add_builtin(BITWISE_OR);
+    frame.add_builtin(BITWISE_XOR);
+    frame.add_builtin(BITWISE_NOT);
+    frame.add_builtin(ARITHMETIC_SHIFT);
+    frame.add_builtin(BIT_COUNT);
+    frame.add_builtin(INTEGER_LENGTH);
+    frame.add_builtin(IS_BIT_SET);
+    frame.add_builtin(COPY_BIT);
+    frame.add_builtin(BIT_FIELD);
+
+    // character builtins
+    frame.add_builtin(IS_CHAR);
+    frame.add_builtin(CHAR_TO_INTEGER);
+    frame.add_builtin(INTEGER_TO_CHAR);
+    frame.add_builtin(CHAR_EQUAL);
+    frame.add_builtin(CHAR_LESS_THAN);
+    frame.add_builtin(CHAR_GREATER_THAN);
+    frame.add_builtin(CHAR_LESS_THAN_OR_EQUAL);
+    frame.add_builtin(CHAR_GREATER_THAN_OR_EQUAL);
+    frame.add_builtin(CHAR_CI_EQUAL);
+    frame.add_builtin(CHAR_CI_LESS_THAN);
+    frame.add_builtin(CHAR_CI_GREATER_THAN);
+    frame.add_builtin(CHAR_CI_LESS_THAN_OR_EQUAL);
+    frame.add_builtin(CHAR_CI_GREATER_THAN_OR_EQUAL);
+    frame.add_builtin(IS_CHAR_ALPHABETIC);
+    frame.add_builtin(IS_CHAR_NUMERIC);
+    frame.add_builtin(IS_CHAR_WHITESPACE);
+    frame.add_builtin(IS_CHAR_UPPER_CASE);
+    frame.add_builtin(IS_CHAR_LOWER_CASE);
+    frame.add_builtin(DIGIT_VALUE);
+    frame.add_builtin(CHAR_UPCASE);
+    frame.add_builtin(CHAR_DOWNCASE);
+    frame.add_builtin(CHAR_FOLDCASE);
+
+    // control builtins
+    frame.add_builtin(VALUES);
+    frame.add_environment_procedure(CALL_WITH_VALUES);
+
+    // random builtins
+    let source = RandomSource::new();
+    frame.define("default-random-source", source.clone().into());
+    frame.add_random(RandomProcedure {
+        name: "random-integer",
+        function: random_integer,
+        source: source.clone(),
+    });
+    frame.add_random(RandomProcedure {
+        name: "random-real",
+        function: random_real,
+        source,
+    });
+    frame.add_builtin(IS_RANDOM_SOURCE);
+    frame.add_builtin(MAKE_RANDOM_SOURCE);
+    frame.add_builtin(RANDOM_SOURCE_MAKE_INTEGERS);
+    frame.add_builtin(RANDOM_SOURCE_MAKE_REALS);
+    frame.add_builtin(RANDOM_SOURCE_PSEUDO_RANDOMIZE);
+    frame.add_builtin(RANDOM_SOURCE_RANDOMIZE);
+    frame.add_builtin(RANDOM_SOURCE_STATE_REF);
+    frame.add_builtin(RANDOM_SOURCE_STATE_SET);
+
+    // homogeneous vector builtins
+    for procedures in NUMERIC_VECTOR_PROCEDURES {
+        for procedure in procedures {
+            frame.add_builtin(procedure.clone());
+        }
+    }
+
+    // bytevector builtins
+    frame.add_builtin(IS_BYTEVECTOR);
+    frame.add_builtin(BYTEVECTOR);
+    frame.add_builtin(MAKE_BYTEVECTOR);
+    frame.add_builtin(BYTEVECTOR_LENGTH);
+    frame.add_builtin(BYTEVECTOR_U8_REF);
+    frame.add_builtin(BYTEVECTOR_U8_SET);
+    frame.add_builtin(BYTEVECTOR_COPY);
+    frame.add_builtin(BYTEVECTOR_COPY_INTO);
+    frame.add_builtin(BYTEVECTOR_APPEND);
+    frame.add_builtin(UTF8_TO_STRING);
+    frame.add_builtin(STRING_TO_UTF8);
 
     // pair builtins
     frame.add_builtin(IS_PAIR);
     frame.add_builtin(CAR);
 
+    // output builtins
+    frame.add_builtin(DISPLAY);
+    frame.add_builtin(NEWLINE);
+    frame.add_builtin(WRITE);
+    frame.add_builtin(WRITE_SHARED);
+    frame.add_builtin(WRITE_SIMPLE);
+
+    // image builtins
+    #[cfg(not(target_arch = "wasm32"))]
+    frame.add_environment_procedure(SAVE_IMAGE);
+
     frame
 }
@@ -0,0 +1,819 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    io::{Read, Write},
+    rc::Rc,
+};
+
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::Zero;
+
+use crate::{
+    data_model::{
+        ExpressionContent, Frame, FrameLink, FrameNode, LambdaProcedure, Link, Pair, Procedure,
+        Promise, RandomProcedure, Thunk, Value,
+    },
+    error::ImageError,
+    number::Number,
+    numeric_vector::{NumericKind, NumericVector},
+    random::RandomSource,
+};
+
+type Result<T> = std::result::Result<T, ImageError>;
+
+const MAGIC: &[u8; 6] = b"RSCMIM";
+const VERSION: u8 = 3;
+
+const VALUE_VOID: u8 = 0;
+const VALUE_EXPRESSION: u8 = 1;
+const VALUE_PRIMITIVE: u8 = 2;
+const VALUE_LAMBDA: u8 = 3;
+const VALUE_THUNK: u8 = 4;
+const VALUE_VALUES: u8 = 5;
+const VALUE_RANDOM_SOURCE: u8 = 6;
+const VALUE_RANDOM: u8 = 7;
+
+const OBJECT_NUMBER: u8 = 0;
+const OBJECT_STRING: u8 = 1;
+const OBJECT_BOOLEAN: u8 = 2;
+const OBJECT_SYMBOL: u8 = 3;
+const OBJECT_PAIR: u8 = 4;
+const OBJECT_VECTOR: u8 = 5;
+const OBJECT_PROMISE: u8 = 6;
+const OBJECT_NUMERIC_VECTOR: u8 = 7;
+const OBJECT_CHARACTER: u8 = 8;
+
+const NUMBER_INTEGER: u8 = 0;
+const NUMBER_REAL: u8 = 1;
+const NUMBER_COMPLEX: u8 = 2;
+const NUMBER_BIG_INTEGER: u8 = 3;
+const NUMBER_RATIONAL: u8 = 4;
+
+/// Primitive procedures that an image refers to by name.
+pub(crate) type Primitives = HashMap<&'static str, Procedure>;
+
+pub(crate) fn primitives(frame: &Frame) -> Primitives {
+    frame
+        .bindings()
+        .filter_map(|(_, value)| match value.as_procedure()? {
+            Procedure::Lambda(_) => None,
+            procedure => Some((primitive_name(procedure)?, procedure.clone())),
+        })
+        .collect()
+}
+
+fn primitive_name(procedure: &Procedure) -> Option<&'static str> {
+    match procedure {
+        Procedure::Builtin(builtin) => Some(builtin.name),
+        Procedure::Environment(environment) => Some(environment.name),
+        #[cfg(target_arch = "wasm32")]
+        Procedure::Graphic(graphic) => Some(graphic.name),
+        Procedure::Random(random) => Some(random.name),
+        Procedure::Lambda(_) => None,
+    }
+}
+
+/// Assigns ids to everything reachable from the global frame. Frames are
+/// numbered parents first, so that loading can rebuild them in a single pass.
+/// Objects are numbered before their elements, which may lead back to them.
+#[derive(Default)]
+struct Collector {
+    strings: Vec<Rc<str>>,
+    string_ids: HashMap<Rc<str>, u32>,
+    frames: Vec<Frame>,
+    frame_ids: HashMap<*const FrameNode, u32>,
+    bindings: Vec<Vec<(Rc<str>, Value)>>,
+    objects: Vec<Rc<ExpressionContent>>,
+    object_ids: HashMap<*const ExpressionContent, u32>,
+    lambdas: Vec<Rc<LambdaProcedure>>,
+    lambda_ids: HashMap<*const LambdaProcedure, u32>,
+    sources: Vec<RandomSource>,
+    source_ids: HashMap<*const RefCell<[u64; 4]>, u32>,
+}
+
+impl Collector {
+    fn collect(global: &Frame) -> Self {
+        let mut collector = Self::default();
+        collector.frame(global);
+        let mut index = 0;
+        while index < collector.frames.len() {
+            let mut bindings: Vec<_> = collector.frames[index]
+                .bindings()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect();
+            bindings.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+            for (name, value) in &bindings {
+                collector.string(name);
+                collector.value(value);
+            }
+            collector.bindings.push(bindings);
+            index += 1;
+        }
+        collector
+    }
+
+    fn string(&mut self, string: &str) -> u32 {
+        if let Some(id) = self.string_ids.get(string) {
+            return *id;
+        }
+        let string: Rc<str> = Rc::from(string);
+        let id = self.strings.len() as u32;
+        self.strings.push(string.clone());
+        self.string_ids.insert(string, id);
+        id
+    }
+
+    fn frame(&mut self, frame: &Frame) -> u32 {
+        let mut chain = vec![];
+        let mut next = Some(frame.clone());
+        while let Some(frame) = next {
+            if self
+                .frame_ids
+                .contains_key(&frame.content.as_ptr().cast_const())
+            {
+                break;
+            }
+            next = frame.parent();
+            chain.push(frame);
+        }
+        while let Some(frame) = chain.pop() {
+            self.frame_ids.insert(
+                frame.content.as_ptr().cast_const(),
+                self.frames.len() as u32,
+            );
+            self.frames.push(frame);
+        }
+        self.frame_ids[&frame.content.as_ptr().cast_const()]
+    }
+
+    fn link_id(&self, link: &Link) -> usize {
+        match link.resolve() {
+            Some(content) => self.object_ids[&Rc::as_ptr(content)] as usize + 1,
+            None => 0,
+        }
+    }
+
+    fn frame_id(&self, link: FrameLink) -> usize {
+        self.frame_ids[&link.as_ptr().cast_const()] as usize
+    }
+
+    fn source(&mut self, source: &RandomSource) {
+        let key = Rc::as_ptr(&source.state);
+        if !self.source_ids.contains_key(&key) {
+            self.source_ids.insert(key, self.sources.len() as u32);
+            self.sources.push(source.clone());
+        }
+    }
+
+    fn source_id(&self, source: &RandomSource) -> usize {
+        self.source_ids[&Rc::as_ptr(&source.state)] as usize
+    }
+
+    fn value(&mut self, value: &Value) {
+        match value {
+            Value::Expression(expression) => self.link(&expression.content),
+            Value::Procedure(Procedure::Lambda(lambda)) => {
+                let key = Rc::as_ptr(lambda);
+                if !self.lambda_ids.contains_key(&key) {
+                    self.link(&lambda.body);
+                    self.frame(&lambda.frame);
+                    if let Some(name) = &lambda.name {
+                        self.string(name);
+                    }
+                    for formal in &lambda.formals {
+                        self.string(formal);
+                    }
+                    self.lambda_ids.insert(key, self.lambdas.len() as u32);
+                    self.lambdas.push(lambda.clone());
+                }
+            }
+            Value::Procedure(Procedure::Random(random)) => {
+                self.string(random.name);
+                self.source(&random.source);
+            }
+            Value::Procedure(procedure) => {
+                if let Some(name) = primitive_name(procedure) {
+                    self.string(name);
+                }
+            }
+            Value::RandomSource(source) => self.source(source),
+            Value::Thunk(thunk) => {
+                self.link(&thunk.content);
+                self.frame(&thunk.frame);
+            }
+            Value::Values(values) => {
+                for value in values {
+                    self.value(value);
+                }
+            }
+            Value::Void => {}
+        }
+    }
+
+    /// Collects the objects reachable from `link`, each before its elements.
+    fn link(&mut self, link: &Link) {
+        let mut stack = vec![link.clone()];
+        while let Some(link) = stack.pop() {
+            let Some(content) = link.resolve() else {
+                continue;
+            };
+            let key = Rc::as_ptr(content);
+            if self.object_ids.contains_key(&key) {
+                continue;
+            }
+            self.object_ids.insert(key, self.objects.len() as u32);
+            self.objects.push(content.clone());
+            match content.as_ref() {
+                ExpressionContent::Symbol(symbol) => {
+                    self.string(symbol);
+                }
+                ExpressionContent::PairLink(pair) => {
+                    stack.push(pair.cdr.clone());
+                    stack.push(pair.car.clone());
+                }
+                ExpressionContent::VectorLink(vector) => {
+                    stack.extend(vector.iter().rev().cloned());
+                }
+                ExpressionContent::Promise(promise) => {
+                    self.frame(&promise.frame);
+                    stack.push(promise.content.clone());
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+struct ImageWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> ImageWriter<W> {
+    fn u8(&mut self, value: u8) -> Result<()> {
+        Ok(self.writer.write_all(&[value])?)
+    }
+
+    fn varint(&mut self, mut value: u64) -> Result<()> {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                return self.u8(byte);
+            }
+            self.u8(byte | 0x80)?;
+        }
+    }
+
+    fn usize(&mut self, value: usize) -> Result<()> {
+        self.varint(value as u64)
+    }
+
+    fn i64(&mut self, value: i64) -> Result<()> {
+        self.varint(((value << 1) ^ (value >> 63)) as u64)
+    }
+
+    fn f64(&mut self, value: f64) -> Result<()> {
+        Ok(self.writer.write_all(&value.to_le_bytes())?)
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        self.usize(bytes.len())?;
+        Ok(self.writer.write_all(bytes)?)
+    }
+}
+
+fn write_image<W: Write>(collector: &Collector, writer: &mut ImageWriter<W>) -> Result<()> {
+    let link_id = |link: &Link| collector.link_id(link);
+    let frame_id = |link: FrameLink| collector.frame_id(link);
+
+    writer.writer.write_all(MAGIC)?;
+    writer.u8(VERSION)?;
+
+    writer.usize(collector.strings.len())?;
+    for string in &collector.strings {
+        writer.bytes(string.as_bytes())?;
+    }
+
+    writer.usize(collector.frames.len())?;
+    for frame in &collector.frames {
+        match frame.parent() {
+            Some(parent) => writer.usize(frame_id(parent.content) + 1)?,
+            None => writer.usize(0)?,
+        }
+    }
+
+    writer.usize(collector.objects.len())?;
+    for object in &collector.objects {
+        match object.as_ref() {
+            ExpressionContent::Number(number) => {
+                writer.u8(OBJECT_NUMBER)?;
+                match number {
+                    Number::Integer(value) => {
+                        writer.u8(NUMBER_INTEGER)?;
+                        writer.i64(*value)?;
+                    }
+                    Number::BigInteger(value) => {
+                        writer.u8(NUMBER_BIG_INTEGER)?;
+                        writer.bytes(&value.to_signed_bytes_le())?;
+                    }
+                    Number::Rational(value) => {
+                        writer.u8(NUMBER_RATIONAL)?;
+                        writer.bytes(&value.numer().to_signed_bytes_le())?;
+                        writer.bytes(&value.denom().to_signed_bytes_le())?;
+                    }
+                    Number::Real(value) => {
+                        writer.u8(NUMBER_REAL)?;
+                        writer.f64(*value)?;
+                    }
+                    Number::Complex(real, imaginary) => {
+                        writer.u8(NUMBER_COMPLEX)?;
+                        writer.f64(*real)?;
+                        writer.f64(*imaginary)?;
+                    }
+                }
+            }
+            ExpressionContent::String(string) => {
+                writer.u8(OBJECT_STRING)?;
+                writer.bytes(string.as_bytes())?;
+            }
+            ExpressionContent::Boolean(boolean) => {
+                writer.u8(OBJECT_BOOLEAN)?;
+                writer.u8(*boolean as u8)?;
+            }
+            ExpressionContent::Character(character) => {
+                writer.u8(OBJECT_CHARACTER)?;
+                writer.varint(u64::from(*character))?;
+            }
+            ExpressionContent::Symbol(symbol) => {
+                writer.u8(OBJECT_SYMBOL)?;
+                writer.usize(collector.string_ids[symbol.as_str()] as usize)?;
+            }
+            ExpressionContent::PairLink(pair) => {
+                writer.u8(OBJECT_PAIR)?;
+                writer.usize(link_id(&pair.car))?;
+                writer.usize(link_id(&pair.cdr))?;
+            }
+            ExpressionContent::VectorLink(vector) => {
+                writer.u8(OBJECT_VECTOR)?;
+                writer.usize(vector.len())?;
+                for link in vector {
+                    writer.usize(link_id(link))?;
+                }
+            }
+            ExpressionContent::Promise(promise) => {
+                writer.u8(OBJECT_PROMISE)?;
+                writer.usize(link_id(&promise.content))?;
+                writer.usize(frame_id(promise.frame.content))?;
+            }
+            ExpressionContent::NumericVector(vector) => {
+                let vector = vector.borrow();
+                writer.u8(OBJECT_NUMERIC_VECTOR)?;
+                writer.u8(vector.kind() as u8)?;
+                writer.bytes(&vector.to_le_bytes())?;
+            }
+        }
+    }
+
+    writer.usize(collector.lambdas.len())?;
+    for lambda in &collector.lambdas {
+        match &lambda.name {
+            Some(name) => writer.usize(collector.string_ids[name.as_str()] as usize + 1)?,
+            None => writer.usize(0)?,
+        }
+        writer.usize(lambda.formals.len())?;
+        for formal in &lambda.formals {
+            writer.usize(collector.string_ids[formal] as usize)?;
+        }
+        writer.usize(link_id(&lambda.body))?;
+        writer.usize(frame_id(lambda.frame.content))?;
+    }
+
+    writer.usize(collector.sources.len())?;
+    for source in &collector.sources {
+        for word in source.get_state() {
+            writer.varint(word)?;
+        }
+    }
+
+    for bindings in &collector.bindings {
+        writer.usize(bindings.len())?;
+        for (name, value) in bindings {
+            writer.usize(collector.string_ids[name] as usize)?;
+            write_value(collector, writer, value)?;
+        }
+    }
+
+    Ok(writer.writer.flush()?)
+}
+
+fn write_value<W: Write>(
+    collector: &Collector,
+    writer: &mut ImageWriter<W>,
+    value: &Value,
+) -> Result<()> {
+    match value {
+        Value::Void => writer.u8(VALUE_VOID)?,
+        Value::Expression(expression) => {
+            writer.u8(VALUE_EXPRESSION)?;
+            writer.usize(collector.link_id(&expression.content))?;
+        }
+        Value::Procedure(Procedure::Lambda(lambda)) => {
+            writer.u8(VALUE_LAMBDA)?;
+            writer.usize(collector.lambda_ids[&Rc::as_ptr(lambda)] as usize)?;
+        }
+        Value::Procedure(Procedure::Random(random)) => {
+            writer.u8(VALUE_RANDOM)?;
+            writer.usize(collector.string_ids[random.name] as usize)?;
+            writer.usize(collector.source_id(&random.source))?;
+        }
+        Value::Procedure(procedure) => {
+            writer.u8(VALUE_PRIMITIVE)?;
+            let name = primitive_name(procedure).unwrap_or_default();
+            writer.usize(collector.string_ids[name] as usize)?;
+        }
+        Value::Thunk(thunk) => {
+            writer.u8(VALUE_THUNK)?;
+            writer.usize(collector.link_id(&thunk.content))?;
+            writer.usize(collector.frame_id(thunk.frame.content))?;
+        }
+        Value::Values(values) => {
+            writer.u8(VALUE_VALUES)?;
+            writer.usize(values.len())?;
+            for value in values {
+                write_value(collector, writer, value)?;
+            }
+        }
+        Value::RandomSource(source) => {
+            writer.u8(VALUE_RANDOM_SOURCE)?;
+            writer.usize(collector.source_id(source))?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes every value reachable from the global frame of `frame`.
+pub(crate) fn save<W: Write>(frame: &Frame, writer: W) -> Result<()> {
+    let collector = Collector::collect(&frame.global());
+    write_image(&collector, &mut ImageWriter { writer })
+}
+
+struct ImageReader<R: Read> {
+    reader: R,
+}
+
+impl<R: Read> ImageReader<R> {
+    fn u8(&mut self) -> Result<u8> {
+        let mut byte = [0];
+        self.reader.read_exact(&mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn varint(&mut self) -> Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(ImageError::Corrupt("integer too long".to_string()))
+    }
+
+    fn usize(&mut self) -> Result<usize> {
+        usize::try_from(self.varint()?)
+            .map_err(|_| ImageError::Corrupt("integer too large".to_string()))
+    }
+
+    fn i64(&mut self) -> Result<i64> {
+        let value = self.varint()?;
+        Ok(((value >> 1) as i64) ^ -((value & 1) as i64))
+    }
+
+    fn f64(&mut self) -> Result<f64> {
+        let mut bytes = [0; 8];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(f64::from_le_bytes(bytes))
+    }
+
+    fn bytes(&mut self) -> Result<Vec<u8>> {
+        let length = self.usize()?;
+        let mut bytes = vec![];
+        (&mut self.reader)
+            .take(length as u64)
+            .read_to_end(&mut bytes)?;
+        if bytes.len() != length {
+            return Err(ImageError::Corrupt("unexpected end of image".to_string()));
+        }
+        Ok(bytes)
+    }
+
+    fn string(&mut self) -> Result<String> {
+        String::from_utf8(self.bytes()?)
+            .map_err(|_| ImageError::Corrupt("invalid UTF-8 string".to_string()))
+    }
+}
+
+fn lookup<'a, T>(table: &'a [T], id: usize, kind: &str) -> Result<&'a T> {
+    table
+        .get(id)
+        .ok_or_else(|| ImageError::Corrupt(format!("invalid {} reference {}", kind, id)))
+}
+
+fn read_link(objects: &[Link], id: usize) -> Result<Link> {
+    match id {
+        0 => Ok(Link::Nil),
+        id => lookup(objects, id - 1, "object").cloned(),
+    }
+}
+
+/// An object read from an image. Pairs, vectors and promises refer to the
+/// objects they hold by id until they are built.
+enum Object {
+    Built(Link),
+    Pair([usize; 2]),
+    Vector(Vec<usize>),
+    Promise(usize, Frame),
+}
+
+impl Object {
+    fn elements(&self) -> &[usize] {
+        match self {
+            Self::Built(_) => &[],
+            Self::Pair(ids) => ids,
+            Self::Vector(ids) => ids,
+            Self::Promise(id, _) => std::slice::from_ref(id),
+        }
+    }
+}
+
+/// Builds the objects read from an image, each after its elements. An element
+/// that leads back to an object still being built links to it through a
+/// placeholder, as a datum label does.
+fn build_objects(mut objects: Vec<Object>) -> Result<Vec<Link>> {
+    let mut building = vec![false; objects.len()];
+    let mut placeholders: HashMap<usize, Link> = HashMap::new();
+    fn element(objects: &[Object], placeholders: &mut HashMap<usize, Link>, id: usize) -> Link {
+        let Some(index) = id.checked_sub(1) else {
+            return Link::Nil;
+        };
+        match &objects[index] {
+            Object::Built(link) => link.clone(),
+            _ => placeholders
+                .entry(index)
+                .or_insert_with(Link::placeholder)
+                .clone(),
+        }
+    }
+    for root in 0..objects.len() {
+        let mut stack = vec![(root, false)];
+        while let Some((index, expanded)) = stack.pop() {
+            let object = &objects[index];
+            if !expanded {
+                if building[index] || matches!(object, Object::Built(_)) {
+                    continue;
+                }
+                building[index] = true;
+                stack.push((index, true));
+                for id in object.elements().iter().rev() {
+                    if let Some(index) = id.checked_sub(1) {
+                        lookup(&objects, index, "object")?;
+                        stack.push((index, false));
+                    }
+                }
+                continue;
+            }
+            let content = match object {
+                Object::Built(_) => unreachable!("built objects are not expanded"),
+                Object::Pair([car, cdr]) => ExpressionContent::PairLink(Pair {
+                    car: element(&objects, &mut placeholders, *car),
+                    cdr: element(&objects, &mut placeholders, *cdr),
+                    location: None,
+                }),
+                Object::Vector(ids) => ExpressionContent::VectorLink(
+                    ids.iter()
+                        .map(|id| element(&objects, &mut placeholders, *id))
+                        .collect(),
+                ),
+                Object::Promise(id, frame) => ExpressionContent::Promise(Promise {
+                    content: element(&objects, &mut placeholders, *id),
+                    frame: frame.clone(),
+                }),
+            };
+            let link = Link::from(content);
+            if let Some(placeholder) = placeholders.remove(&index) {
+                placeholder.fill(link.clone());
+            }
+            building[index] = false;
+            objects[index] = Object::Built(link);
+        }
+    }
+    Ok(objects
+        .into_iter()
+        .map(|object| match object {
+            Object::Built(link) => link,
+            _ => unreachable!("every object is built"),
+        })
+        .collect())
+}
+
+/// Everything a value in the bindings section may refer to.
+struct Tables<'a> {
+    strings: &'a [Rc<str>],
+    objects: &'a [Link],
+    lambdas: &'a [Rc<LambdaProcedure>],
+    sources: &'a [RandomSource],
+    frames: &'a [Frame],
+    primitives: &'a Primitives,
+}
+
+fn read_value<R: Read>(reader: &mut ImageReader<R>, tables: &Tables) -> Result<Value> {
+    Ok(match reader.u8()? {
+        VALUE_VOID => Value::Void,
+        VALUE_EXPRESSION => read_link(tables.objects, reader.usize()?)?.into(),
+        VALUE_PRIMITIVE => {
+            let primitive = lookup(tables.strings, reader.usize()?, "string")?;
+            tables
+                .primitives
+                .get(primitive.as_ref())
+                .cloned()
+                .ok_or_else(|| ImageError::UnknownPrimitive(primitive.to_string()))?
+                .into()
+        }
+        VALUE_RANDOM => {
+            let primitive = lookup(tables.strings, reader.usize()?, "string")?;
+            let Some(Procedure::Random(random)) = tables.primitives.get(primitive.as_ref()) else {
+                return Err(ImageError::UnknownPrimitive(primitive.to_string()));
+            };
+            RandomProcedure {
+                source: lookup(tables.sources, reader.usize()?, "random source")?.clone(),
+                ..random.clone()
+            }
+            .into()
+        }
+        VALUE_RANDOM_SOURCE => lookup(tables.sources, reader.usize()?, "random source")?
+            .clone()
+            .into(),
+        VALUE_LAMBDA => {
+            Procedure::Lambda(lookup(tables.lambdas, reader.usize()?, "lambda")?.clone()).into()
+        }
+        VALUE_THUNK => Thunk {
+            content: read_link(tables.objects, reader.usize()?)?,
+            frame: lookup(tables.frames, reader.usize()?, "frame")?.clone(),
+        }
+        .into(),
+        VALUE_VALUES => {
+            let mut values = vec![];
+            for _ in 0..reader.usize()? {
+                values.push(read_value(reader, tables)?);
+            }
+            Value::Values(values)
+        }
+        tag => return Err(ImageError::Corrupt(format!("invalid value tag {}", tag))),
+    })
+}
+
+/// Rebuilds a global frame from an image, binding primitives by name.
+pub(crate) fn load<R: Read>(reader: R, primitives: &Primitives) -> Result<Frame> {
+    let mut reader = ImageReader { reader };
+    let mut magic = [0; MAGIC.len()];
+    reader
+        .reader
+        .read_exact(&mut magic)
+        .map_err(|_| ImageError::InvalidMagic)?;
+    if &magic != MAGIC {
+        return Err(ImageError::InvalidMagic);
+    }
+    let version = reader.u8()?;
+    if version != VERSION {
+        return Err(ImageError::UnsupportedVersion(version));
+    }
+
+    let mut strings: Vec<Rc<str>> = vec![];
+    for _ in 0..reader.usize()? {
+        strings.push(Rc::from(reader.string()?));
+    }
+
+    let mut frames: Vec<Frame> = vec![];
+    for _ in 0..reader.usize()? {
+        let frame = match reader.usize()? {
+            0 => Frame::new(),
+            parent => Frame::new_with_parent(lookup(&frames, parent - 1, "frame")?),
+        };
+        frames.push(frame);
+    }
+    if frames.is_empty() {
+        return Err(ImageError::Corrupt("missing global frame".to_string()));
+    }
+
+    let mut objects: Vec<Object> = vec![];
+    for _ in 0..reader.usize()? {
+        let content = match reader.u8()? {
+            OBJECT_NUMBER => ExpressionContent::Number(match reader.u8()? {
+                NUMBER_INTEGER => Number::Integer(reader.i64()?),
+                NUMBER_BIG_INTEGER => BigInt::from_signed_bytes_le(&reader.bytes()?).into(),
+                NUMBER_RATIONAL => {
+                    let numerator = BigInt::from_signed_bytes_le(&reader.bytes()?);
+                    let denominator = BigInt::from_signed_bytes_le(&reader.bytes()?);
+                    if denominator.is_zero() {
+                        return Err(ImageError::Corrupt("zero denominator".to_string()));
+                    }
+                    BigRational::new(numerator, denominator).into()
+                }
+                NUMBER_REAL => Number::Real(reader.f64()?),
+                NUMBER_COMPLEX => Number::Complex(reader.f64()?, reader.f64()?),
+                tag => return Err(ImageError::Corrupt(format!("invalid number tag {}", tag))),
+            }),
+            OBJECT_STRING => ExpressionContent::String(reader.string()?),
+            OBJECT_BOOLEAN => ExpressionContent::Boolean(reader.u8()? != 0),
+            OBJECT_CHARACTER => u32::try_from(reader.varint()?)
+                .ok()
+                .and_then(char::from_u32)
+                .map(ExpressionContent::Character)
+                .ok_or_else(|| ImageError::Corrupt("invalid character".to_string()))?,
+            OBJECT_SYMBOL => {
+                ExpressionContent::Symbol(lookup(&strings, reader.usize()?, "string")?.to_string())
+            }
+            OBJECT_PAIR => {
+                objects.push(Object::Pair([reader.usize()?, reader.usize()?]));
+                continue;
+            }
+            OBJECT_VECTOR => {
+                let mut ids = vec![];
+                for _ in 0..reader.usize()? {
+                    ids.push(reader.usize()?);
+                }
+                objects.push(Object::Vector(ids));
+                continue;
+            }
+            OBJECT_PROMISE => {
+                let id = reader.usize()?;
+                let frame = lookup(&frames, reader.usize()?, "frame")?.clone();
+                objects.push(Object::Promise(id, frame));
+                continue;
+            }
+            OBJECT_NUMERIC_VECTOR => {
+                let kind = match NumericKind::ALL.get(reader.u8()? as usize) {
+                    Some(kind) => *kind,
+                    None => return Err(ImageError::Corrupt("invalid vector kind".to_string())),
+                };
+                NumericVector::from_le_bytes(kind, &reader.bytes()?)
+                    .ok_or_else(|| ImageError::Corrupt("truncated vector".to_string()))?
+                    .into()
+            }
+            tag => return Err(ImageError::Corrupt(format!("invalid object tag {}", tag))),
+        };
+        objects.push(Object::Built(content.into()));
+    }
+    let objects = build_objects(objects)?;
+
+    let mut lambdas: Vec<Rc<LambdaProcedure>> = vec![];
+    for _ in 0..reader.usize()? {
+        let name = match reader.usize()? {
+            0 => None,
+            id => Some(lookup(&strings, id - 1, "string")?.to_string()),
+        };
+        let mut formals = vec![];
+        for _ in 0..reader.usize()? {
+            formals.push(lookup(&strings, reader.usize()?, "string")?.clone());
+        }
+        lambdas.push(Rc::new(LambdaProcedure {
+            name,
+            formals,
+            body: read_link(&objects, reader.usize()?)?,
+            frame: lookup(&frames, reader.usize()?, "frame")?.clone(),
+        }));
+    }
+
+    let mut sources: Vec<RandomSource> = vec![];
+    for _ in 0..reader.usize()? {
+        let mut state = [0; 4];
+        for word in &mut state {
+            *word = reader.varint()?;
+        }
+        let source = RandomSource::new();
+        source
+            .set_state(state)
+            .map_err(|_| ImageError::Corrupt("invalid random source state".to_string()))?;
+        sources.push(source);
+    }
+
+    for index in 0..frames.len() {
+        for _ in 0..reader.usize()? {
+            let name = lookup(&strings, reader.usize()?, "string")?.clone();
+            let tables = Tables {
+                strings: &strings,
+                objects: &objects,
+                lambdas: &lambdas,
+                sources: &sources,
+                frames: &frames,
+                primitives,
+            };
+            let value = read_value(&mut reader, &tables)?;
+            frames[index].bind(name, value);
+        }
+    }
+
+    Ok(frames.swap_remove(0))
+}
@@ -1,11 +1,534 @@
+use std::io::{Read, Write};
+
 use crate::{
     data_model::{Frame, Value},
-    error::Error,
-    eval, parse, tokenize,
+    error::{Error, ImageError},
+    eval,
+    frame::create_global_frame,
+    image::{self, Primitives},
+    limits::{BudgetGuard, InterruptHandle, Limits},
+    reader::Reader,
+    span::Source,
 };
 
+/// The name errors in input without one are located under.
+const INPUT: &str = "<input>";
+
 pub fn interpret(input: &str, frame: &mut Frame) -> Result<Value, Error> {
-    let mut tokens = tokenize(input)?;
-    let expression = parse(&mut tokens)?;
-    Ok(eval(expression, frame, false)?)
+    interpret_source(Source::new(INPUT, input), frame, &mut false)
+}
+
+/// Reads every datum of `source` before evaluating them in order, so that
+/// input with a syntax error or an unfinished datum has no effect. `fold_case`
+/// is updated by the `#!fold-case` and `#!no-fold-case` directives read.
+fn interpret_source(
+    source: Source,
+    frame: &mut Frame,
+    fold_case: &mut bool,
+) -> Result<Value, Error> {
+    let mut reader = Reader::from_text(source.name, source.text).with_fold_case(*fold_case);
+    let expressions = reader.by_ref().collect::<Result<Vec<_>, _>>()?;
+    *fold_case = reader.fold_case();
+    let mut value = Value::Void;
+    for expression in expressions {
+        value = eval(expression, frame, false)?;
+    }
+    Ok(value)
+}
+
+pub struct Interpreter {
+    frame: Frame,
+    primitives: Primitives,
+    limits: Limits,
+    interrupt: InterruptHandle,
+    /// Whether identifiers are folded to lower case, which a directive in one
+    /// input changes for the inputs after it.
+    fold_case: bool,
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        Self::with_frame(create_global_frame())
+    }
+
+    pub fn with_limits(limits: Limits) -> Self {
+        let mut interpreter = Self::new();
+        interpreter.limits = limits;
+        interpreter
+    }
+
+    pub(crate) fn with_frame(frame: Frame) -> Self {
+        Self {
+            primitives: image::primitives(&frame),
+            frame,
+            limits: Limits::default(),
+            interrupt: InterruptHandle::new(),
+            fold_case: false,
+        }
+    }
+
+    pub fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        self.interrupt.clone()
+    }
+
+    /// Writes the global environment, including closures and the frames they
+    /// capture, as a binary image.
+    pub fn save_image<W: Write>(&self, writer: W) -> Result<(), ImageError> {
+        image::save(&self.frame, writer)
+    }
+
+    /// Replaces the global environment with one saved by
+    /// [`save_image`](Self::save_image).
+    pub fn load_image<R: Read>(&mut self, reader: R) -> Result<(), ImageError> {
+        self.frame = image::load(reader, &self.primitives)?;
+        Ok(())
+    }
+
+    pub fn eval(&mut self, input: &str) -> Result<Value, Error> {
+        self.eval_source(Source::new(INPUT, input))
+    }
+
+    /// Evaluates the text of `source`, locating errors under its name.
+    pub fn eval_source(&mut self, source: Source) -> Result<Value, Error> {
+        // an interrupt requested while idle must not abort this evaluation
+        self.interrupt.clear();
+        let _budget = BudgetGuard::install(&self.limits, Some(&self.interrupt));
+        interpret_source(source, &mut self.frame, &mut self.fold_case)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{error::LimitError, limits::DEFAULT_MAX_STACK};
+
+    #[test]
+    fn test_step_limit() {
+        let mut interpreter = Interpreter::with_limits(Limits {
+            max_steps: Some(1000),
+            ..Limits::default()
+        });
+        interpreter
+            .eval("(define (loop n) (loop (+ n 1)))")
+            .unwrap();
+        assert_eq!(
+            interpreter.eval("(loop 0)"),
+            Err(Error::LimitExceeded(LimitError::Steps(1000)))
+        );
+        assert_eq!(interpreter.eval("(+ 1 2)").unwrap().to_string(), "3");
+    }
+
+    #[test]
+    fn test_fold_case_directive() {
+        let mut interpreter = Interpreter::new();
+        interpreter.eval("(define Answer 42) #!fold-case").unwrap();
+        interpreter.eval("(DEFINE X 1)").unwrap();
+        assert_eq!(interpreter.eval("(+ x 1)").unwrap().to_string(), "2");
+        assert!(interpreter.eval("Answer").is_err());
+        interpreter.eval("#!no-fold-case").unwrap();
+        assert_eq!(interpreter.eval("Answer").unwrap().to_string(), "42");
+        // input that fails to read changes nothing
+        assert!(interpreter.eval("#!fold-case )").is_err());
+        assert_eq!(interpreter.eval("Answer").unwrap().to_string(), "42");
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        let mut interpreter = Interpreter::with_limits(Limits {
+            max_depth: Some(100),
+            // a debug build runs out of the default stack budget first
+            max_stack: None,
+            ..Limits::default()
+        });
+        interpreter
+            .eval("(define (count n) (if (= n 0) 0 (+ 1 (count (- n 1)))))")
+            .unwrap();
+        assert_eq!(interpreter.eval("(count 99)").unwrap().to_string(), "99");
+        assert_eq!(
+            interpreter.eval("(count 100)"),
+            Err(Error::LimitExceeded(LimitError::Depth(100)))
+        );
+        assert_eq!(interpreter.eval("(count 50)").unwrap().to_string(), "50");
+    }
+
+    #[test]
+    fn test_stack_limit() {
+        // the default limits, on the 2 MiB stack of a test thread
+        let mut interpreter = Interpreter::new();
+        let exceeded = Err(Error::LimitExceeded(LimitError::Stack(DEFAULT_MAX_STACK)));
+        interpreter
+            .eval("(define (count n) (if (= n 0) 0 (+ 1 (count (- n 1)))))")
+            .unwrap();
+        assert_eq!(interpreter.eval("(count 100000)"), exceeded);
+        let nested = format!("{}0{}", "(+ 1 ".repeat(100000), ")".repeat(100000));
+        assert_eq!(interpreter.eval(&nested), exceeded);
+        assert_eq!(interpreter.eval("(count 10)").unwrap().to_string(), "10");
+    }
+
+    #[test]
+    fn test_heap_limit() {
+        let mut interpreter = Interpreter::with_limits(Limits {
+            max_heap: Some(64 * 1024),
+            ..Limits::default()
+        });
+        interpreter
+            .eval("(define (fib n) (if (< n 2) n (+ (fib (- n 1)) (fib (- n 2)))))")
+            .unwrap();
+        assert_eq!(
+            interpreter.eval("(fib 25)"),
+            Err(Error::LimitExceeded(LimitError::Heap(64 * 1024)))
+        );
+        assert_eq!(interpreter.eval("(fib 5)").unwrap().to_string(), "5");
+        // checked before allocating, not after
+        assert_eq!(
+            interpreter.eval("(make-bytevector 10000000000000)"),
+            Err(Error::LimitExceeded(LimitError::Heap(64 * 1024)))
+        );
+        assert_eq!(
+            interpreter
+                .eval("(bytevector-length (make-bytevector 1000))")
+                .unwrap()
+                .to_string(),
+            "1000"
+        );
+        interpreter.eval("(define v (make-bytevector 20000))").unwrap();
+        for input in ["(bytevector-append v v v v)", "(u8vector->list v)"] {
+            assert_eq!(
+                interpreter.eval(input),
+                Err(Error::LimitExceeded(LimitError::Heap(64 * 1024)))
+            );
+        }
+    }
+
+    #[test]
+    fn test_interrupt() {
+        let mut interpreter = Interpreter::new();
+        interpreter
+            .eval("(define (fib n) (if (< n 2) n (+ (fib (- n 1)) (fib (- n 2)))))")
+            .unwrap();
+        let handle = interpreter.interrupt_handle();
+        let interrupter = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            handle.interrupt();
+        });
+        assert_eq!(interpreter.eval("(fib 40)"), Err(Error::Interrupted));
+        interrupter.join().unwrap();
+        assert_eq!(interpreter.eval("(fib 10)").unwrap().to_string(), "55");
+    }
+
+    #[test]
+    fn test_image() {
+        let mut interpreter = Interpreter::new();
+        interpreter
+            .eval("(define (make-adder n) (lambda (x) (+ x n)))")
+            .unwrap();
+        interpreter.eval("(define add3 (make-adder 3))").unwrap();
+        interpreter
+            .eval("(define data '(1 2.5 \"three\" #(four #t)))")
+            .unwrap();
+        interpreter.eval("(define first car)").unwrap();
+        let mut image = vec![];
+        interpreter.save_image(&mut image).unwrap();
+
+        let mut restored = Interpreter::new();
+        restored.load_image(image.as_slice()).unwrap();
+        assert_eq!(restored.eval("(add3 4)").unwrap().to_string(), "7");
+        assert_eq!(
+            restored.eval("data").unwrap().to_string(),
+            "(1 2.5 \"three\" #(four #t))"
+        );
+        assert_eq!(restored.eval("(first data)").unwrap().to_string(), "1");
+        assert_eq!(
+            restored.eval("((make-adder 10) 5)").unwrap().to_string(),
+            "15"
+        );
+
+        let mut resaved = vec![];
+        restored.save_image(&mut resaved).unwrap();
+        assert_eq!(image, resaved);
+    }
+
+    #[test]
+    fn test_circular_data() {
+        let mut interpreter = Interpreter::new();
+        interpreter.eval("(define x '#0=(a . #0#))").unwrap();
+        assert_eq!(interpreter.eval("x").unwrap().to_string(), "#0=(a . #0#)");
+        assert!(matches!(
+            interpreter.eval("#0=(+ 1 #0#)"),
+            Err(Error::LimitExceeded(LimitError::Stack(_)))
+        ));
+    }
+
+    #[test]
+    fn test_circular_image() {
+        let mut interpreter = Interpreter::new();
+        interpreter.eval("(define x '#0=(a b . #0#))").unwrap();
+        interpreter
+            .eval("(define v '#0=#(1 #0# (2 . #0#)))")
+            .unwrap();
+        let mut image = vec![];
+        interpreter.save_image(&mut image).unwrap();
+
+        let mut restored = Interpreter::new();
+        restored.load_image(image.as_slice()).unwrap();
+        assert_eq!(restored.eval("x").unwrap().to_string(), "#0=(a b . #0#)");
+        assert_eq!(
+            restored.eval("v").unwrap().to_string(),
+            "#0=#(1 #0# (2 . #0#))"
+        );
+        assert_eq!(restored.eval("(car x)").unwrap().to_string(), "a");
+
+        let mut resaved = vec![];
+        restored.save_image(&mut resaved).unwrap();
+        assert_eq!(image, resaved);
+    }
+
+    #[test]
+    fn test_random_image() {
+        let mut interpreter = Interpreter::new();
+        interpreter.eval("(define s (make-random-source))").unwrap();
+        interpreter
+            .eval("(random-source-pseudo-randomize! s 3 4)")
+            .unwrap();
+        interpreter
+            .eval("(define r (random-source-make-integers s))")
+            .unwrap();
+        interpreter.eval("(random-integer 100)").unwrap();
+        let mut image = vec![];
+        interpreter.save_image(&mut image).unwrap();
+
+        let mut restored = Interpreter::new();
+        restored.load_image(image.as_slice()).unwrap();
+        for input in ["(random-integer 1000000)", "(r 1000000)", "(random-real)"] {
+            assert_eq!(
+                restored.eval(input).unwrap(),
+                interpreter.eval(input).unwrap()
+            );
+        }
+        // r and s still share one generator after loading.
+        restored
+            .eval("(define state (random-source-state-ref s))")
+            .unwrap();
+        let drawn = restored.eval("(r 1000000)").unwrap();
+        restored.eval("(random-source-state-set! s state)").unwrap();
+        assert_eq!(restored.eval("(r 1000000)").unwrap(), drawn);
+    }
+
+    #[test]
+    fn test_numeric_vectors() {
+        let mut interpreter = Interpreter::new();
+        let eval =
+            |interpreter: &mut Interpreter, input| interpreter.eval(input).unwrap().to_string();
+        interpreter
+            .eval("(define v (make-f64vector 3 0.5))")
+            .unwrap();
+        interpreter.eval("(f64vector-set! v 1 2)").unwrap();
+        assert_eq!(eval(&mut interpreter, "v"), "#f64(0.5 2.0 0.5)");
+        assert_eq!(eval(&mut interpreter, "(f64vector->list v 1)"), "(2.0 0.5)");
+        assert_eq!(
+            eval(
+                &mut interpreter,
+                "(s16vector-append #s16(1) (list->s16vector '(-2 3)))"
+            ),
+            "#s16(1 -2 3)"
+        );
+        assert_eq!(
+            eval(
+                &mut interpreter,
+                "(u8vector->vector (vector->u8vector #(4 5 6) 1))"
+            ),
+            "#(5 6)"
+        );
+        assert_eq!(eval(&mut interpreter, "(u8? 256)"), "#f");
+        assert_eq!(eval(&mut interpreter, "(s8vector? #u8())"), "#f");
+        assert!(interpreter.eval("(u8vector 1 -1)").is_err());
+        assert!(interpreter.eval("(f64vector-ref v 3)").is_err());
+
+        interpreter
+            .eval("(define w (u32vector 1 2 3 4 5))")
+            .unwrap();
+        interpreter.eval("(u32vector-copy! w 1 w 0 3)").unwrap();
+        interpreter.eval("(u32vector-fill! w 9 4)").unwrap();
+        assert_eq!(eval(&mut interpreter, "w"), "#u32(1 1 2 3 9)");
+        assert_eq!(
+            eval(&mut interpreter, "(u32vector= w (u32vector-copy w))"),
+            "#t"
+        );
+
+        // Rust code sees the elements directly.
+        let v = interpreter.eval("v").unwrap();
+        assert_eq!(*v.as_numeric_slice::<f64>().unwrap(), [0.5, 2.0, 0.5]);
+        assert!(v.as_numeric_slice::<f32>().is_none());
+        v.as_numeric_slice_mut::<f64>().unwrap()[0] = 4.0;
+        assert_eq!(eval(&mut interpreter, "(f64vector-ref v 0)"), "4.0");
+        assert_eq!(Value::from(vec![1i8, -1]).to_string(), "#s8(1 -1)");
+
+        let mut image = vec![];
+        interpreter.save_image(&mut image).unwrap();
+        let mut restored = Interpreter::new();
+        restored.load_image(image.as_slice()).unwrap();
+        assert_eq!(eval(&mut restored, "v"), "#f64(4.0 2.0 0.5)");
+        assert_eq!(eval(&mut restored, "w"), "#u32(1 1 2 3 9)");
+    }
+
+    #[test]
+    fn test_bytevectors() {
+        let mut interpreter = Interpreter::new();
+        let mut eval = |input| interpreter.eval(input).unwrap().to_string();
+        assert_eq!(eval("(bytevector 1 2 3)"), "#u8(1 2 3)");
+        assert_eq!(eval("(bytevector? #u8())"), "#t");
+        assert_eq!(eval("(make-bytevector 2 7)"), "#u8(7 7)");
+        assert_eq!(
+            eval("(bytevector-append #u8(1) #u8() #u8(2 3))"),
+            "#u8(1 2 3)"
+        );
+        assert_eq!(eval("(bytevector-copy #u8(1 2 3 4) 1 3)"), "#u8(2 3)");
+        eval("(define b (make-bytevector 4 0))");
+        eval("(bytevector-u8-set! b 0 255)");
+        eval("(bytevector-copy! b 1 #u8(9 8 7) 1)");
+        assert_eq!(eval("b"), "#u8(255 8 7 0)");
+        assert_eq!(eval("(bytevector-u8-ref b 1)"), "8");
+        assert_eq!(eval("(bytevector-length b)"), "4");
+        assert_eq!(eval(r#"(string->utf8 "aλb" 1)"#), "#u8(206 187 98)");
+        assert_eq!(eval("(utf8->string #u8(206 187 98))"), r#""λb""#);
+        assert!(interpreter.eval("(utf8->string #u8(206))").is_err());
+        assert!(interpreter.eval("(bytevector-u8-set! b 0 256)").is_err());
+    }
+
+    #[test]
+    fn test_invalid_image() {
+        let mut interpreter = Interpreter::new();
+        assert_eq!(
+            interpreter.load_image(&b"not an image"[..]),
+            Err(ImageError::InvalidMagic)
+        );
+        let mut image = vec![];
+        interpreter.save_image(&mut image).unwrap();
+        image.truncate(image.len() / 2);
+        assert!(interpreter.load_image(image.as_slice()).is_err());
+        assert_eq!(interpreter.eval("(+ 1 2)").unwrap().to_string(), "3");
+    }
+
+    #[test]
+    fn test_multiple_datums() {
+        let mut interpreter = Interpreter::new();
+        assert_eq!(
+            interpreter
+                .eval("(define x 1) ; one\n(define y 2)\n(+ x y)")
+                .unwrap()
+                .to_string(),
+            "3"
+        );
+        assert!(interpreter
+            .eval("(define z 1) (+ z")
+            .unwrap_err()
+            .is_incomplete());
+        assert!(interpreter.eval("z").is_err());
+        assert_eq!(interpreter.eval("").unwrap(), Value::Void);
+    }
+
+    #[test]
+    fn test_include() {
+        let path = std::env::temp_dir().join(format!("include-{}.scm", std::process::id()));
+        std::fs::write(&path, "(define (Twice x) (* 2 x))\n(define Answer 21)\n").unwrap();
+        let path = path.to_str().unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.eval(&format!("(include {:?})", path)).unwrap();
+        assert_eq!(
+            interpreter.eval("(Twice Answer)").unwrap().to_string(),
+            "42"
+        );
+        assert!(interpreter.eval("(twice answer)").is_err());
+        interpreter
+            .eval(&format!("(include-ci {:?})", path))
+            .unwrap();
+        assert_eq!(
+            interpreter.eval("(twice answer)").unwrap().to_string(),
+            "42"
+        );
+        std::fs::write(path, "(define x\n  (+ 1 y))").unwrap();
+        let error = interpreter
+            .eval(&format!("(include {:?})", path))
+            .unwrap_err();
+        assert_eq!(
+            error.location().unwrap().to_string(),
+            format!("{}:2:3", path)
+        );
+        std::fs::write(path, "(define x").unwrap();
+        let error = interpreter
+            .eval(&format!("(include {:?})", path))
+            .unwrap_err();
+        assert_eq!(
+            error.location().unwrap().to_string(),
+            format!("{}:1:1", path)
+        );
+        std::fs::remove_file(path).unwrap();
+        let error = interpreter
+            .eval(&format!("(include {:?})", path))
+            .unwrap_err();
+        assert!(matches!(error, Error::Located(error, _) if matches!(*error, Error::Io(_))));
+    }
+
+    #[test]
+    fn test_locations() {
+        let mut interpreter = Interpreter::new();
+        let position = |error: Error| {
+            let location = error.location().expect("the error is located").clone();
+            (location.span.line, location.span.column)
+        };
+        assert_eq!(
+            position(interpreter.eval("(+ 1 #asd)").unwrap_err()),
+            (1, 6)
+        );
+        assert_eq!(
+            position(interpreter.eval("\n  (+ 1\n  (+ 2)").unwrap_err()),
+            (2, 3)
+        );
+        assert_eq!(position(interpreter.eval("(car 1 2)").unwrap_err()), (1, 1));
+        interpreter
+            .eval_source(Source::new("lib.scm", "(define (f x)\n  (+ x y))"))
+            .unwrap();
+        let error = interpreter.eval("(+ (f 1) 1)").unwrap_err();
+        assert_eq!(error.to_string(), "lib.scm:2:3: unknown identifier: y");
+        assert_eq!(
+            error.render(),
+            "unknown identifier: y\n --> lib.scm:2:3\n  |\n2 |   (+ x y))\n  |   ^^^^^^^"
+        );
+        let error = interpreter.eval("(f 1 2)").unwrap_err();
+        assert_eq!(error.location().unwrap().to_string(), "<input>:1:1");
+        assert_eq!(interpreter.eval("y").unwrap_err().location(), None);
+    }
+
+    #[test]
+    fn test_timeout() {
+        let timeout = Duration::from_millis(50);
+        let mut interpreter = Interpreter::with_limits(Limits {
+            timeout: Some(timeout),
+            ..Limits::default()
+        });
+        interpreter
+            .eval("(define (fib n) (if (< n 2) n (+ (fib (- n 1)) (fib (- n 2)))))")
+            .unwrap();
+        assert_eq!(
+            interpreter.eval("(fib 40)"),
+            Err(Error::LimitExceeded(LimitError::Timeout(timeout)))
+        );
+        assert_eq!(interpreter.eval("(fib 10)").unwrap().to_string(), "55");
+    }
 }
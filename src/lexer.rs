@@ -1,9 +1,16 @@
 use std::{
     fmt::Display,
     ops::{Deref, Index, Range, RangeInclusive},
+    sync::Arc,
 };
 
-use crate::error::TokenError;
+use crate::{
+    builtin::char::digit_value,
+    error::{Error, TokenError},
+    number::Number,
+    numeric_vector::NumericKind,
+    span::{Location, Locator, Source, Span},
+};
 
 type Result<T> = std::result::Result<T, TokenError>;
 
@@ -17,7 +24,11 @@ const COMMA: &'static str = ",";
 const COMMA_AT: &'static str = ",@";
 const DOT: &'static str = ".";
 const COMMENT: &'static str = ";";
+const DATUM_COMMENT: &str = "#;";
+const FOLD_CASE: &str = "!fold-case";
+const NO_FOLD_CASE: &str = "!no-fold-case";
 const STRING: &'static str = "\"";
+const VERTICAL_LINE: &str = "|";
 
 include!(concat!(env!("OUT_DIR"), "/tokens.rs"));
 
@@ -26,12 +37,23 @@ pub enum Token<'a> {
     Identifier(&'a str),
     Boolean(&'a str),
     Number(&'a str),
+    Character(&'a str),
     String(&'a str),
     Comment(&'a str),
+    /// `#;`, which comments out the datum that follows it.
+    DatumComment,
+    /// `#!fold-case` or `#!no-fold-case`.
+    Directive(&'a str),
+    /// `#n=`, which labels the datum that follows it.
+    DatumLabel(&'a str),
+    /// `#n#`, which stands for the datum labelled `#n=`.
+    DatumReference(&'a str),
     OpenParenthesis,
     CloseParenthesis,
     VectorOpen,
     ByteVectorOpen,
+    /// `#s16(`, `#f64(` and the other SRFI 160 vector prefixes but `#u8(`.
+    NumericVectorOpen(&'a str),
     Quote,
     BackQuote,
     Comma,
@@ -45,12 +67,18 @@ impl<'a> Token<'a> {
             Self::Identifier(string) => string,
             Self::Boolean(string) => string,
             Self::Number(string) => string,
+            Self::Character(string) => string,
             Self::String(string) => string,
             Self::Comment(string) => string,
+            Self::DatumComment => DATUM_COMMENT,
+            Self::Directive(string) => string,
+            Self::DatumLabel(string) => string,
+            Self::DatumReference(string) => string,
             Self::OpenParenthesis => OPEN_PARENTHESIS,
             Self::CloseParenthesis => CLOSE_PARENTHESIS,
             Self::VectorOpen => VECTOR_OPEN,
             Self::ByteVectorOpen => BYTE_VECTOR_OPEN,
+            Self::NumericVectorOpen(string) => string,
             Self::Quote => QUOTE,
             Self::BackQuote => BACKQUOTE,
             Self::Comma => COMMA,
@@ -167,13 +195,22 @@ impl<'a> Index<RangeInclusive<usize>> for Buffer<'a> {
 #[derive(Debug, Clone)]
 pub struct TokenBuffer<'a> {
     buffer: Vec<Token<'a>>,
+    spans: Vec<Span>,
+    /// The source the tokens were read from, if they are to be located.
+    source: Option<Arc<Source>>,
+    /// Whether identifiers and character names are folded to lower case, as
+    /// set by `#!fold-case` and `#!no-fold-case`.
+    fold_case: bool,
     index: usize,
 }
 
 impl<'a> TokenBuffer<'a> {
-    pub fn new(tokens: Vec<Token<'a>>) -> Self {
+    pub fn new(tokens: Vec<Token<'a>>, spans: Vec<Span>) -> Self {
         Self {
             buffer: tokens,
+            spans,
+            source: None,
+            fold_case: false,
             index: 0,
         }
     }
@@ -188,9 +225,83 @@ impl<'a> TokenBuffer<'a> {
         token
     }
 
+    /// Puts back the token popped last.
+    pub(crate) fn unpop(&mut self) {
+        self.index -= 1;
+    }
+
     pub fn is_empty(&self) -> bool {
         self.index >= self.buffer.len()
     }
+
+    /// Whether a datum is left to parse, rather than nothing but comments,
+    /// directives and datums commented out with `#;`. A `#;` whose datum is
+    /// unfinished counts as a datum, so that parsing reports it.
+    pub(crate) fn has_datum(&self) -> bool {
+        // the datums still to be commented out, and the depth of nesting
+        let mut commented = 0;
+        let mut depth = 0;
+        for token in &self.buffer[self.index..] {
+            match token {
+                Token::Comment(_) | Token::Directive(_) => {}
+                Token::DatumComment => commented += 1,
+                _ if commented == 0 => return true,
+                Token::Quote
+                | Token::BackQuote
+                | Token::Comma
+                | Token::CommaAt
+                | Token::DatumLabel(_) => {}
+                Token::OpenParenthesis
+                | Token::VectorOpen
+                | Token::ByteVectorOpen
+                | Token::NumericVectorOpen(_) => depth += 1,
+                Token::CloseParenthesis if depth == 0 => return true,
+                Token::CloseParenthesis => {
+                    depth -= 1;
+                    if depth == 0 {
+                        commented -= 1;
+                    }
+                }
+                Token::Dot if depth == 0 => return true,
+                _ if depth == 0 => commented -= 1,
+                _ => {}
+            }
+        }
+        commented > 0
+    }
+
+    pub fn fold_case(&self) -> bool {
+        self.fold_case
+    }
+
+    pub fn set_fold_case(&mut self, fold_case: bool) {
+        self.fold_case = fold_case;
+    }
+
+    /// The byte offset of the next token, if there is one.
+    pub(crate) fn next_start(&self) -> Option<usize> {
+        self.spans.get(self.index).map(|span| span.start)
+    }
+
+    /// Skips the tokens that start before the byte offset `offset`.
+    pub(crate) fn skip_to(&mut self, offset: usize) {
+        while self.next_start().is_some_and(|start| start < offset) {
+            self.index += 1;
+        }
+    }
+
+    /// The span of the token popped last.
+    pub fn span(&self) -> Option<Span> {
+        self.index.checked_sub(1).map(|index| self.spans[index])
+    }
+
+    /// Places `span` in the source of the tokens, if they have one.
+    pub(crate) fn locate(&self, span: Span) -> Option<Location> {
+        self.source.as_ref().map(|source| Location {
+            source: source.clone(),
+            span,
+        })
+    }
 }
 
 fn read_until_delimiter<'a>(buffer: &Buffer<'a>, start_index: usize) -> (&'a str, usize) {
@@ -225,6 +336,54 @@ fn read_comment<'a>(
     Ok((Token::Comment(&buffer.src[start_index..index]), index))
 }
 
+/// Reads a `#| ... |#` comment, which may nest.
+fn read_block_comment<'a>(
+    buffer: &Buffer<'a>,
+    start_index: usize,
+    mut index: usize,
+) -> Result<(Token<'a>, usize)> {
+    let mut depth = 1;
+    while index < buffer.length {
+        let (character, _, end) = buffer.get(index);
+        let (next, _, next_end) = buffer.get(end);
+        match (character, next) {
+            ("|", "#") => {
+                depth -= 1;
+                index = next_end;
+                if depth == 0 {
+                    return Ok((Token::Comment(&buffer.src[start_index..index]), index));
+                }
+            }
+            ("#", "|") => {
+                depth += 1;
+                index = next_end;
+            }
+            _ => index = end,
+        }
+    }
+    Err(TokenError::MissingCloseComment)
+}
+
+/// Whether a non-ASCII character may be part of an identifier. R7RS allows
+/// letters, marks, numbers, punctuation and symbols, which leaves out only
+/// whitespace and control characters.
+fn is_unicode_subsequent(character: &str) -> bool {
+    character
+        .chars()
+        .next()
+        .is_some_and(|c| !c.is_ascii() && !c.is_whitespace() && !c.is_control())
+}
+
+fn is_initial(character: &str) -> bool {
+    INITIAL.contains(character)
+        || (is_unicode_subsequent(character)
+            && character.chars().next().and_then(digit_value).is_none())
+}
+
+fn is_subsequent(character: &str) -> bool {
+    SUBSEQUENT.contains(character) || is_unicode_subsequent(character)
+}
+
 fn read_identifier<'a>(
     buffer: &Buffer<'a>,
     start_index: usize,
@@ -232,7 +391,7 @@ fn read_identifier<'a>(
 ) -> Result<(Token<'a>, usize)> {
     while index < buffer.length {
         match buffer.get(index) {
-            (character, _, end) if SUBSEQUENT.contains(character) => {
+            (character, _, end) if is_subsequent(character) => {
                 index = end;
             }
             (character, _, _) if DELIMITER.contains(character) => {
@@ -250,41 +409,30 @@ fn read_identifier<'a>(
     Ok((Token::Identifier(&buffer.src[start_index..index]), index))
 }
 
-fn read_number<'a>(
+/// Reads a `|...|` identifier. Its escapes are replaced when it is parsed.
+fn read_symbol<'a>(
     buffer: &Buffer<'a>,
     start_index: usize,
     mut index: usize,
 ) -> Result<(Token<'a>, usize)> {
     while index < buffer.length {
         match buffer.get(index) {
-            (character, _, end) if DIGIT.contains(character) => {
-                index = end;
-            }
-            (character, _, end) if SIGN.contains(character) => {
-                index = end;
-            }
-            (".", _, end) => {
-                index = end;
-            }
-            ("@", _, end) => {
-                index = end;
-            }
-            ("i", _, end) => {
-                index = end;
-            }
-            (character, _, _) if DELIMITER.contains(character) => {
-                return Ok((Token::Number(&buffer.src[start_index..index]), index));
-            }
-            (_, _, end) => {
-                return Err(TokenError::InvalidNumber(format!(
-                    "{}{}",
-                    &buffer.src[start_index..end],
-                    read_until_delimiter(buffer, end).0
-                )));
+            // the escaped character is skipped, so that `\|` does not end it
+            ("\\", _, end) => index = buffer.get(end).2,
+            (VERTICAL_LINE, _, end) => {
+                return Ok((Token::Identifier(&buffer.src[start_index..end]), end));
             }
+            (_, _, end) => index = end,
         }
     }
-    Ok((Token::Number(&buffer.src[start_index..index]), index))
+    Err(TokenError::MissingCloseVerticalLine)
+}
+
+/// Reads a numeric literal up to the next delimiter. Its syntax is checked by
+/// `Number::try_from` when the token is parsed.
+fn read_number<'a>(buffer: &Buffer<'a>, start_index: usize, index: usize) -> (Token<'a>, usize) {
+    let (_, end) = read_until_delimiter(buffer, index);
+    (Token::Number(&buffer.src[start_index..end]), end)
 }
 
 fn read_string<'a>(
@@ -318,130 +466,244 @@ fn read_string<'a>(
     Err(TokenError::MissingCloseQuote)
 }
 
+/// Tokenizes `src`, recording the span of every token.
 pub fn tokenize<'a>(src: &'a str) -> Result<TokenBuffer<'a>> {
+    read_tokens(src, 0, 1, None).map_err(|(error, _)| error)
+}
+
+/// Tokenizes the text of `source` between the byte offsets `start` and `end`.
+/// Errors and the expressions parsed from the tokens are located in `source`.
+pub(crate) fn tokenize_source(
+    source: &Arc<Source>,
+    start: usize,
+    end: usize,
+) -> std::result::Result<TokenBuffer<'_>, Error> {
+    match read_tokens(&source.text[..end], start, source.line, None) {
+        Ok(mut tokens) => {
+            tokens.source = Some(source.clone());
+            Ok(tokens)
+        }
+        Err((error, span)) => Err(Error::Located(
+            Box::new(error.into()),
+            Location {
+                source: source.clone(),
+                span,
+            },
+        )),
+    }
+}
+
+/// Tokenizes all of `source`, getting past invalid tokens. Their errors are
+/// returned, located, together with the tokens read.
+pub(crate) fn tokenize_recovering(source: &Arc<Source>) -> (TokenBuffer<'_>, Vec<Error>) {
+    let mut errors = vec![];
+    let mut tokens = read_tokens(&source.text, 0, source.line, Some(&mut errors))
+        .expect("errors are collected when recovering");
+    tokens.source = Some(source.clone());
+    let errors = errors
+        .into_iter()
+        .map(|(error, span)| {
+            Error::Located(
+                Box::new(error.into()),
+                Location {
+                    source: source.clone(),
+                    span,
+                },
+            )
+        })
+        .collect();
+    (tokens, errors)
+}
+
+/// Reads the tokens of `src` from the byte offset `start`, without locating
+/// them, up to the first invalid one, whose error and offset are returned.
+pub(crate) fn scan_tokens<'a>(
+    src: &'a str,
+    start: usize,
+    mut visit: impl FnMut(&Token<'a>),
+) -> std::result::Result<(), (TokenError, usize)> {
     let buffer = Buffer::new(src);
-    let mut token_list = vec![];
-    let mut index = 0;
-    let mut token: Token;
+    let mut index = start;
     while index < buffer.length {
-        match buffer.get(index) {
-            ("", _, _) => {
-                return Ok(TokenBuffer::new(token_list));
-            }
-            (OPEN_PARENTHESIS, _, end) => {
-                token_list.push(Token::OpenParenthesis);
+        let (token, end) = read_token(&buffer, index).map_err(|error| (error, index))?;
+        if let Some(token) = token {
+            visit(&token);
+        }
+        index = end;
+    }
+    Ok(())
+}
+
+/// Tokenizes `src` from the byte offset `start`. With `errors`, an invalid
+/// token is recorded there and skipped up to the next delimiter, or to the end
+/// for an unterminated string, comment or identifier.
+fn read_tokens<'a>(
+    src: &'a str,
+    start: usize,
+    line: usize,
+    mut errors: Option<&mut Vec<(TokenError, Span)>>,
+) -> std::result::Result<TokenBuffer<'a>, (TokenError, Span)> {
+    let buffer = Buffer::new(src);
+    let mut locator = Locator::new(src, line);
+    let mut tokens = vec![];
+    let mut spans = vec![];
+    let mut index = start;
+    while index < buffer.length {
+        match read_token(&buffer, index) {
+            Ok((token, end)) => {
+                if let Some(token) = token {
+                    tokens.push(token);
+                    spans.push(locator.span(index, end));
+                }
                 index = end;
             }
-            (CLOSE_PARENTHESIS, _, end) => {
-                token_list.push(Token::CloseParenthesis);
-                index = end;
+            Err(error) => {
+                let (_, end) = read_until_delimiter(&buffer, index);
+                let span = locator.span(index, end);
+                let Some(errors) = errors.as_deref_mut() else {
+                    return Err((error, span));
+                };
+                index = match error {
+                    TokenError::MissingCloseQuote
+                    | TokenError::MissingCloseComment
+                    | TokenError::MissingCloseVerticalLine => buffer.length,
+                    _ => end.max(buffer.get(index).2),
+                };
+                errors.push((error, span));
             }
-            (QUOTE, _, end) => {
-                token_list.push(Token::Quote);
-                index = end;
+        }
+    }
+    Ok(TokenBuffer::new(tokens, spans))
+}
+
+/// Reads the token starting at `index`, returning it, or `None` for
+/// whitespace, together with the index after it.
+fn read_token<'a>(buffer: &Buffer<'a>, index: usize) -> Result<(Option<Token<'a>>, usize)> {
+    let (token, index) = match buffer.get(index) {
+        (OPEN_PARENTHESIS, _, end) => (Token::OpenParenthesis, end),
+        (CLOSE_PARENTHESIS, _, end) => (Token::CloseParenthesis, end),
+        (QUOTE, _, end) => (Token::Quote, end),
+        (BACKQUOTE, _, end) => (Token::BackQuote, end),
+        (COMMA, _, end) => {
+            if buffer.get(end) == ("@", end, end + 1) {
+                (Token::CommaAt, end + 1)
+            } else {
+                (Token::Comma, end)
             }
-            (BACKQUOTE, _, end) => {
-                token_list.push(Token::BackQuote);
-                index = end;
+        }
+        (DOT, start, end) => {
+            let (character2, _, end2) = buffer.get(end);
+            if DIGIT.contains(character2) {
+                read_number(buffer, start, end2)
+            } else if character2.is_empty() || DELIMITER.contains(character2) {
+                (Token::Dot, end)
+            } else {
+                // `...`, `.foo` and the like
+                read_identifier(buffer, start, end)?
             }
-            (COMMA, _, end) => {
-                if buffer.get(end) == ("@", end, end + 1) {
-                    token_list.push(Token::CommaAt);
-                    index = end + 1;
-                } else {
-                    token_list.push(Token::Comma);
-                    index = end;
+        }
+        (COMMENT, start, end) => read_comment(buffer, start, end)?,
+        ("#", start, end) => {
+            let (character2, end2) = read_until_delimiter(buffer, end);
+            let digits = buffer.src[end..]
+                .bytes()
+                .take_while(u8::is_ascii_digit)
+                .count();
+            match character2 {
+                _ if digits > 0 && buffer.get(end + digits).0 == "=" => (
+                    Token::DatumLabel(&buffer.src[start..end + digits + 1]),
+                    end + digits + 1,
+                ),
+                _ if digits > 0 && buffer.get(end + digits).0 == "#" => (
+                    Token::DatumReference(&buffer.src[start..end + digits + 1]),
+                    end + digits + 1,
+                ),
+                "" if buffer.get(end).0 == VERTICAL_LINE => {
+                    read_block_comment(buffer, start, end + 1)?
                 }
-            }
-            (DOT, start, end) => {
-                let (character2, _, end2) = buffer.get(end);
-                if DELIMITER.contains(character2) {
-                    token_list.push(Token::Dot);
-                    index = end;
-                } else {
-                    let (character3, _, end3) = buffer.get(end2);
-                    let (character4, _, _) = buffer.get(end3);
-                    if (character2 == DOT) && (character3 == DOT) && DELIMITER.contains(character4)
-                    {
-                        token_list.push(Token::Identifier("..."));
-                        index = end3;
-                    } else {
-                        return Err(TokenError::InvalidIdentifier(format!(
-                            "{}{}",
-                            &buffer.src[start..end3],
-                            read_until_delimiter(&buffer, end3).0
-                        )));
+                "" if buffer.get(end).0 == COMMENT => (Token::DatumComment, end + 1),
+                "t" | "f" | "true" | "false" => (Token::Boolean(&buffer.src[start..end2]), end2),
+                FOLD_CASE | NO_FOLD_CASE => (Token::Directive(&buffer.src[start..end2]), end2),
+                _ if character2.starts_with('\\') => {
+                    // The character right after `#\` is taken even when it
+                    // is a delimiter, as in `#\(` and `#\ `.
+                    let (character3, _, end3) = buffer.get(end + 1);
+                    if character3.is_empty() {
+                        return Err(TokenError::InvalidConstant("#\\".to_string()));
                     }
+                    let (_, end4) = read_until_delimiter(buffer, end3);
+                    (Token::Character(&buffer.src[start..end4]), end4)
                 }
-            }
-            (COMMENT, start, end) => {
-                (token, index) = read_comment(&buffer, start, end)?;
-                token_list.push(token);
-            }
-            ("#", start, end) => {
-                let (character2, end2) = read_until_delimiter(&buffer, end);
-                match character2 {
-                    "t" | "f" | "true" | "false" => {
-                        token_list.push(Token::Boolean(&buffer.src[start..end2]));
-                        index = end2;
-                    }
-                    _ => {
-                        let (character3, _, end3) = buffer.get(end);
-                        if character3 == "(" {
-                            token_list.push(Token::VectorOpen);
-                            index = end3;
+                _ if buffer
+                    .src
+                    .get(start..start + 2)
+                    .map(str::to_ascii_lowercase)
+                    .is_some_and(|prefix| {
+                        EXACTNESS.contains(&prefix) || RADIX.contains(&prefix)
+                    }) =>
+                {
+                    (Token::Number(&buffer.src[start..end2]), end2)
+                }
+                _ => {
+                    let (character3, _, end3) = buffer.get(end);
+                    if character3 == "(" {
+                        (Token::VectorOpen, end3)
+                    } else if NumericKind::from_tag(character2).is_some()
+                        && buffer.get(end2).0 == "("
+                    {
+                        if character2.eq_ignore_ascii_case("u8") {
+                            (Token::ByteVectorOpen, end2 + 1)
                         } else {
-                            return Err(TokenError::InvalidConstant(format!(
-                                "{}",
-                                &buffer.src[start..end2],
-                            )));
+                            (
+                                Token::NumericVectorOpen(&buffer.src[start..end2 + 1]),
+                                end2 + 1,
+                            )
                         }
+                    } else {
+                        return Err(TokenError::InvalidConstant(
+                            buffer.src[start..end2].to_string(),
+                        ));
                     }
                 }
             }
-            ("+", start, end) | ("-", start, end) => match buffer.get(end) {
-                (character2, _, _) if DELIMITER.contains(character2) => {
-                    token_list.push(Token::Identifier(&buffer.src[start..end]));
-                    index = end;
-                }
-                (character2, _, end2) if DIGIT.contains(character2) => {
-                    (token, index) = read_number(&buffer, start, end2)?;
-                    token_list.push(token);
-                }
-                ("i", _, end2) => {
-                    (token, index) = read_number(&buffer, start, end2)?;
-                    token_list.push(token);
-                }
-                (_, _, end2) => {
-                    return Err(TokenError::InvalidIdentifier(format!(
-                        "{}{}",
-                        &buffer.src[start..end2],
-                        read_until_delimiter(&buffer, end2).0
-                    )));
+        }
+        ("+", start, end) | ("-", start, end) => match buffer.get(end) {
+            (character2, _, _) if DELIMITER.contains(character2) => {
+                (Token::Identifier(&buffer.src[start..end]), end)
+            }
+            (DOT, _, end2) => match buffer.get(end2) {
+                (character3, _, _)
+                    if character3.is_empty()
+                        || DIGIT.contains(character3)
+                        || DELIMITER.contains(character3) =>
+                {
+                    read_number(buffer, start, end2)
                 }
+                // `+.a`, `-..` and the like
+                _ => read_identifier(buffer, start, end)?,
             },
-            (STRING, start, end) => {
-                (token, index) = read_string(&buffer, start, end)?;
-                token_list.push(token);
-            }
-            (character, _, end) if WHITESPACE.contains(character) => {
-                index = end;
-            }
-            (character, start, end) if INITIAL.contains(character) => {
-                (token, index) = read_identifier(&buffer, start, end)?;
-                token_list.push(token);
-            }
-            (character, start, end) if DIGIT.contains(character) => {
-                (token, index) = read_number(&buffer, start, end)?;
-                token_list.push(token);
-            }
-            (character, _, _) => {
-                return Err(TokenError::InvalidCharacter(character.to_string()));
+            (character2, _, end2) if DIGIT.contains(character2) => read_number(buffer, start, end2),
+            (_, _, end2) => {
+                // `+i`, `-inf.0`, `+nan.0` and the like, or else an identifier
+                // such as `->list`
+                let (_, end3) = read_until_delimiter(buffer, end2);
+                if Number::try_from(&buffer.src[start..end3]).is_ok() {
+                    (Token::Number(&buffer.src[start..end3]), end3)
+                } else {
+                    read_identifier(buffer, start, end)?
+                }
             }
+        },
+        (STRING, start, end) => read_string(buffer, start, end)?,
+        (character, _, end) if WHITESPACE.contains(character) => return Ok((None, end)),
+        (VERTICAL_LINE, start, end) => read_symbol(buffer, start, end)?,
+        (character, start, end) if is_initial(character) => read_identifier(buffer, start, end)?,
+        (character, start, end) if DIGIT.contains(character) => read_number(buffer, start, end),
+        (character, _, _) => {
+            return Err(TokenError::InvalidCharacter(character.to_string()));
         }
-    }
-
-    Ok(TokenBuffer::new(token_list))
+    };
+    Ok((Some(token), index))
 }
 
 #[cfg(test)]
@@ -485,4 +747,25 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_spans() {
+        let mut tokens = tokenize("(ab x\n  \"λ\")").unwrap();
+        let mut spans = vec![];
+        while !tokens.is_empty() {
+            tokens.pop();
+            let span = tokens.span().unwrap();
+            spans.push((span.start, span.end, span.line, span.column));
+        }
+        assert_eq!(
+            spans,
+            [
+                (0, 1, 1, 1),
+                (1, 3, 1, 2),
+                (4, 5, 1, 5),
+                (8, 12, 2, 3),
+                (12, 13, 2, 6)
+            ]
+        );
+    }
 }
@@ -5,18 +5,29 @@ mod data_model;
 pub mod error;
 mod evaluator;
 mod frame;
+mod image;
 mod interpreter;
 mod lexer;
+mod limits;
 mod number;
+mod numeric_vector;
+mod output;
 mod parser;
+mod random;
+mod reader;
+mod span;
 #[cfg(target_arch = "wasm32")]
 mod wasm;
 
 pub use data_model::{Expression, Value};
 pub use evaluator::eval;
 pub use frame::create_global_frame;
-pub use interpreter::interpret;
+pub use interpreter::{interpret, Interpreter};
 pub use lexer::tokenize;
-pub use parser::parse;
+pub use limits::{InterruptHandle, Limits, DEFAULT_MAX_STACK};
+pub use numeric_vector::NumericElement;
+pub use parser::{parse, parse_recovering};
+pub use reader::Reader;
+pub use span::{Location, Source, Span};
 #[cfg(target_arch = "wasm32")]
 pub use wasm::*;
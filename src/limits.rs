@@ -0,0 +1,252 @@
+use std::{
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use crate::{
+    data_model::{ExpressionContent, Link},
+    error::{EvalError, LimitError},
+};
+
+/// How often (in evaluation steps) the wall-clock deadline is checked.
+const CLOCK_INTERVAL: u64 = 1024;
+
+/// The default stack budget: half of the 2 MiB stack Rust gives a spawned
+/// thread. A procedure call takes about 2.6 KB of it in a release build and
+/// 11 KB in a debug build.
+#[cfg(not(target_arch = "wasm32"))]
+pub const DEFAULT_MAX_STACK: usize = 1024 * 1024;
+
+/// The default stack budget: half of the 1 MiB stack of a WebAssembly module.
+#[cfg(target_arch = "wasm32")]
+pub const DEFAULT_MAX_STACK: usize = 512 * 1024;
+
+/// Resource limits applied to every evaluation of an
+/// [`Interpreter`](crate::Interpreter). `None` means unlimited.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    /// Maximum number of evaluation steps.
+    pub max_steps: Option<u64>,
+    /// Maximum depth of nested procedure calls.
+    pub max_depth: Option<usize>,
+    /// Approximate number of bytes that may be allocated.
+    pub max_heap: Option<usize>,
+    /// Wall-clock time budget.
+    pub timeout: Option<Duration>,
+    /// Maximum number of bytes of native stack that nested evaluation may
+    /// use, which has to leave room in the stack of the evaluating thread.
+    pub max_stack: Option<usize>,
+}
+
+impl Default for Limits {
+    /// No limits, except a stack budget of [`DEFAULT_MAX_STACK`] so that deep
+    /// recursion is an error rather than a stack overflow.
+    fn default() -> Self {
+        Self {
+            max_steps: None,
+            max_depth: None,
+            max_heap: None,
+            timeout: None,
+            max_stack: Some(DEFAULT_MAX_STACK),
+        }
+    }
+}
+
+/// A cloneable handle that aborts the evaluation currently running on its
+/// [`Interpreter`](crate::Interpreter). It is safe to use from another thread
+/// or from a signal handler.
+#[derive(Debug, Clone, Default)]
+pub struct InterruptHandle {
+    flag: Arc<AtomicBool>,
+}
+
+impl InterruptHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn interrupt(&self) {
+        self.flag.store(true, Ordering::Relaxed);
+    }
+
+    pub(crate) fn clear(&self) {
+        self.flag.store(false, Ordering::Relaxed);
+    }
+
+    fn take(&self) -> bool {
+        self.flag.swap(false, Ordering::Relaxed)
+    }
+}
+
+struct Budget {
+    limits: Limits,
+    interrupt: Option<InterruptHandle>,
+    steps: u64,
+    depth: usize,
+    heap: usize,
+    deadline: Option<f64>,
+    /// The stack address evaluation started from.
+    stack_base: usize,
+}
+
+thread_local! {
+    static BUDGET: RefCell<Option<Budget>> = const { RefCell::new(None) };
+}
+
+/// The address of the current stack frame, near enough.
+#[inline(always)]
+fn stack_address() -> usize {
+    let marker = 0u8;
+    std::ptr::addr_of!(marker) as usize
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn now() -> f64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |duration| duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = Date)]
+    fn now() -> f64;
+}
+
+/// Installs `limits` for the current thread until the guard is dropped.
+pub(crate) struct BudgetGuard {
+    previous: Option<Budget>,
+}
+
+impl BudgetGuard {
+    pub(crate) fn install(limits: &Limits, interrupt: Option<&InterruptHandle>) -> Self {
+        let budget = Budget {
+            limits: *limits,
+            interrupt: interrupt.cloned(),
+            steps: 0,
+            depth: 0,
+            heap: 0,
+            deadline: limits
+                .timeout
+                .map(|timeout| now() + timeout.as_secs_f64() * 1000.0),
+            stack_base: stack_address(),
+        };
+        Self {
+            previous: BUDGET.with(|cell| cell.replace(Some(budget))),
+        }
+    }
+}
+
+impl Drop for BudgetGuard {
+    fn drop(&mut self) {
+        BUDGET.with(|cell| *cell.borrow_mut() = self.previous.take());
+    }
+}
+
+/// Accounts for one evaluation step.
+pub(crate) fn step() -> Result<(), EvalError> {
+    BUDGET.with(|cell| {
+        let mut budget = cell.borrow_mut();
+        let Some(budget) = budget.as_mut() else {
+            return Ok(());
+        };
+        budget.steps += 1;
+        if let Some(interrupt) = &budget.interrupt {
+            if interrupt.take() {
+                return Err(EvalError::Interrupted);
+            }
+        }
+        if let Some(max_steps) = budget.limits.max_steps {
+            if budget.steps > max_steps {
+                return Err(LimitError::Steps(max_steps).into());
+            }
+        }
+        if let Some(max_heap) = budget.limits.max_heap {
+            if budget.heap > max_heap {
+                return Err(LimitError::Heap(max_heap).into());
+            }
+        }
+        if let Some(max_stack) = budget.limits.max_stack {
+            // every nested evaluation takes a step, so this bounds the recursion
+            if budget.stack_base.abs_diff(stack_address()) > max_stack {
+                return Err(LimitError::Stack(max_stack).into());
+            }
+        }
+        if let Some(deadline) = budget.deadline {
+            if budget.steps % CLOCK_INTERVAL == 0 && now() > deadline {
+                let timeout = budget.limits.timeout.unwrap_or_default();
+                return Err(LimitError::Timeout(timeout).into());
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Leaves a procedure call when dropped.
+pub(crate) struct DepthGuard;
+
+impl Drop for DepthGuard {
+    fn drop(&mut self) {
+        BUDGET.with(|cell| {
+            if let Some(budget) = cell.borrow_mut().as_mut() {
+                budget.depth = budget.depth.saturating_sub(1);
+            }
+        });
+    }
+}
+
+/// Enters a procedure call.
+pub(crate) fn enter() -> Result<DepthGuard, LimitError> {
+    BUDGET.with(|cell| {
+        if let Some(budget) = cell.borrow_mut().as_mut() {
+            if let Some(max_depth) = budget.limits.max_depth {
+                if budget.depth >= max_depth {
+                    return Err(LimitError::Depth(max_depth));
+                }
+            }
+            budget.depth += 1;
+        }
+        Ok(DepthGuard)
+    })
+}
+
+/// Checks that `bytes` more fit in the heap budget before they are allocated,
+/// for allocations too large to be caught at the next step.
+pub(crate) fn reserve(bytes: usize) -> Result<(), LimitError> {
+    BUDGET.with(|cell| match cell.borrow().as_ref() {
+        Some(budget) => match budget.limits.max_heap {
+            Some(max_heap) if budget.heap.saturating_add(bytes) > max_heap => {
+                Err(LimitError::Heap(max_heap))
+            }
+            _ => Ok(()),
+        },
+        None => Ok(()),
+    })
+}
+
+/// Records an allocation of roughly `bytes` bytes. The budget is enforced at
+/// the next evaluation step.
+pub(crate) fn allocate(bytes: usize) {
+    BUDGET.with(|cell| {
+        if let Some(budget) = cell.borrow_mut().as_mut() {
+            budget.heap += bytes;
+        }
+    });
+}
+
+pub(crate) fn footprint(content: &ExpressionContent) -> usize {
+    std::mem::size_of::<ExpressionContent>()
+        + match content {
+            ExpressionContent::String(string) | ExpressionContent::Symbol(string) => string.len(),
+            ExpressionContent::VectorLink(vector) => vector.len() * std::mem::size_of::<Link>(),
+            ExpressionContent::NumericVector(vector) => vector.borrow().byte_len(),
+            _ => 0,
+        }
+}
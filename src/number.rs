@@ -4,29 +4,867 @@ use std::{
     ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
 };
 
-use once_cell::sync::Lazy;
-use regex::Regex;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_rational::BigRational;
+use num_traits::{Signed, ToPrimitive, Zero};
 
 use crate::error::{InvalidArgument, ParseError};
 
-#[derive(Debug, Clone, Copy)]
+/// A Scheme number. Exact integers that fit in an `i64` are stored inline and
+/// only move to `BigInteger` on overflow, so a `BigInteger` is never in the
+/// `i64` range. A `Rational` is always in lowest terms with a denominator
+/// greater than one.
+#[derive(Debug, Clone)]
 pub enum Number {
-    Integer(i32),
-    // Rational(i32, i32),
+    Integer(i64),
+    BigInteger(BigInt),
+    Rational(Box<BigRational>),
     Real(f64),
     Complex(f64, f64),
 }
 
+/// Two operands promoted to the narrowest representation that holds both.
+enum Coerced {
+    Integer(BigInt, BigInt),
+    Rational(BigRational, BigRational),
+    Real(f64, f64),
+    Complex((f64, f64), (f64, f64)),
+}
+
+impl Number {
+    pub fn is_exact(&self) -> bool {
+        matches!(
+            self,
+            Self::Integer(_) | Self::BigInteger(_) | Self::Rational(_)
+        )
+    }
+
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Self::Integer(value) => *value == 0,
+            Self::BigInteger(_) | Self::Rational(_) => false,
+            Self::Real(value) => *value == 0.0,
+            Self::Complex(real, im) => *real == 0.0 && *im == 0.0,
+        }
+    }
+
+    fn to_big(&self) -> BigInt {
+        match self {
+            Self::Integer(value) => BigInt::from(*value),
+            Self::BigInteger(value) => value.clone(),
+            _ => unreachable!("not an exact integer"),
+        }
+    }
+
+    fn to_rational(&self) -> BigRational {
+        match self {
+            Self::Rational(value) => value.as_ref().clone(),
+            _ => BigRational::from_integer(self.to_big()),
+        }
+    }
+
+    /// Converts to the exact number with the same value.
+    pub fn to_exact(&self) -> Result<Self, InvalidArgument> {
+        match self {
+            Self::Real(value) => BigRational::from_float(*value)
+                .map(Self::from)
+                .ok_or_else(|| InvalidArgument::NoExactRepresentation(self.to_string())),
+            Self::Complex(real, im) if *im == 0.0 => Self::Real(*real).to_exact(),
+            Self::Complex(_, _) => Err(InvalidArgument::NoExactRepresentation(self.to_string())),
+            _ => Ok(self.clone()),
+        }
+    }
+
+    /// Converts to the closest inexact number.
+    pub fn to_inexact(&self) -> Self {
+        match self {
+            Self::Integer(_) | Self::BigInteger(_) | Self::Rational(_) => Self::Real(self.to_f64()),
+            _ => self.clone(),
+        }
+    }
+
+    /// Checks that the number is an exact non-negative integer usable as an
+    /// index or a size.
+    pub fn to_index(&self) -> Result<usize, InvalidArgument> {
+        match self {
+            Self::Integer(value) => {
+                usize::try_from(*value).map_err(|_| InvalidArgument::OutOfRange(self.to_string()))
+            }
+            Self::BigInteger(_) => Err(InvalidArgument::OutOfRange(self.to_string())),
+            _ => Err(InvalidArgument::InvalidType(
+                self.to_string(),
+                "exact integer".to_string(),
+            )),
+        }
+    }
+
+    /// The numerator of the number in lowest terms; `None` for non-rational
+    /// numbers. Inexact numbers give an inexact result.
+    pub fn numerator(&self) -> Option<Self> {
+        match self {
+            Self::Integer(_) | Self::BigInteger(_) => Some(self.clone()),
+            Self::Rational(value) => Some(value.numer().clone().into()),
+            Self::Real(value) => {
+                let value = BigRational::from_float(*value)?;
+                Some(Self::Real(value.numer().to_f64()?))
+            }
+            Self::Complex(_, _) => None,
+        }
+    }
+
+    /// The denominator of the number in lowest terms; `None` for non-rational
+    /// numbers. Inexact numbers give an inexact result.
+    pub fn denominator(&self) -> Option<Self> {
+        match self {
+            Self::Integer(_) | Self::BigInteger(_) => Some(Self::Integer(1)),
+            Self::Rational(value) => Some(value.denom().clone().into()),
+            Self::Real(value) => {
+                let value = BigRational::from_float(*value)?;
+                Some(Self::Real(value.denom().to_f64()?))
+            }
+            Self::Complex(_, _) => None,
+        }
+    }
+
+    pub(crate) fn to_f64(&self) -> f64 {
+        match self {
+            Self::Integer(value) => *value as f64,
+            Self::BigInteger(value) => value.to_f64().unwrap_or(f64::NAN),
+            Self::Rational(value) => value.to_f64().unwrap_or(f64::NAN),
+            Self::Real(value) => *value,
+            Self::Complex(real, _) => *real,
+        }
+    }
+
+    fn to_complex(&self) -> (f64, f64) {
+        match self {
+            Self::Complex(real, im) => (*real, *im),
+            _ => (self.to_f64(), 0.0),
+        }
+    }
+
+    fn coerce(lhs: &Self, rhs: &Self) -> Coerced {
+        match (lhs, rhs) {
+            (Self::Complex(_, _), _) | (_, Self::Complex(_, _)) => {
+                Coerced::Complex(lhs.to_complex(), rhs.to_complex())
+            }
+            (Self::Real(_), _) | (_, Self::Real(_)) => Coerced::Real(lhs.to_f64(), rhs.to_f64()),
+            (Self::Rational(_), _) | (_, Self::Rational(_)) => {
+                Coerced::Rational(lhs.to_rational(), rhs.to_rational())
+            }
+            _ => Coerced::Integer(lhs.to_big(), rhs.to_big()),
+        }
+    }
+}
+
+/// How an integer division rounds its quotient.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Division {
+    Floor,
+    Truncate,
+}
+
+/// How a number is rounded to an integer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rounding {
+    Floor,
+    Ceiling,
+    Truncate,
+    /// Round to nearest, ties to even.
+    Round,
+}
+
+/// Largest number of bits an exact result of `expt` or a bitwise operation
+/// may have.
+const MAX_EXACT_BITS: u64 = 1 << 26;
+
+fn not_integer(number: &Number) -> InvalidArgument {
+    InvalidArgument::InvalidType(number.to_string(), "integer".to_string())
+}
+
+fn not_real(number: &Number) -> InvalidArgument {
+    InvalidArgument::InvalidType(number.to_string(), "real".to_string())
+}
+
+fn round_rational(value: &BigRational, rounding: Rounding) -> BigInt {
+    match rounding {
+        Rounding::Floor => value.floor().to_integer(),
+        Rounding::Ceiling => value.ceil().to_integer(),
+        Rounding::Truncate => value.trunc().to_integer(),
+        Rounding::Round => {
+            let floor = value.floor().to_integer();
+            let difference = value - BigRational::from_integer(floor.clone());
+            match difference.cmp(&BigRational::new(1.into(), 2.into())) {
+                Ordering::Less => floor,
+                Ordering::Greater => floor + 1,
+                Ordering::Equal if floor.is_even() => floor,
+                Ordering::Equal => floor + 1,
+            }
+        }
+    }
+}
+
+impl Number {
+    pub fn is_real(&self) -> bool {
+        !matches!(self, Self::Complex(_, _))
+    }
+
+    pub fn is_rational(&self) -> bool {
+        match self {
+            Self::Real(value) => value.is_finite(),
+            Self::Complex(_, _) => false,
+            _ => true,
+        }
+    }
+
+    pub fn is_integer(&self) -> bool {
+        match self {
+            Self::Integer(_) | Self::BigInteger(_) => true,
+            Self::Real(value) => value.is_finite() && value.fract() == 0.0,
+            Self::Rational(_) | Self::Complex(_, _) => false,
+        }
+    }
+
+    pub fn is_nan(&self) -> bool {
+        match self {
+            Self::Real(value) => value.is_nan(),
+            Self::Complex(real, im) => real.is_nan() || im.is_nan(),
+            _ => false,
+        }
+    }
+
+    pub fn is_infinite(&self) -> bool {
+        match self {
+            Self::Real(value) => value.is_infinite(),
+            Self::Complex(real, im) => real.is_infinite() || im.is_infinite(),
+            _ => false,
+        }
+    }
+
+    /// The sign of a real number, `None` for NaN.
+    pub fn signum(&self) -> Result<Option<Ordering>, InvalidArgument> {
+        if self.is_real() {
+            Ok(self.partial_cmp(&Self::Integer(0)))
+        } else {
+            Err(not_real(self))
+        }
+    }
+
+    pub fn is_even(&self) -> Result<bool, InvalidArgument> {
+        match self {
+            Self::Integer(value) => Ok(value % 2 == 0),
+            Self::BigInteger(value) => Ok(value.is_even()),
+            Self::Real(value) if self.is_integer() => Ok(value % 2.0 == 0.0),
+            _ => Err(not_integer(self)),
+        }
+    }
+
+    pub fn abs(&self) -> Result<Self, InvalidArgument> {
+        match self {
+            Self::Integer(value) => Ok(value
+                .checked_abs()
+                .map_or_else(|| BigInt::from(*value).abs().into(), Self::Integer)),
+            Self::BigInteger(value) => Ok(value.abs().into()),
+            Self::Rational(value) => Ok(value.abs().into()),
+            Self::Real(value) => Ok(Self::Real(value.abs())),
+            Self::Complex(_, _) => Err(not_real(self)),
+        }
+    }
+
+    /// Divides two integers, returning the quotient and the remainder. The
+    /// result is inexact if either operand is.
+    pub fn integer_divide(
+        &self,
+        rhs: &Self,
+        division: Division,
+    ) -> Result<(Self, Self), InvalidArgument> {
+        for number in [self, rhs] {
+            if !number.is_integer() {
+                return Err(not_integer(number));
+            }
+        }
+        if rhs.is_zero() {
+            return Err(InvalidArgument::ZeroDivisor);
+        }
+        if let (Self::Integer(lhs), Self::Integer(rhs)) = (self, rhs) {
+            if *lhs != i64::MIN || *rhs != -1 {
+                let (quotient, remainder) = match division {
+                    Division::Floor => lhs.div_mod_floor(rhs),
+                    Division::Truncate => lhs.div_rem(rhs),
+                };
+                return Ok((Self::Integer(quotient), Self::Integer(remainder)));
+            }
+        }
+        if self.is_exact() && rhs.is_exact() {
+            let (lhs, rhs) = (self.to_big(), rhs.to_big());
+            let (quotient, remainder) = match division {
+                Division::Floor => lhs.div_mod_floor(&rhs),
+                Division::Truncate => lhs.div_rem(&rhs),
+            };
+            return Ok((quotient.into(), remainder.into()));
+        }
+        let (lhs, rhs) = (self.to_f64(), rhs.to_f64());
+        let mut remainder = lhs % rhs;
+        if division == Division::Floor && remainder != 0.0 && (remainder < 0.0) != (rhs < 0.0) {
+            remainder += rhs;
+        }
+        let quotient = ((lhs - remainder) / rhs).round();
+        Ok((Self::Real(quotient), Self::Real(remainder)))
+    }
+
+    /// Applies an exact integer operation to two integers, converting
+    /// inexact operands to exact and back.
+    fn exact_integer_operation(
+        &self,
+        rhs: &Self,
+        operation: impl FnOnce(BigInt, BigInt) -> BigInt,
+    ) -> Result<Self, InvalidArgument> {
+        for number in [self, rhs] {
+            if !number.is_integer() {
+                return Err(not_integer(number));
+            }
+        }
+        let result = Self::from(operation(
+            self.to_exact()?.to_big(),
+            rhs.to_exact()?.to_big(),
+        ));
+        if self.is_exact() && rhs.is_exact() {
+            Ok(result)
+        } else {
+            Ok(result.to_inexact())
+        }
+    }
+
+    pub fn gcd(&self, rhs: &Self) -> Result<Self, InvalidArgument> {
+        self.exact_integer_operation(rhs, |lhs, rhs| lhs.gcd(&rhs))
+    }
+
+    pub fn lcm(&self, rhs: &Self) -> Result<Self, InvalidArgument> {
+        self.exact_integer_operation(rhs, |lhs, rhs| {
+            if lhs.is_zero() || rhs.is_zero() {
+                BigInt::zero()
+            } else {
+                lhs.lcm(&rhs).abs()
+            }
+        })
+    }
+
+    /// Rounds a real number to an integer of the same exactness.
+    pub fn round(&self, rounding: Rounding) -> Result<Self, InvalidArgument> {
+        match self {
+            Self::Integer(_) | Self::BigInteger(_) => Ok(self.clone()),
+            Self::Rational(value) => Ok(round_rational(value, rounding).into()),
+            Self::Real(value) => Ok(Self::Real(match rounding {
+                Rounding::Floor => value.floor(),
+                Rounding::Ceiling => value.ceil(),
+                Rounding::Truncate => value.trunc(),
+                Rounding::Round => value.round_ties_even(),
+            })),
+            Self::Complex(_, _) => Err(not_real(self)),
+        }
+    }
+
+    fn real_value(&self) -> Result<f64, InvalidArgument> {
+        match self {
+            Self::Complex(_, _) => Err(not_real(self)),
+            _ => Ok(self.to_f64()),
+        }
+    }
+
+    /// Builds an inexact number from polar parts.
+    pub fn polar(magnitude: f64, angle: f64) -> Self {
+        Self::from((magnitude * angle.cos(), magnitude * angle.sin()))
+    }
+
+    /// Builds a number from real rectangular parts; an exact zero imaginary
+    /// part keeps the real part as it is.
+    pub fn make_rectangular(real: &Self, im: &Self) -> Result<Self, InvalidArgument> {
+        let (real_value, im_value) = (real.real_value()?, im.real_value()?);
+        if im.is_exact() && im.is_zero() {
+            Ok(real.clone())
+        } else {
+            Ok(Self::from((real_value, im_value)))
+        }
+    }
+
+    /// Builds a number from a real magnitude and angle; an exact zero angle
+    /// keeps the magnitude as it is.
+    pub fn make_polar(magnitude: &Self, angle: &Self) -> Result<Self, InvalidArgument> {
+        let (magnitude_value, angle_value) = (magnitude.real_value()?, angle.real_value()?);
+        if angle.is_exact() && angle.is_zero() {
+            Ok(magnitude.clone())
+        } else {
+            Ok(Self::polar(magnitude_value, angle_value))
+        }
+    }
+
+    pub fn real_part(&self) -> Self {
+        match self {
+            Self::Complex(real, _) => Self::Real(*real),
+            _ => self.clone(),
+        }
+    }
+
+    /// The imaginary part; exact zero for every real number.
+    pub fn imag_part(&self) -> Self {
+        match self {
+            Self::Complex(_, im) => Self::Real(*im),
+            _ => Self::Integer(0),
+        }
+    }
+
+    pub fn magnitude(&self) -> Self {
+        match self {
+            Self::Complex(real, im) => Self::Real(real.hypot(*im)),
+            _ => self.abs().expect("real numbers have an absolute value"),
+        }
+    }
+
+    /// The angle in `(-pi, pi]`; exact zero for exact non-negative numbers.
+    pub fn angle(&self) -> Self {
+        match self {
+            Self::Complex(real, im) => Self::Real(im.atan2(*real)),
+            Self::Real(value) => Self::Real(0f64.atan2(*value)),
+            _ if self.partial_cmp(&Self::Integer(0)) == Some(Ordering::Less) => {
+                Self::Real(std::f64::consts::PI)
+            }
+            _ => Self::Integer(0),
+        }
+    }
+
+    /// Applies `real` to real arguments inside `domain` and `complex` to
+    /// everything else.
+    fn transcendental(
+        &self,
+        domain: impl FnOnce(f64) -> bool,
+        real: impl FnOnce(f64) -> f64,
+        complex: impl FnOnce(Rectangular) -> Rectangular,
+    ) -> Self {
+        match self {
+            Self::Complex(real, im) => complex((*real, *im)).into(),
+            _ if domain(self.to_f64()) => Self::Real(real(self.to_f64())),
+            _ => complex(self.to_complex()).into(),
+        }
+    }
+
+    pub fn exp(&self) -> Self {
+        self.transcendental(|_| true, f64::exp, complex_exp)
+    }
+
+    /// The natural logarithm. Negative reals have a complex logarithm.
+    pub fn ln(&self) -> Self {
+        self.transcendental(|value| value >= 0.0, f64::ln, complex_ln)
+    }
+
+    pub fn sin(&self) -> Self {
+        self.transcendental(|_| true, f64::sin, complex_sin)
+    }
+
+    pub fn cos(&self) -> Self {
+        self.transcendental(|_| true, f64::cos, complex_cos)
+    }
+
+    pub fn tan(&self) -> Self {
+        self.transcendental(
+            |_| true,
+            f64::tan,
+            |z| complex_div(complex_sin(z), complex_cos(z)),
+        )
+    }
+
+    /// The arcsine. Reals outside `[-1, 1]` have a complex arcsine.
+    pub fn asin(&self) -> Self {
+        self.transcendental(|value| value.abs() <= 1.0, f64::asin, complex_asin)
+    }
+
+    /// The arccosine. Reals outside `[-1, 1]` have a complex arccosine.
+    pub fn acos(&self) -> Self {
+        self.transcendental(
+            |value| value.abs() <= 1.0,
+            f64::acos,
+            |z| {
+                let (real, im) = complex_asin(z);
+                (std::f64::consts::FRAC_PI_2 - real, -im)
+            },
+        )
+    }
+
+    pub fn atan(&self) -> Self {
+        self.transcendental(|_| true, f64::atan, complex_atan)
+    }
+
+    /// The angle of the point (`x`, `self`), as in two-argument `atan`.
+    pub fn atan2(&self, x: &Self) -> Result<Self, InvalidArgument> {
+        Ok(Self::Real(self.real_value()?.atan2(x.real_value()?)))
+    }
+
+    /// The principal square root; exact for exact perfect squares.
+    pub fn sqrt(&self) -> Self {
+        if self.is_exact() {
+            let value = self.to_rational();
+            let (numerator, denominator) = (value.numer().abs(), value.denom().clone());
+            let (numerator_root, denominator_root) = (numerator.sqrt(), denominator.sqrt());
+            if &numerator_root * &numerator_root == numerator
+                && &denominator_root * &denominator_root == denominator
+            {
+                let root = Self::from(BigRational::new(numerator_root, denominator_root));
+                return if value.is_negative() {
+                    Self::from((0.0, root.to_f64()))
+                } else {
+                    root
+                };
+            }
+        }
+        self.transcendental(|value| value >= 0.0, f64::sqrt, complex_sqrt)
+    }
+
+    /// The integer square root `s` and remainder `r` of an exact
+    /// non-negative integer, so that `self = s * s + r`.
+    pub fn exact_integer_sqrt(&self) -> Result<(Self, Self), InvalidArgument> {
+        match self {
+            Self::Integer(_) | Self::BigInteger(_) => {
+                let value = self.to_big();
+                if value.is_negative() {
+                    return Err(InvalidArgument::OutOfRange(self.to_string()));
+                }
+                let root = value.sqrt();
+                let remainder = &value - &root * &root;
+                Ok((root.into(), remainder.into()))
+            }
+            _ => Err(InvalidArgument::InvalidType(
+                self.to_string(),
+                "exact integer".to_string(),
+            )),
+        }
+    }
+
+    /// The exact `degree`th root of an exact non-negative number, if it has one.
+    fn exact_root(&self, degree: &BigInt) -> Option<Self> {
+        if !self.is_exact() {
+            return None;
+        }
+        let (value, degree) = (self.to_rational(), degree.to_u32()?);
+        if value.is_negative() {
+            return None;
+        }
+        let root =
+            |part: &BigInt| Some(part.nth_root(degree)).filter(|root| &root.pow(degree) == part);
+        Some(BigRational::new(root(value.numer())?, root(value.denom())?).into())
+    }
+
+    /// Raises `self` to `power`. Exact bases with exact integer powers give
+    /// exact results, as do exact non-negative bases whose root the
+    /// denominator of an exact power calls for is exact; otherwise the result
+    /// is `exp(power * log(self))` on the principal branch. An exact zero
+    /// power gives 1, inexact for an inexact base.
+    pub fn expt(&self, power: &Self) -> Result<Self, InvalidArgument> {
+        if power.is_exact() && power.is_zero() {
+            return Ok(if self.is_exact() {
+                Self::Integer(1)
+            } else {
+                Self::Real(1.0)
+            });
+        }
+        if let (true, Self::Integer(power)) = (self.is_exact(), power) {
+            let base = self.to_rational();
+            if base.is_zero() {
+                return if *power < 0 {
+                    Err(InvalidArgument::ZeroDivisor)
+                } else {
+                    Ok(Self::Integer(0))
+                };
+            }
+            let bits = base.numer().bits().max(base.denom().bits());
+            let exponent = u32::try_from(power.unsigned_abs())
+                .ok()
+                .filter(|exponent| {
+                    bits <= 1 || bits.saturating_mul(*exponent as u64) <= MAX_EXACT_BITS
+                })
+                .ok_or_else(|| InvalidArgument::OutOfRange(power.to_string()))?;
+            let result = BigRational::new(base.numer().pow(exponent), base.denom().pow(exponent));
+            return Ok(if *power < 0 { result.recip() } else { result }.into());
+        }
+        if let Self::Rational(power) = power {
+            if let Some(root) = self.exact_root(power.denom()) {
+                return root.expt(&power.numer().clone().into());
+            }
+        }
+        match (self, power) {
+            (Self::Complex(_, _), _) | (_, Self::Complex(_, _)) => {
+                if self.is_zero() {
+                    return Ok(if power.to_f64() > 0.0 {
+                        Self::Real(0.0)
+                    } else {
+                        Self::from((f64::NAN, f64::NAN))
+                    });
+                }
+                if let Self::Integer(power) = power {
+                    return Ok(complex_powi(self.to_complex(), *power).into());
+                }
+                Ok(complex_exp(complex_mul(
+                    power.to_complex(),
+                    complex_ln(self.to_complex()),
+                ))
+                .into())
+            }
+            _ => {
+                let (base, power) = (self.to_f64(), power.to_f64());
+                if base < 0.0 && power.fract() != 0.0 {
+                    Ok(Self::polar(
+                        (-base).powf(power),
+                        power * std::f64::consts::PI,
+                    ))
+                } else {
+                    Ok(Self::Real(base.powf(power)))
+                }
+            }
+        }
+    }
+
+    /// Formats the number in radix 2, 8, 10 or 16. Only exact numbers can be
+    /// written in a radix other than 10.
+    pub fn to_string_radix(&self, radix: u32) -> Result<String, InvalidArgument> {
+        match (self, radix) {
+            (_, 10) => Ok(self.to_string()),
+            (_, 2 | 8 | 16) => match self {
+                Self::Integer(_) | Self::BigInteger(_) => Ok(self.to_big().to_str_radix(radix)),
+                Self::Rational(value) => Ok(format!(
+                    "{}/{}",
+                    value.numer().to_str_radix(radix),
+                    value.denom().to_str_radix(radix)
+                )),
+                _ => Err(InvalidArgument::InvalidType(
+                    self.to_string(),
+                    "exact number".to_string(),
+                )),
+            },
+            _ => Err(InvalidArgument::OutOfRange(radix.to_string())),
+        }
+    }
+}
+
+/// Bitwise operations on exact integers, which behave as infinitely
+/// sign-extended two's complement numbers.
+impl Number {
+    pub(crate) fn exact_integer(&self) -> Result<BigInt, InvalidArgument> {
+        match self {
+            Self::Integer(_) | Self::BigInteger(_) => Ok(self.to_big()),
+            _ => Err(InvalidArgument::InvalidType(
+                self.to_string(),
+                "exact integer".to_string(),
+            )),
+        }
+    }
+
+    fn bitwise(
+        &self,
+        rhs: &Self,
+        small: impl FnOnce(i64, i64) -> i64,
+        big: impl FnOnce(BigInt, BigInt) -> BigInt,
+    ) -> Result<Self, InvalidArgument> {
+        if let (Self::Integer(lhs), Self::Integer(rhs)) = (self, rhs) {
+            return Ok(Self::Integer(small(*lhs, *rhs)));
+        }
+        Ok(big(self.exact_integer()?, rhs.exact_integer()?).into())
+    }
+
+    pub fn bitwise_and(&self, rhs: &Self) -> Result<Self, InvalidArgument> {
+        self.bitwise(rhs, |lhs, rhs| lhs & rhs, |lhs, rhs| lhs & rhs)
+    }
+
+    pub fn bitwise_or(&self, rhs: &Self) -> Result<Self, InvalidArgument> {
+        self.bitwise(rhs, |lhs, rhs| lhs | rhs, |lhs, rhs| lhs | rhs)
+    }
+
+    pub fn bitwise_xor(&self, rhs: &Self) -> Result<Self, InvalidArgument> {
+        self.bitwise(rhs, |lhs, rhs| lhs ^ rhs, |lhs, rhs| lhs ^ rhs)
+    }
+
+    pub fn bitwise_not(&self) -> Result<Self, InvalidArgument> {
+        match self {
+            Self::Integer(value) => Ok(Self::Integer(!value)),
+            _ => Ok((!self.exact_integer()?).into()),
+        }
+    }
+
+    /// Shifts left for a positive `count` and right, rounding towards
+    /// negative infinity, for a negative one.
+    pub fn arithmetic_shift(&self, count: &Self) -> Result<Self, InvalidArgument> {
+        let value = self.exact_integer()?;
+        let count = count.exact_integer()?;
+        if count.is_negative() {
+            return Ok(match (-count).to_u64() {
+                Some(count) => value >> count,
+                None if value.is_negative() => BigInt::from(-1),
+                None => BigInt::zero(),
+            }
+            .into());
+        }
+        if value.is_zero() {
+            return Ok(Self::Integer(0));
+        }
+        let count = count
+            .to_u64()
+            .filter(|count| value.bits().saturating_add(*count) <= MAX_EXACT_BITS)
+            .ok_or_else(|| InvalidArgument::OutOfRange(count.to_string()))?;
+        Ok((value << count).into())
+    }
+
+    /// The number of one bits in a non-negative integer, or of zero bits in a
+    /// negative one.
+    pub fn bit_count(&self) -> Result<Self, InvalidArgument> {
+        let value = self.exact_integer()?;
+        let value = if value.is_negative() { !value } else { value };
+        Ok(Self::from(BigInt::from(value.magnitude().count_ones())))
+    }
+
+    /// The number of bits needed to represent the integer, not counting the
+    /// sign bit.
+    pub fn integer_length(&self) -> Result<Self, InvalidArgument> {
+        let value = self.exact_integer()?;
+        let value = if value.is_negative() { !value } else { value };
+        Ok(Self::from(BigInt::from(value.bits())))
+    }
+
+    pub fn is_bit_set(&self, index: usize) -> Result<bool, InvalidArgument> {
+        Ok(self.exact_integer()?.bit(index as u64))
+    }
+
+    pub fn copy_bit(&self, index: usize, bit: bool) -> Result<Self, InvalidArgument> {
+        let mut value = self.exact_integer()?;
+        if index as u64 >= MAX_EXACT_BITS {
+            return Err(InvalidArgument::OutOfRange(index.to_string()));
+        }
+        value.set_bit(index as u64, bit);
+        Ok(value.into())
+    }
+
+    /// The bits from `start` up to but excluding `end`, shifted down to bit
+    /// zero.
+    pub fn bit_field(&self, start: usize, end: usize) -> Result<Self, InvalidArgument> {
+        let value = self.exact_integer()?;
+        if end < start {
+            return Err(InvalidArgument::OutOfRange(end.to_string()));
+        }
+        let mut width = (end - start) as u64;
+        if !value.is_negative() {
+            width = width.min(value.bits());
+        } else if width > MAX_EXACT_BITS {
+            return Err(InvalidArgument::OutOfRange(end.to_string()));
+        }
+        let mask: BigInt = (BigInt::from(1) << width) - 1;
+        Ok(Self::from((value >> start) & mask))
+    }
+}
+
+/// An inexact complex number as its real and imaginary parts.
+type Rectangular = (f64, f64);
+
+fn complex_mul((lhs_real, lhs_im): Rectangular, (rhs_real, rhs_im): Rectangular) -> Rectangular {
+    (
+        lhs_real * rhs_real - lhs_im * rhs_im,
+        lhs_real * rhs_im + lhs_im * rhs_real,
+    )
+}
+
+fn complex_div((lhs_real, lhs_im): Rectangular, (rhs_real, rhs_im): Rectangular) -> Rectangular {
+    let denominator = rhs_real * rhs_real + rhs_im * rhs_im;
+    (
+        (lhs_real * rhs_real + lhs_im * rhs_im) / denominator,
+        (lhs_im * rhs_real - lhs_real * rhs_im) / denominator,
+    )
+}
+
+/// Raises to an integer power by repeated squaring, which keeps results
+/// such as `i^2` free of rounding in the argument.
+fn complex_powi(mut base: Rectangular, power: i64) -> Rectangular {
+    let mut result = (1.0, 0.0);
+    let mut exponent = power.unsigned_abs();
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = complex_mul(result, base);
+        }
+        base = complex_mul(base, base);
+        exponent >>= 1;
+    }
+    if power < 0 {
+        complex_div((1.0, 0.0), result)
+    } else {
+        result
+    }
+}
+
+fn complex_exp((real, im): Rectangular) -> Rectangular {
+    let magnitude = real.exp();
+    (magnitude * im.cos(), magnitude * im.sin())
+}
+
+/// The principal logarithm, with the imaginary part in `(-pi, pi]`.
+fn complex_ln((real, im): Rectangular) -> Rectangular {
+    (real.hypot(im).ln(), im.atan2(real))
+}
+
+/// The principal square root, with a non-negative real part.
+fn complex_sqrt((real, im): Rectangular) -> Rectangular {
+    let magnitude = real.hypot(im);
+    (
+        ((magnitude + real) / 2.0).sqrt(),
+        ((magnitude - real) / 2.0).sqrt().copysign(im),
+    )
+}
+
+fn complex_sin((real, im): Rectangular) -> Rectangular {
+    (real.sin() * im.cosh(), real.cos() * im.sinh())
+}
+
+fn complex_cos((real, im): Rectangular) -> Rectangular {
+    (real.cos() * im.cosh(), -real.sin() * im.sinh())
+}
+
+/// `asin z = -i log(iz + sqrt(1 - z^2))`
+fn complex_asin(z @ (real, im): Rectangular) -> Rectangular {
+    let (square_real, square_im) = complex_mul(z, z);
+    let (root_real, root_im) = complex_sqrt((1.0 - square_real, 0.0 - square_im));
+    let (log_real, log_im) = complex_ln((root_real - im, root_im + real));
+    (log_im, -log_real)
+}
+
+/// `atan z = (log(1 + iz) - log(1 - iz)) / 2i`
+fn complex_atan((real, im): Rectangular) -> Rectangular {
+    let (plus_real, plus_im) = complex_ln((1.0 - im, real));
+    let (minus_real, minus_im) = complex_ln((1.0 + im, -real));
+    ((plus_im - minus_im) / 2.0, (minus_real - plus_real) / 2.0)
+}
+
+/// Compares an exact number with a real without rounding the exact number.
+/// Every finite `f64` is itself an exact rational.
+fn compare_exact_real(exact: &BigRational, real: f64) -> Option<Ordering> {
+    if real.is_nan() {
+        None
+    } else if real.is_infinite() {
+        Some(if real > 0.0 {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        })
+    } else {
+        Some(exact.cmp(&BigRational::from_float(real)?))
+    }
+}
+
 impl TryInto<i32> for Number {
     type Error = InvalidArgument;
 
     fn try_into(self) -> Result<i32, Self::Error> {
         match self {
-            Self::Integer(value) => Ok(value),
-            Self::Real(value) => Ok(value as i32),
-            Self::Complex(_, _) => Err(InvalidArgument::InvalidType(
+            Self::Integer(value) => {
+                i32::try_from(value).map_err(|_| InvalidArgument::OutOfRange(self.to_string()))
+            }
+            Self::BigInteger(_) => Err(InvalidArgument::OutOfRange(self.to_string())),
+            _ => Err(InvalidArgument::InvalidType(
                 self.to_string(),
-                "integer".to_string(),
+                "exact integer".to_string(),
             )),
         }
     }
@@ -36,7 +874,7 @@ impl TryInto<i32> for &Number {
     type Error = InvalidArgument;
 
     fn try_into(self) -> Result<i32, Self::Error> {
-        (*self).try_into()
+        self.clone().try_into()
     }
 }
 
@@ -45,12 +883,11 @@ impl TryInto<f64> for Number {
 
     fn try_into(self) -> Result<f64, Self::Error> {
         match self {
-            Self::Integer(value) => Ok(value as f64),
-            Self::Real(value) => Ok(value),
             Self::Complex(_, _) => Err(InvalidArgument::InvalidType(
                 self.to_string(),
                 "real".to_string(),
             )),
+            _ => Ok(self.to_f64()),
         }
     }
 }
@@ -59,7 +896,23 @@ impl TryInto<f64> for &Number {
     type Error = InvalidArgument;
 
     fn try_into(self) -> Result<f64, Self::Error> {
-        (*self).try_into()
+        match self {
+            Number::Complex(_, _) => self.clone().try_into(),
+            _ => Ok(self.to_f64()),
+        }
+    }
+}
+
+/// Writes a real in a form that reads back to the same `f64`: the shortest
+/// round-trip digits, a `.0` on integral values, and `+inf.0`, `-inf.0` and
+/// `+nan.0` for the special values.
+fn fmt_real(value: f64, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if value.is_nan() {
+        write!(f, "+nan.0")
+    } else if value.is_infinite() {
+        write!(f, "{}inf.0", if value > 0.0 { "+" } else { "-" })
+    } else {
+        write!(f, "{:?}", value)
     }
 }
 
@@ -67,25 +920,18 @@ impl Display for Number {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Integer(value) => write!(f, "{}", value),
-            Self::Real(value) => write!(f, "{}", value),
+            Self::BigInteger(value) => write!(f, "{}", value),
+            Self::Rational(value) => write!(f, "{}", value),
+            Self::Real(value) => fmt_real(*value, f),
             Self::Complex(real, im) => {
-                if *im == 0.0 {
-                    write!(f, "{}", real)
-                } else if *real == 0.0 && *im == 1.0 {
-                    write!(f, "+i")
-                } else if *real == 0.0 && *im == -1.0 {
-                    write!(f, "-i")
-                } else if *real == 0.0 {
-                    write!(f, "{}i", im)
-                } else if *im == 1.0 {
-                    write!(f, "{}+i", real)
-                } else if *im == -1.0 {
-                    write!(f, "{}-i", real)
-                } else if *im > 0.0 {
-                    write!(f, "{}+{}i", real, im)
-                } else {
-                    write!(f, "{}{}i", real, im)
+                if *real != 0.0 || real.is_sign_negative() {
+                    fmt_real(*real, f)?;
+                }
+                if im.is_finite() && im.is_sign_positive() {
+                    write!(f, "+")?;
                 }
+                fmt_real(*im, f)?;
+                write!(f, "i")
             }
         }
     }
@@ -93,22 +939,14 @@ impl Display for Number {
 
 impl PartialEq for Number {
     fn eq(&self, other: &Self) -> bool {
-        match (*self, *other) {
-            (Self::Integer(lhs), Self::Integer(rhs)) => lhs == rhs,
-            (Self::Real(lhs), Self::Real(rhs)) => lhs == rhs,
+        match (self, other) {
             (Self::Complex(lhs_real, lhs_im), Self::Complex(rhs_real, rhs_im)) => {
                 lhs_real == rhs_real && lhs_im == rhs_im
             }
-            (Self::Integer(lhs), Self::Real(rhs)) => lhs as f64 == rhs,
-            (Self::Real(lhs), Self::Integer(rhs)) => lhs == rhs as f64,
-            (Self::Integer(lhs), Self::Complex(rhs_real, rhs_im)) => {
-                lhs as f64 == rhs_real && rhs_im == 0.0
+            (Self::Complex(real, im), number) | (number, Self::Complex(real, im)) => {
+                *im == 0.0 && Self::Real(*real) == *number
             }
-            (Self::Complex(lhs_real, lhs_im), Self::Integer(rhs)) => {
-                lhs_real == rhs as f64 && lhs_im == 0.0
-            }
-            (Self::Real(lhs), Self::Complex(rhs_real, rhs_im)) => lhs == rhs_real && rhs_im == 0.0,
-            (Self::Complex(lhs_real, lhs_im), Self::Real(rhs)) => lhs_real == rhs && lhs_im == 0.0,
+            _ => self.partial_cmp(other) == Some(Ordering::Equal),
         }
     }
 }
@@ -120,25 +958,17 @@ impl Add for Number {
 
     #[inline]
     fn add(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Self::Integer(lhs), Self::Integer(rhs)) => Self::Integer(lhs + rhs),
-            (Self::Real(lhs), Self::Real(rhs)) => Self::Real(lhs + rhs),
-            (Self::Complex(lhs_real, lhs_im), Self::Complex(rhs_real, rhs_im)) => {
-                Self::Complex(lhs_real + rhs_real, lhs_im + rhs_im)
-            }
-            (Self::Integer(lhs), Self::Real(rhs)) => Self::Real(lhs as f64 + rhs),
-            (Self::Real(lhs), Self::Integer(rhs)) => Self::Real(lhs + rhs as f64),
-            (Self::Integer(lhs), Self::Complex(rhs_real, rhs_im)) => {
-                Self::Complex(lhs as f64 + rhs_real, rhs_im)
-            }
-            (Self::Complex(lhs_real, lhs_im), Self::Integer(rhs)) => {
-                Self::Complex(lhs_real + rhs as f64, lhs_im)
+        if let (Self::Integer(lhs), Self::Integer(rhs)) = (&self, &rhs) {
+            if let Some(sum) = lhs.checked_add(*rhs) {
+                return Self::Integer(sum);
             }
-            (Self::Real(lhs), Self::Complex(rhs_real, rhs_im)) => {
-                Self::Complex(lhs + rhs_real, rhs_im)
-            }
-            (Self::Complex(lhs_real, lhs_im), Self::Real(rhs)) => {
-                Self::Complex(lhs_real + rhs, lhs_im)
+        }
+        match Self::coerce(&self, &rhs) {
+            Coerced::Integer(lhs, rhs) => (lhs + rhs).into(),
+            Coerced::Rational(lhs, rhs) => (lhs + rhs).into(),
+            Coerced::Real(lhs, rhs) => Self::Real(lhs + rhs),
+            Coerced::Complex((lhs_real, lhs_im), (rhs_real, rhs_im)) => {
+                Self::from((lhs_real + rhs_real, lhs_im + rhs_im))
             }
         }
     }
@@ -149,25 +979,17 @@ impl Sub for Number {
 
     #[inline]
     fn sub(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Self::Integer(lhs), Self::Integer(rhs)) => Self::Integer(lhs - rhs),
-            (Self::Real(lhs), Self::Real(rhs)) => Self::Real(lhs - rhs),
-            (Self::Complex(lhs_real, lhs_im), Self::Complex(rhs_real, rhs_im)) => {
-                Self::Complex(lhs_real - rhs_real, lhs_im - rhs_im)
-            }
-            (Self::Integer(lhs), Self::Real(rhs)) => Self::Real(lhs as f64 - rhs),
-            (Self::Real(lhs), Self::Integer(rhs)) => Self::Real(lhs - rhs as f64),
-            (Self::Integer(lhs), Self::Complex(rhs_real, rhs_im)) => {
-                Self::Complex(lhs as f64 - rhs_real, rhs_im)
+        if let (Self::Integer(lhs), Self::Integer(rhs)) = (&self, &rhs) {
+            if let Some(difference) = lhs.checked_sub(*rhs) {
+                return Self::Integer(difference);
             }
-            (Self::Complex(lhs_real, lhs_im), Self::Integer(rhs)) => {
-                Self::Complex(lhs_real - rhs as f64, lhs_im)
-            }
-            (Self::Real(lhs), Self::Complex(rhs_real, rhs_im)) => {
-                Self::Complex(lhs - rhs_real, rhs_im)
-            }
-            (Self::Complex(lhs_real, lhs_im), Self::Real(rhs)) => {
-                Self::Complex(lhs_real - rhs, lhs_im)
+        }
+        match Self::coerce(&self, &rhs) {
+            Coerced::Integer(lhs, rhs) => (lhs - rhs).into(),
+            Coerced::Rational(lhs, rhs) => (lhs - rhs).into(),
+            Coerced::Real(lhs, rhs) => Self::Real(lhs - rhs),
+            Coerced::Complex((lhs_real, lhs_im), (rhs_real, rhs_im)) => {
+                Self::from((lhs_real - rhs_real, lhs_im - rhs_im))
             }
         }
     }
@@ -178,28 +1000,17 @@ impl Mul for Number {
 
     #[inline]
     fn mul(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Self::Integer(lhs), Self::Integer(rhs)) => Self::Integer(lhs * rhs),
-            (Self::Real(lhs), Self::Real(rhs)) => Self::Real(lhs * rhs),
-            (Self::Complex(lhs_real, lhs_im), Self::Complex(rhs_real, rhs_im)) => Self::Complex(
-                lhs_real * rhs_real - lhs_im * rhs_im,
-                lhs_real * rhs_im + lhs_im * rhs_real,
-            ),
-            (Self::Integer(lhs), Self::Real(rhs)) => Self::Real(lhs as f64 * rhs),
-            (Self::Real(lhs), Self::Integer(rhs)) => Self::Real(lhs * rhs as f64),
-            (Self::Integer(lhs), Self::Complex(rhs_real, rhs_im)) => {
-                Self::Complex(lhs as f64 * rhs_real, lhs as f64 * rhs_im)
-            }
-            (Self::Complex(lhs_real, lhs_im), Self::Integer(rhs)) => {
-                Self::Complex(lhs_real * rhs as f64, lhs_im * rhs as f64)
-            }
-            (Self::Real(lhs), Self::Complex(rhs_real, rhs_im)) => {
-                Self::Complex(lhs * rhs_real, lhs * rhs_im)
-            }
-            (Self::Complex(lhs_real, lhs_im), Self::Real(rhs)) => {
-                Self::Complex(lhs_real * rhs, lhs_im * rhs)
+        if let (Self::Integer(lhs), Self::Integer(rhs)) = (&self, &rhs) {
+            if let Some(product) = lhs.checked_mul(*rhs) {
+                return Self::Integer(product);
             }
         }
+        match Self::coerce(&self, &rhs) {
+            Coerced::Integer(lhs, rhs) => (lhs * rhs).into(),
+            Coerced::Rational(lhs, rhs) => (lhs * rhs).into(),
+            Coerced::Real(lhs, rhs) => Self::Real(lhs * rhs),
+            Coerced::Complex(lhs, rhs) => complex_mul(lhs, rhs).into(),
+        }
     }
 }
 
@@ -208,36 +1019,19 @@ impl Div for Number {
 
     #[inline]
     fn div(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Self::Integer(lhs), Self::Integer(rhs)) => Self::Integer(lhs / rhs),
-            (Self::Real(lhs), Self::Real(rhs)) => Self::Real(lhs / rhs),
-            (Self::Complex(lhs_real, lhs_im), Self::Complex(rhs_real, rhs_im)) => {
-                let denominator = rhs_real * rhs_real + rhs_im * rhs_im;
-                Self::Complex(
-                    (lhs_real * rhs_real + lhs_im * rhs_im) / denominator,
-                    (lhs_im * rhs_real - lhs_real * rhs_im) / denominator,
-                )
-            }
-            (Self::Integer(lhs), Self::Real(rhs)) => Self::Real(lhs as f64 / rhs),
-            (Self::Real(lhs), Self::Integer(rhs)) => Self::Real(lhs / rhs as f64),
-            (Self::Integer(lhs), Self::Complex(rhs_real, rhs_im)) => {
-                let denominator = rhs_real * rhs_real + rhs_im * rhs_im;
-                Self::Complex(
-                    lhs as f64 * rhs_real / denominator,
-                    -lhs as f64 * rhs_im / denominator,
-                )
-            }
-            (Self::Complex(lhs_real, lhs_im), Self::Integer(rhs)) => {
-                Self::Complex(lhs_real / rhs as f64, lhs_im / rhs as f64)
-            }
-            (Self::Real(lhs), Self::Complex(rhs_real, rhs_im)) => {
-                let denominator = rhs_real * rhs_real + rhs_im * rhs_im;
-                Self::Complex(lhs * rhs_real / denominator, -lhs * rhs_im / denominator)
-            }
-            (Self::Complex(lhs_real, lhs_im), Self::Real(rhs)) => {
-                Self::Complex(lhs_real / rhs, lhs_im / rhs)
+        if let (Self::Integer(lhs), Self::Integer(rhs)) = (&self, &rhs) {
+            if lhs.checked_rem(*rhs) == Some(0) {
+                if let Some(quotient) = lhs.checked_div(*rhs) {
+                    return Self::Integer(quotient);
+                }
             }
         }
+        match Self::coerce(&self, &rhs) {
+            Coerced::Integer(lhs, rhs) => BigRational::new(lhs, rhs).into(),
+            Coerced::Rational(lhs, rhs) => (lhs / rhs).into(),
+            Coerced::Real(lhs, rhs) => Self::Real(lhs / rhs),
+            Coerced::Complex(lhs, rhs) => complex_div(lhs, rhs).into(),
+        }
     }
 }
 
@@ -247,7 +1041,11 @@ impl Neg for Number {
     #[inline]
     fn neg(self) -> Self::Output {
         match self {
-            Self::Integer(value) => Self::Integer(-value),
+            Self::Integer(value) => value
+                .checked_neg()
+                .map_or_else(|| (-BigInt::from(value)).into(), Self::Integer),
+            Self::BigInteger(value) => (-value).into(),
+            Self::Rational(value) => Self::Rational(Box::new(-*value)),
             Self::Real(value) => Self::Real(-value),
             Self::Complex(real, im) => Self::Complex(-real, -im),
         }
@@ -257,28 +1055,28 @@ impl Neg for Number {
 impl AddAssign for Number {
     #[inline]
     fn add_assign(&mut self, rhs: Self) {
-        *self = *self + rhs;
+        *self = std::mem::replace(self, Self::Integer(0)) + rhs;
     }
 }
 
 impl SubAssign for Number {
     #[inline]
     fn sub_assign(&mut self, rhs: Self) {
-        *self = *self - rhs;
+        *self = std::mem::replace(self, Self::Integer(0)) - rhs;
     }
 }
 
 impl MulAssign for Number {
     #[inline]
     fn mul_assign(&mut self, rhs: Self) {
-        *self = *self * rhs;
+        *self = std::mem::replace(self, Self::Integer(0)) * rhs;
     }
 }
 
 impl DivAssign for Number {
     #[inline]
     fn div_assign(&mut self, rhs: Self) {
-        *self = *self / rhs;
+        *self = std::mem::replace(self, Self::Integer(0)) / rhs;
     }
 }
 
@@ -290,7 +1088,7 @@ macro_rules! forward_ref {
 
             #[inline]
             fn $method(self, other: $u) -> <$t as $imp<$u>>::Output {
-                $imp::$method(*self, other)
+                $imp::$method(self.clone(), other)
             }
         }
 
@@ -299,7 +1097,7 @@ macro_rules! forward_ref {
 
             #[inline]
             fn $method(self, other: &'a $u) -> <$t as $imp<$u>>::Output {
-                $imp::$method(self, *other)
+                $imp::$method(self, other.clone())
             }
         }
 
@@ -308,7 +1106,7 @@ macro_rules! forward_ref {
 
             #[inline]
             fn $method(self, other: &'a $u) -> <$t as $imp<$u>>::Output {
-                $imp::$method(*self, *other)
+                $imp::$method(self.clone(), other.clone())
             }
         }
     };
@@ -323,128 +1121,807 @@ forward_ref! { Add add, Sub sub, Mul mul, Div div }
 
 impl From<i32> for Number {
     fn from(value: i32) -> Self {
+        Self::Integer(value.into())
+    }
+}
+
+impl From<i64> for Number {
+    fn from(value: i64) -> Self {
         Self::Integer(value)
     }
 }
 
+impl From<BigInt> for Number {
+    fn from(value: BigInt) -> Self {
+        match value.to_i64() {
+            Some(value) => Self::Integer(value),
+            None => Self::BigInteger(value),
+        }
+    }
+}
+
 impl From<f64> for Number {
     fn from(value: f64) -> Self {
         Self::Real(value)
     }
 }
 
+/// Builds an inexact number from rectangular parts. A zero imaginary part
+/// gives a real.
 impl From<(f64, f64)> for Number {
-    fn from(value: (f64, f64)) -> Self {
-        Self::Complex(value.0, value.1)
+    fn from((real, im): (f64, f64)) -> Self {
+        if im == 0.0 {
+            Self::Real(real)
+        } else {
+            Self::Complex(real, im)
+        }
     }
 }
 
-impl TryFrom<&str> for Number {
-    type Error = ParseError;
+impl From<BigRational> for Number {
+    fn from(value: BigRational) -> Self {
+        if value.is_integer() {
+            value.to_integer().into()
+        } else {
+            Self::Rational(Box::new(value))
+        }
+    }
+}
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        const COMPLEX: Lazy<[Regex; 7]> = Lazy::new(|| {
-            [
-                Regex::new(r#"^(?P<real>[+-]?[0-9]+(\.[0-9]+)?)(?P<im>[+-][0-9]+(\.[0-9]+)?)i$"#)
-                    .unwrap(),
-                Regex::new(r#"^(?P<im>[+-]?[0-9]+(\.[0-9]+)?)i(?P<real>[+-][0-9]+(\.[0-9]+)?)$"#)
-                    .unwrap(),
-                Regex::new(r#"^(?P<real>[+-]?[0-9]+(\.[0-9]+)?)@(?P<im>[+-]?[0-9]+(\.[0-9]+)?)$"#)
-                    .unwrap(),
-                Regex::new(r#"^(?<real>[+-]?[0-9]+(\.[0-9]+)?)(?<sign>[+-])i$"#).unwrap(),
-                Regex::new(r#"^(?P<sign>[+-])i(?P<real>[+-][0-9]+(\.[0-9]+)?)$"#).unwrap(),
-                Regex::new(r#"^(?<im>[+-]?[0-9]+(\.[0-9]+)?)i$"#).unwrap(),
-                Regex::new(r#"^(?P<sign>[+-])i$"#).unwrap(),
-            ]
-        });
-        if let Ok(value) = value.parse::<i32>() {
-            Ok(Self::Integer(value))
-        } else if let Ok(value) = value.parse::<f64>() {
-            Ok(Self::Real(value))
-        } else if let Some(caps) = COMPLEX
-            .iter()
-            .map(|pattern| pattern.captures(value))
-            .skip_while(|res| res.is_none())
-            .next()
-            .flatten()
-        {
-            let real = match caps.name("real") {
-                Some(real) => real
-                    .as_str()
-                    .parse::<f64>()
-                    .map_err(|_| ParseError::InvalidNumber(value.to_string()))?,
-                None => 0.0,
-            };
-            let im = match caps.name("im") {
-                Some(im) => im
-                    .as_str()
-                    .parse::<f64>()
-                    .map_err(|_| ParseError::InvalidNumber(value.to_string()))?,
-                None => {
-                    if let Some(sign) = caps.name("sign") {
-                        match sign.as_str() {
-                            "+" => 1.0,
-                            "-" => -1.0,
-                            _ => 0.0,
-                        }
-                    } else {
-                        0.0
+/// Largest decimal exponent accepted in an exact literal such as `#e1e400`.
+const MAX_EXACT_EXPONENT: i64 = 100_000;
+
+/// A real component of a numeric literal before exactness is applied.
+enum Real<'a> {
+    /// An integer or `n/d` literal, sign included.
+    Exact(Number),
+    /// A radix 10 literal with a decimal point or an exponent, sign included.
+    Decimal(&'a str),
+    /// `+inf.0`, `-inf.0`, `+nan.0` or `-nan.0`.
+    Special(f64),
+}
+
+impl Real<'_> {
+    fn into_number(self, exactness: Option<char>) -> Result<Number, &'static str> {
+        match (self, exactness) {
+            (Real::Exact(number), Some('i')) => Ok(number.to_inexact()),
+            (Real::Exact(number), _) => Ok(number),
+            (Real::Decimal(text), Some('e')) => exact_decimal(text),
+            (Real::Decimal(text), _) => text
+                .parse::<f64>()
+                .map(Number::Real)
+                .map_err(|_| "invalid decimal"),
+            (Real::Special(_), Some('e')) => {
+                Err("infinities and NaNs have no exact representation")
+            }
+            (Real::Special(value), _) => Ok(Number::Real(value)),
+        }
+    }
+}
+
+/// Reads the exact value of a decimal literal, e.g. `#e1.25e1` is `25/2`.
+fn exact_decimal(text: &str) -> Result<Number, &'static str> {
+    let (mantissa, exponent) = match text.split_once('e') {
+        Some((mantissa, exponent)) => (
+            mantissa,
+            exponent
+                .parse::<i64>()
+                .ok()
+                .filter(|exponent| exponent.abs() <= MAX_EXACT_EXPONENT)
+                .ok_or("exponent is too large for an exact number")?,
+        ),
+        None => (text, 0),
+    };
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = BigInt::parse_bytes(format!("{}{}", whole, fraction).as_bytes(), 10)
+        .ok_or("invalid decimal")?;
+    let scale = exponent - fraction.len() as i64;
+    let power = num_traits::pow(BigInt::from(10), scale.unsigned_abs() as usize);
+    Ok(if scale >= 0 {
+        (digits * power).into()
+    } else {
+        BigRational::new(digits, power).into()
+    })
+}
+
+/// Parses an unsigned integer in `radix`.
+fn parse_uinteger(digits: &str, radix: u32) -> Number {
+    match i64::from_str_radix(digits, radix) {
+        Ok(value) => Number::Integer(value),
+        Err(_) => BigInt::parse_bytes(digits.as_bytes(), radix)
+            .expect("digits were validated")
+            .into(),
+    }
+}
+
+/// Hand-written reader for the R7RS `<number>` grammar. `text` is already
+/// lowercased, and every method consumes a prefix of it.
+struct NumberReader<'a> {
+    text: &'a str,
+    radix: u32,
+}
+
+impl<'a> NumberReader<'a> {
+    fn peek(&self) -> Option<char> {
+        self.text.chars().next()
+    }
+
+    fn eat(&mut self, prefix: &str) -> bool {
+        match self.text.strip_prefix(prefix) {
+            Some(rest) => {
+                self.text = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn digits(&mut self) -> &'a str {
+        let radix = self.radix;
+        let end = self
+            .text
+            .find(|c: char| !c.is_digit(radix))
+            .unwrap_or(self.text.len());
+        let (digits, rest) = self.text.split_at(end);
+        self.text = rest;
+        digits
+    }
+
+    /// `<real>`: an optionally signed `<ureal>`, or `<infnan>`.
+    fn real(&mut self) -> Result<Real<'a>, &'static str> {
+        let start = self.text;
+        let negative = match self.peek() {
+            Some('+') => false,
+            Some('-') => true,
+            _ => return self.ureal(start, false),
+        };
+        self.text = &self.text[1..];
+        if self.eat("inf.0") {
+            Ok(Real::Special(if negative {
+                f64::NEG_INFINITY
+            } else {
+                f64::INFINITY
+            }))
+        } else if self.eat("nan.0") {
+            Ok(Real::Special(f64::NAN))
+        } else {
+            self.ureal(start, negative)
+        }
+    }
+
+    /// `<ureal>`: an integer, `n/d` or, in radix 10, a decimal. `start` is
+    /// the text from the sign on, used to read decimals.
+    fn ureal(&mut self, start: &'a str, negative: bool) -> Result<Real<'a>, &'static str> {
+        let whole = self.digits();
+        if self.eat("/") {
+            let denominator = self.digits();
+            if whole.is_empty() || denominator.is_empty() {
+                return Err("a rational needs digits on both sides of /");
+            }
+            let denominator = parse_uinteger(denominator, self.radix);
+            if denominator.is_zero() {
+                return Err("zero denominator");
+            }
+            let number = parse_uinteger(whole, self.radix) / denominator;
+            return Ok(Real::Exact(if negative { -number } else { number }));
+        }
+        if !matches!(self.peek(), Some('.' | 'e')) {
+            if whole.is_empty() {
+                return Err("expected digits");
+            }
+            let number = parse_uinteger(whole, self.radix);
+            return Ok(Real::Exact(if negative { -number } else { number }));
+        }
+        if self.radix != 10 {
+            return Err("decimals are only allowed in radix 10");
+        }
+        let mut fraction = "";
+        if self.eat(".") {
+            fraction = self.digits();
+        }
+        if whole.is_empty() && fraction.is_empty() {
+            return Err("expected digits");
+        }
+        if self.eat("e") {
+            if !self.eat("+") {
+                self.eat("-");
+            }
+            if self.digits().is_empty() {
+                return Err("missing exponent digits");
+            }
+        }
+        Ok(Real::Decimal(&start[..start.len() - self.text.len()]))
+    }
+
+    /// `<complex>`, after any prefixes.
+    fn complex(mut self, exactness: Option<char>) -> Result<Number, &'static str> {
+        let imaginary = |real: Real, exactness| -> Result<Number, &'static str> {
+            match real.into_number(exactness)? {
+                im if im.is_exact() && im.is_zero() => Ok(im),
+                _ if exactness == Some('e') => Err("exact complex numbers are not supported"),
+                im => Ok(im),
+            }
+        };
+        if self.text.is_empty() {
+            return Err("empty number");
+        }
+        if matches!(self.text, "+i" | "-i") {
+            let im = Real::Exact(Number::Integer(if self.text == "+i" { 1 } else { -1 }));
+            return Ok(Number::from((0.0, imaginary(im, exactness)?.to_f64())));
+        }
+        let signed = matches!(self.peek(), Some('+' | '-'));
+        let first = self.real()?;
+        match self.peek() {
+            None => first.into_number(exactness),
+            Some('i') if signed && self.text == "i" => {
+                let im = imaginary(first, exactness)?;
+                Ok(if im.is_exact() && im.is_zero() {
+                    im
+                } else {
+                    Number::from((0.0, im.to_f64()))
+                })
+            }
+            Some('@') => {
+                self.text = &self.text[1..];
+                let magnitude = first.into_number(exactness)?;
+                let angle = self.real()?;
+                if !self.text.is_empty() {
+                    return Err("unexpected characters after the angle");
+                }
+                let angle = imaginary(angle, exactness)?;
+                if angle.is_exact() && angle.is_zero() {
+                    return Ok(magnitude);
+                }
+                Ok(Number::polar(magnitude.to_f64(), angle.to_f64()))
+            }
+            Some('+' | '-') => {
+                let real = first.into_number(exactness)?;
+                let im = if matches!(self.text, "+i" | "-i") {
+                    Real::Exact(Number::Integer(if self.text == "+i" { 1 } else { -1 }))
+                } else {
+                    let im = self.real()?;
+                    if self.text != "i" {
+                        return Err("the imaginary part must end with i");
                     }
+                    im
+                };
+                let im = imaginary(im, exactness)?;
+                Ok(if im.is_exact() && im.is_zero() {
+                    real
+                } else {
+                    Number::from((real.to_f64(), im.to_f64()))
+                })
+            }
+            Some(_) => Err("unexpected character"),
+        }
+    }
+}
+
+impl Number {
+    /// Parses a numeric literal: optional `#e`/`#i` and `#b`/`#o`/`#d`/`#x`
+    /// prefixes, then an integer, rational, decimal, `<infnan>`, rectangular
+    /// or polar complex number. Letters are not case sensitive.
+    /// `default_radix` is used when there is no radix prefix.
+    pub fn parse(value: &str, default_radix: u32) -> Result<Self, ParseError> {
+        let invalid = |reason: &'static str| ParseError::InvalidNumber(value.to_string(), reason);
+        let text = value.to_ascii_lowercase();
+        let mut exactness = None;
+        let mut radix = None;
+        let mut rest = text.as_str();
+        while let Some(prefix) = rest.strip_prefix('#') {
+            let mut characters = prefix.chars();
+            match characters.next() {
+                Some(c @ ('e' | 'i')) if exactness.is_none() => exactness = Some(c),
+                Some('e' | 'i') => return Err(invalid("more than one exactness prefix")),
+                Some(c @ ('b' | 'o' | 'd' | 'x')) if radix.is_none() => {
+                    radix = Some(match c {
+                        'b' => 2,
+                        'o' => 8,
+                        'd' => 10,
+                        _ => 16,
+                    })
                 }
-            };
-            Ok(Self::Complex(real, im))
-        } else {
-            Err(ParseError::InvalidNumber(value.to_string()))
+                Some('b' | 'o' | 'd' | 'x') => return Err(invalid("more than one radix prefix")),
+                _ => return Err(invalid("unknown prefix")),
+            }
+            rest = characters.as_str();
         }
+        NumberReader {
+            text: rest,
+            radix: radix.unwrap_or(default_radix),
+        }
+        .complex(exactness)
+        .map_err(invalid)
+    }
+}
+
+impl TryFrom<&str> for Number {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value, 10)
     }
 }
 
 impl PartialOrd for Number {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        match (*self, *other) {
-            (Self::Integer(lhs), Self::Integer(rhs)) => lhs.partial_cmp(&rhs),
-            (Self::Real(lhs), Self::Real(rhs)) => lhs.partial_cmp(&rhs),
+        match (self, other) {
+            (Self::Integer(lhs), Self::Integer(rhs)) => lhs.partial_cmp(rhs),
+            (Self::Real(lhs), Self::Real(rhs)) => lhs.partial_cmp(rhs),
             (Self::Complex(lhs_real, lhs_im), Self::Complex(rhs_real, rhs_im)) => {
                 if (lhs_real, lhs_im) == (rhs_real, rhs_im) {
                     Some(Ordering::Equal)
-                } else if lhs_im == 0.0 && rhs_im == 0.0 {
-                    lhs_real.partial_cmp(&rhs_real)
+                } else if *lhs_im == 0.0 && *rhs_im == 0.0 {
+                    lhs_real.partial_cmp(rhs_real)
                 } else {
                     None
                 }
             }
-            (Self::Integer(lhs), Self::Real(rhs)) => (lhs as f64).partial_cmp(&rhs),
-            (Self::Real(lhs), Self::Integer(rhs)) => lhs.partial_cmp(&(rhs as f64)),
-            (Self::Integer(lhs), Self::Complex(rhs_real, rhs_im)) => {
-                if rhs_im == 0.0 {
-                    (lhs as f64).partial_cmp(&rhs_real)
+            (Self::Complex(real, im), number) => {
+                if *im == 0.0 {
+                    Self::Real(*real).partial_cmp(number)
                 } else {
                     None
                 }
             }
-            (Self::Complex(lhs_real, lhs_im), Self::Integer(rhs)) => {
-                if lhs_im == 0.0 {
-                    lhs_real.partial_cmp(&(rhs as f64))
+            (number, Self::Complex(real, im)) => {
+                if *im == 0.0 {
+                    number.partial_cmp(&Self::Real(*real))
                 } else {
                     None
                 }
             }
-            (Self::Real(lhs), Self::Complex(rhs_real, rhs_im)) => {
-                if rhs_im == 0.0 {
-                    lhs.partial_cmp(&rhs_real)
-                } else {
-                    None
-                }
+            (Self::Real(lhs), rhs) => {
+                compare_exact_real(&rhs.to_rational(), *lhs).map(Ordering::reverse)
             }
-            (Self::Complex(lhs_real, lhs_im), Self::Real(rhs)) => {
-                if lhs_im == 0.0 {
-                    lhs_real.partial_cmp(&rhs)
-                } else {
-                    None
-                }
+            (lhs, Self::Real(rhs)) => compare_exact_real(&lhs.to_rational(), *rhs),
+            (Self::Rational(_), _) | (_, Self::Rational(_)) => {
+                self.to_rational().partial_cmp(&other.to_rational())
             }
+            (lhs, rhs) => lhs.to_big().partial_cmp(&rhs.to_big()),
         }
     }
 }
 
 // TODO: implement check_{operator} for Number
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overflow_promotes_to_big_integer() {
+        let product = Number::from(100000) * Number::from(100000);
+        assert_eq!(product, Number::Integer(10000000000));
+        let big = Number::Integer(i64::MAX) + Number::Integer(1);
+        assert!(matches!(big, Number::BigInteger(_)));
+        assert_eq!(big.to_string(), "9223372036854775808");
+        assert_eq!(big - Number::Integer(1), Number::Integer(i64::MAX));
+        assert!(matches!(-Number::Integer(i64::MIN), Number::BigInteger(_)));
+        assert!(matches!(
+            Number::Integer(i64::MIN) / Number::Integer(-1),
+            Number::BigInteger(_)
+        ));
+    }
+
+    #[test]
+    fn test_big_integer_literals() {
+        let literal = "123456789012345678901234567890";
+        let number = Number::try_from(literal).unwrap();
+        assert!(matches!(number, Number::BigInteger(_)));
+        assert_eq!(number.to_string(), literal);
+        assert_eq!(
+            Number::try_from("-9223372036854775808").unwrap(),
+            Number::Integer(i64::MIN)
+        );
+    }
+
+    #[test]
+    fn test_exact_comparison_with_reals() {
+        let big = Number::try_from("9007199254740993").unwrap();
+        assert!(big > Number::Real(9007199254740992.0));
+        assert_ne!(big, Number::Real(9007199254740992.0));
+        let huge = Number::try_from("100000000000000000000").unwrap();
+        assert_eq!(huge, Number::Real(1e20));
+        assert!(huge < Number::Real(f64::INFINITY));
+        assert!(huge > Number::Real(1e20 - 1e5));
+        assert_eq!(huge.partial_cmp(&Number::Real(f64::NAN)), None);
+    }
+
+    #[test]
+    fn test_rationals() {
+        let half = Number::from(1) / Number::from(2);
+        assert_eq!(half.to_string(), "1/2");
+        assert_eq!(
+            Number::from(6) / Number::from(4),
+            Number::try_from("3/2").unwrap()
+        );
+        assert_eq!(Number::try_from("-4/6").unwrap().to_string(), "-2/3");
+        assert_eq!(Number::try_from("4/2").unwrap(), Number::Integer(2));
+        assert!(Number::try_from("1/0").is_err());
+        assert!(Number::try_from("1/-2").is_err());
+
+        let third = Number::try_from("1/3").unwrap();
+        assert_eq!(&half + &third, Number::try_from("5/6").unwrap());
+        assert_eq!(&half - &half, Number::Integer(0));
+        assert_eq!(&half * Number::from(2), Number::Integer(1));
+        assert_eq!(-third.clone() * Number::from(3), Number::Integer(-1));
+        assert_eq!(&half + Number::Real(0.25), Number::Real(0.75));
+        assert!(third < half && half < Number::Real(0.5000001));
+        assert!(third > Number::Real(0.3333333333333333));
+        assert_eq!(&half + Number::Complex(0.0, 1.0), Number::Complex(0.5, 1.0));
+    }
+
+    #[test]
+    fn test_numerator_and_denominator() {
+        let number = Number::try_from("-6/4").unwrap();
+        assert_eq!(number.numerator(), Some(Number::Integer(-3)));
+        assert_eq!(number.denominator(), Some(Number::Integer(2)));
+        assert_eq!(Number::Integer(5).denominator(), Some(Number::Integer(1)));
+        assert_eq!(Number::Real(0.75).numerator(), Some(Number::Real(3.0)));
+        assert_eq!(Number::Real(0.75).denominator(), Some(Number::Real(4.0)));
+        assert_eq!(Number::Complex(1.0, 1.0).numerator(), None);
+    }
+
+    #[test]
+    fn test_prefixes_and_exactness() {
+        assert_eq!(Number::try_from("#x1F").unwrap(), Number::Integer(31));
+        assert_eq!(Number::try_from("#b-101").unwrap(), Number::Integer(-5));
+        assert_eq!(Number::try_from("#o17/2").unwrap().to_string(), "15/2");
+        assert_eq!(Number::try_from("#E#x10").unwrap(), Number::Integer(16));
+        assert_eq!(Number::try_from("#e1.5").unwrap().to_string(), "3/2");
+        assert!(Number::try_from("#i3/4").unwrap() == Number::Real(0.75));
+        assert!(!Number::try_from("#i1").unwrap().is_exact());
+        assert!(Number::try_from("#e#e1").is_err());
+        assert!(Number::try_from("#x1.5").is_err());
+        assert!(Number::try_from("#e+inf.0").is_err());
+
+        assert!(Number::Real(f64::NAN).to_exact().is_err());
+        assert!(Number::Real(f64::INFINITY).to_exact().is_err());
+        assert_eq!(Number::Real(-0.5).to_exact().unwrap().to_string(), "-1/2");
+    }
+
+    #[test]
+    fn test_strict_integer_conversions() {
+        assert_eq!(Number::Integer(3).to_index(), Ok(3));
+        assert!(Number::Integer(-1).to_index().is_err());
+        assert!(Number::Real(1.7).to_index().is_err());
+        assert!(Number::Real(1.0).to_index().is_err());
+        let truncated: Result<i32, _> = Number::Real(1.7).try_into();
+        assert!(truncated.is_err());
+        let overflow: Result<i32, _> = Number::Integer(1 << 40).try_into();
+        assert!(overflow.is_err());
+    }
+
+    fn parse(text: &str) -> Number {
+        Number::try_from(text).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    #[test]
+    fn test_decimal_syntax() {
+        assert_eq!(parse("1e10"), Number::Real(1e10));
+        assert_eq!(parse(".5"), Number::Real(0.5));
+        assert_eq!(parse("-.5e-1"), Number::Real(-0.05));
+        assert_eq!(parse("1."), Number::Real(1.0));
+        assert_eq!(parse("1.E2"), Number::Real(100.0));
+        assert_eq!(parse("#e1.25e1").to_string(), "25/2");
+        assert_eq!(parse("#e1.1").to_string(), "11/10");
+        assert_eq!(parse("#e-1e3"), Number::Integer(-1000));
+        assert_eq!(
+            parse("#e1e30").to_string(),
+            "1000000000000000000000000000000"
+        );
+        assert_eq!(parse("#i#x10"), Number::Real(16.0));
+        assert_eq!(parse("#XFF"), Number::Integer(255));
+        assert_eq!(parse("#x-ff/a").to_string(), "-51/2");
+    }
+
+    #[test]
+    fn test_special_reals() {
+        assert_eq!(parse("+inf.0"), Number::Real(f64::INFINITY));
+        assert_eq!(parse("-INF.0"), Number::Real(f64::NEG_INFINITY));
+        assert!(matches!(parse("+nan.0"), Number::Real(value) if value.is_nan()));
+        assert!(matches!(parse("-nan.0"), Number::Real(value) if value.is_nan()));
+        assert!(Number::try_from("inf.0").is_err());
+    }
+
+    #[test]
+    fn test_complex_syntax() {
+        assert_eq!(parse("1+2i"), Number::Complex(1.0, 2.0));
+        assert_eq!(parse("-1.5-2e1i"), Number::Complex(-1.5, -20.0));
+        assert_eq!(parse("1/2+i"), Number::Complex(0.5, 1.0));
+        assert_eq!(parse("+i"), Number::Complex(0.0, 1.0));
+        assert_eq!(parse("-3/4i"), Number::Complex(0.0, -0.75));
+        assert_eq!(parse("1+inf.0i"), Number::Complex(1.0, f64::INFINITY));
+        assert_eq!(parse("+inf.0-i"), Number::Complex(f64::INFINITY, -1.0));
+        assert_eq!(parse("#x10+ai"), Number::Complex(16.0, 10.0));
+        assert_eq!(parse("1+0i"), Number::Integer(1));
+        assert_eq!(parse("2@0"), Number::Integer(2));
+        assert_eq!(parse("1@1e0"), Number::Complex(1f64.cos(), 1f64.sin()));
+        assert_eq!(
+            parse("-2@-1/2"),
+            Number::Complex(-2.0 * 0.5f64.cos(), 2.0 * 0.5f64.sin())
+        );
+    }
+
+    #[test]
+    fn test_invalid_numbers() {
+        let reason = |text| match Number::try_from(text) {
+            Err(ParseError::InvalidNumber(_, reason)) => reason,
+            other => panic!("{} parsed as {:?}", text, other),
+        };
+        assert_eq!(reason(""), "empty number");
+        assert_eq!(reason("#q1"), "unknown prefix");
+        assert_eq!(reason("#x#b1"), "more than one radix prefix");
+        assert_eq!(reason("#i#e1"), "more than one exactness prefix");
+        assert_eq!(reason("1/0"), "zero denominator");
+        assert_eq!(reason("1/"), "a rational needs digits on both sides of /");
+        assert_eq!(reason("#b1.0"), "decimals are only allowed in radix 10");
+        assert_eq!(reason("1e"), "missing exponent digits");
+        assert_eq!(reason("."), "expected digits");
+        assert_eq!(reason("2i"), "unexpected character");
+        assert_eq!(reason("1+2"), "the imaginary part must end with i");
+        assert_eq!(reason("1@2x"), "unexpected characters after the angle");
+        assert_eq!(reason("#e1+2i"), "exact complex numbers are not supported");
+        assert_eq!(
+            reason("#e+nan.0"),
+            "infinities and NaNs have no exact representation"
+        );
+        assert_eq!(
+            Number::try_from("12abc").unwrap_err().to_string(),
+            "invalid number 12abc: unexpected character"
+        );
+    }
+
+    #[test]
+    fn test_integer_division() {
+        let divide =
+            |a: &str, b: &str, division| parse(a).integer_divide(&parse(b), division).unwrap();
+        assert_eq!(
+            divide("7", "-2", Division::Floor),
+            (parse("-4"), parse("-1"))
+        );
+        assert_eq!(
+            divide("7", "-2", Division::Truncate),
+            (parse("-3"), parse("1"))
+        );
+        assert_eq!(
+            divide("-7.", "2", Division::Floor),
+            (parse("-4."), parse("1."))
+        );
+        assert_eq!(
+            divide("100000000000000000000", "3", Division::Floor),
+            (parse("33333333333333333333"), parse("1"))
+        );
+        assert!(parse("1")
+            .integer_divide(&parse("0"), Division::Floor)
+            .is_err());
+        assert!(parse("1/2")
+            .integer_divide(&parse("1"), Division::Floor)
+            .is_err());
+        assert_eq!(parse("12").gcd(&parse("-18")).unwrap(), parse("6"));
+        assert_eq!(parse("4").lcm(&parse("6.")).unwrap(), parse("12."));
+    }
+
+    #[test]
+    fn test_rounding() {
+        let round = |text| parse(text).round(Rounding::Round).unwrap();
+        assert_eq!(round("2.5"), parse("2."));
+        assert_eq!(round("3.5"), parse("4."));
+        assert_eq!(round("-5/2"), parse("-2"));
+        assert_eq!(round("7/2"), parse("4"));
+        assert_eq!(parse("-7/2").round(Rounding::Floor).unwrap(), parse("-4"));
+        assert_eq!(
+            parse("-3.5").round(Rounding::Ceiling).unwrap(),
+            parse("-3.")
+        );
+        assert_eq!(
+            parse("-3.5").round(Rounding::Truncate).unwrap(),
+            parse("-3.")
+        );
+    }
+
+    #[test]
+    fn test_roots_and_powers() {
+        assert_eq!(parse("16").sqrt(), parse("4"));
+        assert_eq!(parse("4/9").sqrt(), parse("2/3"));
+        assert_eq!(parse("2").sqrt(), Number::Real(2f64.sqrt()));
+        assert_eq!(parse("-4").sqrt(), parse("+2.i"));
+        assert_eq!(
+            parse("17").exact_integer_sqrt().unwrap(),
+            (parse("4"), parse("1"))
+        );
+        assert!(parse("-1").exact_integer_sqrt().is_err());
+        assert_eq!(
+            parse("2").expt(&parse("100")).unwrap(),
+            parse("1267650600228229401496703205376")
+        );
+        assert_eq!(parse("2").expt(&parse("-2")).unwrap(), parse("1/4"));
+        let one = parse("0.").expt(&parse("0")).unwrap();
+        assert_eq!(one, parse("1."));
+        assert!(!one.is_exact());
+        assert_eq!(parse("1+2i").expt(&parse("0")).unwrap(), parse("1."));
+        assert!(parse("0").expt(&parse("0")).unwrap().is_exact());
+        assert!(parse("0").expt(&parse("-1")).is_err());
+        assert_eq!(parse("4").expt(&parse("1/2")).unwrap(), parse("2"));
+        assert_eq!(parse("8/27").expt(&parse("-2/3")).unwrap(), parse("9/4"));
+        assert_eq!(parse("0").expt(&parse("1/2")).unwrap(), parse("0"));
+        assert_eq!(
+            parse("2").expt(&parse("1/2")).unwrap(),
+            Number::Real(2f64.sqrt())
+        );
+        assert!(!parse("4").expt(&parse("0.5")).unwrap().is_exact());
+        // exact complex numbers are not representable
+        close(parse("-4").expt(&parse("1/2")).unwrap(), (0.0, 2.0));
+    }
+
+    #[test]
+    fn test_radix_strings() {
+        assert_eq!(parse("255").to_string_radix(16).unwrap(), "ff");
+        assert_eq!(parse("-5/3").to_string_radix(2).unwrap(), "-101/11");
+        assert_eq!(parse("1.5").to_string_radix(10).unwrap(), "1.5");
+        assert!(parse("1.5").to_string_radix(2).is_err());
+        assert_eq!(Number::parse("ff", 16).unwrap(), parse("255"));
+        assert_eq!(Number::parse("#d10", 16).unwrap(), parse("10"));
+    }
+
+    fn close(number: Number, (real, im): (f64, f64)) {
+        let (number_real, number_im) = number.to_complex();
+        assert!(
+            (number_real - real).abs() < 1e-12 && (number_im - im).abs() < 1e-12,
+            "{} is not {}+{}i",
+            number,
+            real,
+            im
+        );
+    }
+
+    #[test]
+    fn test_complex_procedures() {
+        assert_eq!(
+            Number::make_rectangular(&parse("1"), &parse("2")).unwrap(),
+            parse("1+2i")
+        );
+        assert!(Number::make_rectangular(&parse("1/2"), &parse("0"))
+            .unwrap()
+            .is_exact());
+        assert!(Number::make_polar(&parse("+i"), &parse("1")).is_err());
+        assert_eq!(parse("3+4i").magnitude(), parse("5."));
+        assert_eq!(parse("-5").magnitude(), parse("5"));
+        assert_eq!(parse("1+2i").real_part(), parse("1."));
+        assert_eq!(parse("1+2i").imag_part(), parse("2."));
+        assert!(parse("3").imag_part().is_exact());
+        assert_eq!(parse("1").angle(), parse("0"));
+        close(parse("-1").angle(), (std::f64::consts::PI, 0.0));
+        close(parse("+i").angle(), (std::f64::consts::FRAC_PI_2, 0.0));
+        close(
+            Number::make_polar(&parse("2"), &parse("0.5")).unwrap(),
+            (2.0 * 0.5f64.cos(), 2.0 * 0.5f64.sin()),
+        );
+    }
+
+    #[test]
+    fn test_complex_functions() {
+        let pi = std::f64::consts::PI;
+        assert_eq!(parse("-4.").sqrt(), parse("+2.i"));
+        close(parse("+2i").sqrt(), (1.0, 1.0));
+        close(parse("-2i").sqrt(), (1.0, -1.0));
+        close(parse("+3.141592653589793i").exp(), (-1.0, 0.0));
+        close(parse("-1").ln(), (0.0, pi));
+        close(parse("+i").ln(), (0.0, pi / 2.0));
+        close(parse("2").asin(), (pi / 2.0, -1.3169578969248166));
+        close(parse("2").acos(), (0.0, 1.3169578969248166));
+        close(parse("+2i").atan(), (pi / 2.0, 0.5493061443340549));
+        close(parse("1+i").sin(), (1.2984575814159773, 0.6349639147847361));
+        close(
+            parse("1+i").cos(),
+            (0.8337300251311491, -0.9888977057628651),
+        );
+        assert_eq!(parse("+i").expt(&parse("2")).unwrap(), parse("-1."));
+        assert_eq!(parse("+i").expt(&parse("-1")).unwrap(), parse("-i"));
+        close(
+            parse("+i").expt(&parse("+i")).unwrap(),
+            ((-pi / 2.0).exp(), 0.0),
+        );
+        close(
+            parse("-8.").expt(&parse("1/3")).unwrap(),
+            (1.0, 3f64.sqrt()),
+        );
+    }
+
+    #[test]
+    fn test_complex_collapse() {
+        assert!(parse("1+0.i").is_real());
+        assert!((parse("1+i") - parse("+i")).is_real());
+        assert!((parse("+i") * parse("+i")).is_real());
+        assert!((parse("1+i") / parse("1+i")).is_real());
+        assert_eq!(parse("+i").partial_cmp(&parse("1")), None);
+    }
+
+    #[test]
+    fn test_write_round_trip() {
+        let cases = [
+            (Number::Real(2.0), "2.0"),
+            (Number::Real(-0.0), "-0.0"),
+            (Number::Real(0.1), "0.1"),
+            (Number::Real(1e300), "1e300"),
+            (Number::Real(1.25e-10), "1.25e-10"),
+            (Number::Real(f64::INFINITY), "+inf.0"),
+            (Number::Real(f64::NEG_INFINITY), "-inf.0"),
+            (Number::Real(f64::NAN), "+nan.0"),
+            (parse("-5/7"), "-5/7"),
+            (
+                parse("123456789012345678901234567890"),
+                "123456789012345678901234567890",
+            ),
+            (Number::Complex(1.0, 2.0), "1.0+2.0i"),
+            (Number::Complex(0.0, -2.5), "-2.5i"),
+            (Number::Complex(-0.0, 1.0), "-0.0+1.0i"),
+            (Number::Complex(1.0, f64::NEG_INFINITY), "1.0-inf.0i"),
+            (Number::Complex(f64::NAN, f64::NAN), "+nan.0+nan.0i"),
+        ];
+        for (number, text) in cases {
+            assert_eq!(number.to_string(), text);
+            // Debug output tells apart exactness, -0.0 and NaN, like eqv?.
+            assert_eq!(format!("{:?}", parse(text)), format!("{:?}", number));
+        }
+        for value in [
+            0.3,
+            1.0 / 3.0,
+            f64::MAX,
+            f64::MIN_POSITIVE,
+            5e-324,
+            9007199254740993.0,
+        ] {
+            assert_eq!(parse(&Number::Real(value).to_string()), Number::Real(value));
+        }
+    }
+
+    #[test]
+    fn test_bitwise() {
+        assert_eq!(parse("12").bitwise_and(&parse("-3")).unwrap(), parse("12"));
+        assert_eq!(parse("-12").bitwise_or(&parse("3")).unwrap(), parse("-9"));
+        assert_eq!(
+            parse("-1")
+                .bitwise_xor(&parse("18446744073709551616"))
+                .unwrap(),
+            parse("-18446744073709551617")
+        );
+        assert_eq!(parse("5").bitwise_not().unwrap(), parse("-6"));
+        assert!(parse("1.").bitwise_not().is_err());
+        assert_eq!(
+            parse("-9223372036854775808")
+                .arithmetic_shift(&parse("1"))
+                .unwrap(),
+            parse("-18446744073709551616")
+        );
+        assert_eq!(
+            parse("-18446744073709551616")
+                .arithmetic_shift(&parse("-1"))
+                .unwrap(),
+            Number::Integer(i64::MIN)
+        );
+        assert_eq!(
+            parse("-5").arithmetic_shift(&parse("-1")).unwrap(),
+            parse("-3")
+        );
+        assert_eq!(
+            parse("-5")
+                .arithmetic_shift(&parse("-100000000000000000000"))
+                .unwrap(),
+            parse("-1")
+        );
+        assert!(parse("1").arithmetic_shift(&parse("100000000000")).is_err());
+        assert_eq!(parse("-8").bit_count().unwrap(), parse("3"));
+        assert_eq!(parse("-256").integer_length().unwrap(), parse("8"));
+        assert!(parse("-1").is_bit_set(1000).unwrap());
+        assert_eq!(parse("-1").copy_bit(0, false).unwrap(), parse("-2"));
+        assert_eq!(
+            parse("0").copy_bit(64, true).unwrap(),
+            parse("18446744073709551616")
+        );
+        assert_eq!(parse("109").bit_field(2, 6).unwrap(), parse("11"));
+        assert_eq!(parse("-1").bit_field(0, 3).unwrap(), parse("7"));
+        assert!(parse("1").bit_field(3, 2).is_err());
+    }
+}
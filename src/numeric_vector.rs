@@ -0,0 +1,503 @@
+use std::fmt::Display;
+
+use crate::{error::InvalidArgument, number::Number};
+
+/// The element type of a homogeneous vector, named by its SRFI 160 tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumericKind {
+    U8,
+    S8,
+    U16,
+    S16,
+    U32,
+    S32,
+    U64,
+    S64,
+    F32,
+    F64,
+}
+
+impl NumericKind {
+    pub const ALL: [Self; 10] = [
+        Self::U8,
+        Self::S8,
+        Self::U16,
+        Self::S16,
+        Self::U32,
+        Self::S32,
+        Self::U64,
+        Self::S64,
+        Self::F32,
+        Self::F64,
+    ];
+
+    pub fn tag(self) -> &'static str {
+        match self {
+            Self::U8 => "u8",
+            Self::S8 => "s8",
+            Self::U16 => "u16",
+            Self::S16 => "s16",
+            Self::U32 => "u32",
+            Self::S32 => "s32",
+            Self::U64 => "u64",
+            Self::S64 => "s64",
+            Self::F32 => "f32",
+            Self::F64 => "f64",
+        }
+    }
+
+    /// The size of an element in bytes.
+    pub fn element_size(self) -> usize {
+        match self {
+            Self::U8 | Self::S8 => 1,
+            Self::U16 | Self::S16 => 2,
+            Self::U32 | Self::S32 | Self::F32 => 4,
+            Self::U64 | Self::S64 | Self::F64 => 8,
+        }
+    }
+
+    /// Looks a kind up by its tag, ignoring case as the lexer does for `#u8(`.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.tag().eq_ignore_ascii_case(tag))
+    }
+}
+
+/// A SRFI 160 homogeneous vector: the elements are stored unboxed, so the
+/// vector can be handed to Rust code as a plain slice.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NumericVector {
+    U8(Vec<u8>),
+    S8(Vec<i8>),
+    U16(Vec<u16>),
+    S16(Vec<i16>),
+    U32(Vec<u32>),
+    S32(Vec<i32>),
+    U64(Vec<u64>),
+    S64(Vec<i64>),
+    F32(Vec<f32>),
+    F64(Vec<f64>),
+}
+
+/// Runs `$body` with `$elements` bound to the storage of `$vector`.
+macro_rules! each {
+    ($vector:expr, $elements:ident => $body:expr) => {
+        match $vector {
+            NumericVector::U8($elements) => $body,
+            NumericVector::S8($elements) => $body,
+            NumericVector::U16($elements) => $body,
+            NumericVector::S16($elements) => $body,
+            NumericVector::U32($elements) => $body,
+            NumericVector::S32($elements) => $body,
+            NumericVector::U64($elements) => $body,
+            NumericVector::S64($elements) => $body,
+            NumericVector::F32($elements) => $body,
+            NumericVector::F64($elements) => $body,
+        }
+    };
+}
+
+/// Runs `$body` with `$element` naming the Rust element type of `$kind`.
+macro_rules! with_kind {
+    ($kind:expr, $element:ident => $body:expr) => {
+        match $kind {
+            NumericKind::U8 => {
+                type $element = u8;
+                $body
+            }
+            NumericKind::S8 => {
+                type $element = i8;
+                $body
+            }
+            NumericKind::U16 => {
+                type $element = u16;
+                $body
+            }
+            NumericKind::S16 => {
+                type $element = i16;
+                $body
+            }
+            NumericKind::U32 => {
+                type $element = u32;
+                $body
+            }
+            NumericKind::S32 => {
+                type $element = i32;
+                $body
+            }
+            NumericKind::U64 => {
+                type $element = u64;
+                $body
+            }
+            NumericKind::S64 => {
+                type $element = i64;
+                $body
+            }
+            NumericKind::F32 => {
+                type $element = f32;
+                $body
+            }
+            NumericKind::F64 => {
+                type $element = f64;
+                $body
+            }
+        }
+    };
+}
+
+mod sealed {
+    use crate::{error::InvalidArgument, number::Number};
+
+    use super::NumericVector;
+
+    pub trait Element: Copy + Sized {
+        fn from_number(number: &Number) -> Result<Self, InvalidArgument>;
+        fn to_number(self) -> Number;
+        fn fmt_element(self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result;
+        fn slice(vector: &NumericVector) -> Option<&[Self]>;
+        fn slice_mut(vector: &mut NumericVector) -> Option<&mut [Self]>;
+        fn wrap(elements: Vec<Self>) -> NumericVector;
+        fn write_le(self, bytes: &mut Vec<u8>);
+        fn read_le(bytes: &[u8]) -> Self;
+    }
+}
+
+use sealed::Element;
+
+/// A Rust type that homogeneous vectors store unboxed: `u8`, `i8`, `u16`,
+/// `i16`, `u32`, `i32`, `u64`, `i64`, `f32` or `f64`.
+pub trait NumericElement: Element {}
+
+macro_rules! element {
+    ($variant:ident, $element:ident) => {
+        impl NumericElement for $element {}
+
+        impl Element for $element {
+            fn from_number(number: &Number) -> Result<Self, InvalidArgument> {
+                element!(@from_number $element, number)
+            }
+
+            fn to_number(self) -> Number {
+                element!(@to_number $element, self)
+            }
+
+            fn fmt_element(self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                element!(@fmt $element, self, f)
+            }
+
+            fn slice(vector: &NumericVector) -> Option<&[Self]> {
+                match vector {
+                    NumericVector::$variant(elements) => Some(elements),
+                    _ => None,
+                }
+            }
+
+            fn slice_mut(vector: &mut NumericVector) -> Option<&mut [Self]> {
+                match vector {
+                    NumericVector::$variant(elements) => Some(elements),
+                    _ => None,
+                }
+            }
+
+            fn wrap(elements: Vec<Self>) -> NumericVector {
+                NumericVector::$variant(elements)
+            }
+
+            fn write_le(self, bytes: &mut Vec<u8>) {
+                bytes.extend_from_slice(&self.to_le_bytes());
+            }
+
+            fn read_le(bytes: &[u8]) -> Self {
+                Self::from_le_bytes(bytes.try_into().expect("element size"))
+            }
+        }
+    };
+    // A finite number too large for the element is out of range, rather than
+    // stored as an infinity.
+    (@from_number f32, $number:ident) => {
+        element!(@from_number f64, $number).and_then(|value| match value as f32 {
+            narrowed if narrowed.is_finite() || !value.is_finite() => Ok(narrowed),
+            _ => Err(InvalidArgument::OutOfRange($number.to_string())),
+        })
+    };
+    (@from_number f64, $number:ident) => {
+        if $number.is_real() {
+            match $number.to_f64() {
+                value if value.is_finite() || !$number.is_exact() => Ok(value),
+                _ => Err(InvalidArgument::OutOfRange($number.to_string())),
+            }
+        } else {
+            Err(InvalidArgument::InvalidType(
+                $number.to_string(),
+                "real number".to_string(),
+            ))
+        }
+    };
+    (@from_number $element:ident, $number:ident) => {
+        match $number {
+            Number::Integer(value) => Self::try_from(*value).ok(),
+            Number::BigInteger(value) => Self::try_from(value).ok(),
+            _ => {
+                return Err(InvalidArgument::InvalidType(
+                    $number.to_string(),
+                    "exact integer".to_string(),
+                ))
+            }
+        }
+        .ok_or_else(|| InvalidArgument::OutOfRange($number.to_string()))
+    };
+    (@to_number f32, $value:ident) => {
+        Number::Real($value.into())
+    };
+    (@to_number f64, $value:ident) => {
+        Number::Real($value)
+    };
+    (@to_number u64, $value:ident) => {
+        Number::from(num_bigint::BigInt::from($value))
+    };
+    (@to_number $element:ident, $value:ident) => {
+        Number::Integer($value.into())
+    };
+    // The shortest digits that read back to the same `f32`, rather than those
+    // of the widened `f64`.
+    (@fmt f32, $value:ident, $f:ident) => {
+        if $value.is_finite() {
+            write!($f, "{:?}", $value)
+        } else {
+            write!($f, "{}", $value.to_number())
+        }
+    };
+    (@fmt $element:ident, $value:ident, $f:ident) => {
+        write!($f, "{}", $value.to_number())
+    };
+}
+
+element!(U8, u8);
+element!(S8, i8);
+element!(U16, u16);
+element!(S16, i16);
+element!(U32, u32);
+element!(S32, i32);
+element!(U64, u64);
+element!(S64, i64);
+element!(F32, f32);
+element!(F64, f64);
+
+impl NumericVector {
+    /// A vector of `length` copies of `fill`. A length that cannot be
+    /// allocated is out of range, rather than aborting the process.
+    pub fn new(kind: NumericKind, length: usize, fill: &Number) -> Result<Self, InvalidArgument> {
+        with_kind!(kind, T => {
+            let fill = T::from_number(fill)?;
+            let mut elements = Vec::new();
+            elements
+                .try_reserve_exact(length)
+                .map_err(|_| InvalidArgument::OutOfRange(length.to_string()))?;
+            elements.resize(length, fill);
+            Ok(T::wrap(elements))
+        })
+    }
+
+    pub fn from_numbers<'a>(
+        kind: NumericKind,
+        numbers: impl IntoIterator<Item = &'a Number>,
+    ) -> Result<Self, InvalidArgument> {
+        with_kind!(kind, T => numbers
+            .into_iter()
+            .map(T::from_number)
+            .collect::<Result<Vec<T>, _>>()
+            .map(T::wrap))
+    }
+
+    pub fn kind(&self) -> NumericKind {
+        match self {
+            Self::U8(_) => NumericKind::U8,
+            Self::S8(_) => NumericKind::S8,
+            Self::U16(_) => NumericKind::U16,
+            Self::S16(_) => NumericKind::S16,
+            Self::U32(_) => NumericKind::U32,
+            Self::S32(_) => NumericKind::S32,
+            Self::U64(_) => NumericKind::U64,
+            Self::S64(_) => NumericKind::S64,
+            Self::F32(_) => NumericKind::F32,
+            Self::F64(_) => NumericKind::F64,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        each!(self, elements => elements.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of bytes taken by the elements.
+    pub fn byte_len(&self) -> usize {
+        each!(self, elements => std::mem::size_of_val(elements.as_slice()))
+    }
+
+    pub fn get(&self, index: usize) -> Option<Number> {
+        each!(self, elements => elements.get(index).map(|element| element.to_number()))
+    }
+
+    pub fn set(&mut self, index: usize, number: &Number) -> Result<(), InvalidArgument> {
+        each!(self, elements => {
+            let element = Element::from_number(number)?;
+            *elements
+                .get_mut(index)
+                .ok_or_else(|| InvalidArgument::OutOfRange(index.to_string()))? = element;
+        });
+        Ok(())
+    }
+
+    /// The elements in `start..end` as numbers.
+    pub fn numbers(&self, start: usize, end: usize) -> Vec<Number> {
+        each!(self, elements => elements[start..end]
+            .iter()
+            .map(|element| element.to_number())
+            .collect())
+    }
+
+    /// A new vector holding the elements in `start..end`.
+    pub fn copy(&self, start: usize, end: usize) -> Self {
+        each!(self, elements => Element::wrap(elements[start..end].to_vec()))
+    }
+
+    /// Overwrites the elements from `at` on with the elements of `source`,
+    /// which must be of the same kind and fit.
+    pub fn copy_from(&mut self, at: usize, source: &Self) {
+        each!(self, elements => {
+            let source = Element::slice(source).expect("same kind");
+            elements[at..at + source.len()].copy_from_slice(source);
+        })
+    }
+
+    pub fn fill(
+        &mut self,
+        number: &Number,
+        start: usize,
+        end: usize,
+    ) -> Result<(), InvalidArgument> {
+        each!(self, elements => elements[start..end].fill(Element::from_number(number)?));
+        Ok(())
+    }
+
+    /// Appends the elements of `other`, which must be of the same kind.
+    pub fn extend(&mut self, other: &Self) {
+        each!(self, elements => elements.extend_from_slice(Element::slice(other).expect("same kind")))
+    }
+
+    /// The elements in little-endian byte order, as stored in images.
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.byte_len());
+        each!(self, elements => {
+            for element in elements {
+                element.write_le(&mut bytes);
+            }
+        });
+        bytes
+    }
+
+    pub fn from_le_bytes(kind: NumericKind, bytes: &[u8]) -> Option<Self> {
+        with_kind!(kind, T => {
+            let size = std::mem::size_of::<T>();
+            if !bytes.len().is_multiple_of(size) {
+                return None;
+            }
+            Some(T::wrap(bytes.chunks_exact(size).map(T::read_le).collect()))
+        })
+    }
+}
+
+impl Display for NumericVector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}(", self.kind().tag())?;
+        each!(self, elements => {
+            for (index, element) in elements.iter().enumerate() {
+                if index > 0 {
+                    f.write_str(" ")?;
+                }
+                element.fmt_element(f)?;
+            }
+        });
+        f.write_str(")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elements() {
+        let mut vector = NumericVector::new(NumericKind::U8, 3, &Number::Integer(7)).unwrap();
+        assert_eq!(vector, NumericVector::U8(vec![7, 7, 7]));
+        vector.set(1, &Number::Integer(255)).unwrap();
+        assert_eq!(vector.get(1), Some(Number::Integer(255)));
+        assert_eq!(vector.get(3), None);
+        assert!(vector.set(0, &Number::Integer(256)).is_err());
+        assert!(vector.set(0, &Number::Real(1.0)).is_err());
+        assert!(vector.set(3, &Number::Integer(0)).is_err());
+        assert_eq!(
+            NumericVector::new(NumericKind::U64, usize::MAX / 4, &Number::Integer(0)),
+            Err(InvalidArgument::OutOfRange((usize::MAX / 4).to_string()))
+        );
+
+        let numbers = [Number::Integer(1), Number::Real(0.5)];
+        let vector = NumericVector::from_numbers(NumericKind::F32, &numbers).unwrap();
+        assert_eq!(vector, NumericVector::F32(vec![1.0, 0.5]));
+        assert!(NumericVector::from_numbers(NumericKind::S8, &numbers).is_err());
+        let large = Number::Real(1e40);
+        assert_eq!(
+            NumericVector::new(NumericKind::F32, 1, &large),
+            Err(InvalidArgument::OutOfRange(large.to_string()))
+        );
+        assert!(NumericVector::new(NumericKind::F64, 1, &large).is_ok());
+        let huge = Number::from(num_bigint::BigInt::from(10).pow(400));
+        assert!(NumericVector::new(NumericKind::F64, 1, &huge).is_err());
+        let infinity = Number::Real(f64::INFINITY);
+        assert_eq!(
+            NumericVector::new(NumericKind::F32, 1, &infinity),
+            Ok(NumericVector::F32(vec![f32::INFINITY]))
+        );
+
+        let vector = NumericVector::U64(vec![u64::MAX]);
+        assert_eq!(
+            vector.get(0),
+            Some(Number::from(num_bigint::BigInt::from(u64::MAX)))
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(NumericVector::S16(vec![-1, 2]).to_string(), "#s16(-1 2)");
+        assert_eq!(
+            NumericVector::F32(vec![0.1, 2.0]).to_string(),
+            "#f32(0.1 2.0)"
+        );
+        assert_eq!(NumericVector::F64(vec![]).to_string(), "#f64()");
+        assert_eq!(
+            NumericVector::F64(vec![f64::INFINITY]).to_string(),
+            "#f64(+inf.0)"
+        );
+    }
+
+    #[test]
+    fn test_bytes() {
+        for vector in [
+            NumericVector::S32(vec![-2, 0, i32::MAX]),
+            NumericVector::F64(vec![0.5, f64::MIN]),
+            NumericVector::U8(vec![]),
+        ] {
+            let bytes = vector.to_le_bytes();
+            assert_eq!(bytes.len(), vector.byte_len());
+            assert_eq!(
+                NumericVector::from_le_bytes(vector.kind(), &bytes),
+                Some(vector)
+            );
+        }
+        assert_eq!(NumericVector::from_le_bytes(NumericKind::U16, &[0]), None);
+    }
+}
@@ -0,0 +1,42 @@
+use std::cell::RefCell;
+
+thread_local! {
+    /// Output captured by [`capture`], if it is running.
+    static CAPTURED: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Writes the output of `write` and the other output procedures: to standard
+/// output, or to the buffer of a running [`capture`].
+pub(crate) fn write(text: &str) {
+    let captured = CAPTURED.with(|captured| {
+        let mut captured = captured.borrow_mut();
+        captured.as_mut().map(|buffer| buffer.push_str(text))
+    });
+    if captured.is_none() {
+        print(text);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn print(text: &str) {
+    use std::io::Write;
+
+    let mut stdout = std::io::stdout().lock();
+    // Output is best effort, as with `print!`, but without its panic.
+    let _ = stdout
+        .write_all(text.as_bytes())
+        .and_then(|_| stdout.flush());
+}
+
+/// There is no standard output in the browser, where all output is captured.
+#[cfg(target_arch = "wasm32")]
+fn print(_text: &str) {}
+
+/// Runs `f`, returning what it wrote together with its result.
+#[cfg(any(test, target_arch = "wasm32"))]
+pub(crate) fn capture<T>(f: impl FnOnce() -> T) -> (T, String) {
+    let outer = CAPTURED.with(|captured| captured.replace(Some(String::new())));
+    let result = f();
+    let output = CAPTURED.with(|captured| captured.replace(outer));
+    (result, output.unwrap_or_default())
+}
@@ -1,215 +1,531 @@
+use std::{collections::HashMap, rc::Rc, sync::Arc};
+
 use crate::{
-    data_model::{AsSymbol, Expression, Link},
-    error::ParseError,
-    lexer::{Token, TokenBuffer},
+    builtin::char::foldcase,
+    data_model::{AsSymbol, Expression, Link, CHARACTER_NAMES},
+    error::{Error, ParseError},
+    lexer::{tokenize_recovering, Token, TokenBuffer},
     number::Number,
+    numeric_vector::{NumericKind, NumericVector},
+    span::{Location, Source, Span},
 };
 
 type Result<T> = std::result::Result<T, ParseError>;
 
+/// A container whose closing token has not been read yet.
+enum Open {
+    List {
+        items: Vec<Link>,
+        dotted: bool,
+        tail: Option<Link>,
+    },
+    Vector(Vec<Link>),
+    NumericVector(NumericKind, Vec<Link>),
+    Abbreviation(&'static str),
+    /// A `#;` waiting for the datum it discards.
+    DatumComment,
+    /// A `#n=` waiting for the datum it labels, by the number `n`.
+    Label(String),
+}
+
+impl Open {
+    /// The token of a prefix waiting for a datum, or `None` for a container.
+    fn prefix(&self) -> Option<String> {
+        match self {
+            Self::Abbreviation(name) => Some(
+                match *name {
+                    "quote" => "'",
+                    "quasiquote" => "`",
+                    "unquote" => ",",
+                    _ => ",@",
+                }
+                .to_string(),
+            ),
+            Self::DatumComment => Some("#;".to_string()),
+            Self::Label(label) => Some(format!("#{}=", label)),
+            Self::List { .. } | Self::Vector(_) | Self::NumericVector(..) => None,
+        }
+    }
+}
+
+/// Parses one datum. Nesting is tracked on an explicit stack, so deeply nested
+/// or very long input cannot overflow the call stack.
 pub fn parse<'a>(buffer: &mut TokenBuffer<'a>) -> Result<Expression> {
-    if buffer.is_empty() {
-        return Err(ParseError::EOF);
+    parse_datum(buffer, &mut vec![], None)
+}
+
+/// Parses one datum, locating an error at the token it was raised at, or at
+/// the container left open by the end of the input.
+pub(crate) fn parse_source(buffer: &mut TokenBuffer) -> std::result::Result<Expression, Error> {
+    let mut stack = vec![];
+    parse_datum(buffer, &mut stack, None).map_err(|error| {
+        let span = match error {
+            ParseError::EOF | ParseError::MissingCLoseParenthesis => {
+                stack.last().map(|(_, span)| *span)
+            }
+            _ => buffer.span(),
+        };
+        match span.and_then(|span| buffer.locate(span)) {
+            Some(location) => Error::Located(Box::new(error.into()), location),
+            None => error.into(),
+        }
+    })
+}
+
+/// Parses every datum of `source`, getting past syntax errors rather than
+/// stopping at the first, as an editor needs. Returns the datums read, with
+/// malformed ones made out as well as they can be, and every error found in
+/// the order of the source. A parenthesis left unclosed is located where it
+/// was opened.
+pub fn parse_recovering(source: Source) -> (Vec<Expression>, Vec<Error>) {
+    let source = Arc::new(source);
+    let (mut buffer, mut errors) = tokenize_recovering(&source);
+    let mut datums = vec![];
+    let mut problems = vec![];
+    while let Ok(datum) = parse_datum(&mut buffer, &mut vec![], Some(&mut problems)) {
+        datums.push(datum);
     }
-    match *buffer.pop() {
-        Token::Identifier(identifier) => Ok(identifier.to_ascii_lowercase().as_symbol().into()),
-        Token::Boolean(value) => match value {
-            "#t" | "#true" => Ok(true.into()),
-            "#f" | "#false" => Ok(false.into()),
-            _ => unreachable!(),
-        },
-        Token::Number(number) => Ok(Number::try_from(number)?.into()),
-        Token::String(string) => {
-            let mut result = String::new();
-            let mut start_escape = false;
-            let mut start_hex = false;
-            let mut hex_buffer = String::new();
-            for character in string.chars() {
-                match character {
-                    '\\' => {
-                        if start_escape {
-                            result.push('\\');
-                            start_escape = false;
-                        } else {
-                            start_escape = true;
-                        }
-                    }
-                    '"' => {
-                        if start_escape {
-                            result.push('"');
-                            start_escape = false;
-                        } else {
-                            // remove the first and last quote
-                            continue;
-                        }
-                    }
-                    'n' => {
-                        if start_escape {
-                            result.push('\n');
-                            start_escape = false;
-                        } else {
-                            result.push('n');
-                        }
-                    }
-                    'r' => {
-                        if start_escape {
-                            result.push('\r');
-                            start_escape = false;
-                        } else {
-                            result.push('r');
-                        }
-                    }
-                    't' => {
-                        if start_escape {
-                            result.push('\t');
-                            start_escape = false;
-                        } else {
-                            result.push('t');
-                        }
-                    }
-                    'b' => {
-                        if start_escape {
-                            result.push('\x08');
-                            start_escape = false;
-                        } else {
-                            result.push('b');
-                        }
+    errors.extend(problems.into_iter().map(|(error, span)| {
+        let location = buffer.locate(span).expect("the tokens have a source");
+        Error::Located(Box::new(error.into()), location)
+    }));
+    errors.sort_by_key(|error| error.location().map(|location| location.span.start));
+    (datums, errors)
+}
+
+/// Parses with the containers still open kept on `stack`, each with the span
+/// of its opening token.
+///
+/// With `problems`, the parse is recovering: errors are recorded there rather
+/// than returned, and parsing goes on. A malformed atom is read as a symbol
+/// of its text, a stray dot or `)` is skipped, and at the end of the input
+/// the open containers are closed. Only the end of the input is returned.
+fn parse_datum(
+    buffer: &mut TokenBuffer,
+    stack: &mut Vec<(Open, Span)>,
+    mut problems: Option<&mut Vec<(ParseError, Span)>>,
+) -> Result<Expression> {
+    // The datums labelled so far. Labels are local to the outermost datum.
+    let mut labels = HashMap::new();
+    // Placeholders for references to labelled datums still being read.
+    let mut placeholders = HashMap::new();
+    loop {
+        let (token, span) = if buffer.is_empty() {
+            let (Some((open, opener)), Some(problems)) = (stack.last(), problems.as_deref_mut())
+            else {
+                return Err(match stack.last() {
+                    Some((open, _)) if open.prefix().is_none() => {
+                        ParseError::MissingCLoseParenthesis
                     }
-                    'a' => {
-                        if start_escape {
-                            result.push('\x07');
-                            start_escape = false;
-                        } else {
-                            result.push('a');
-                        }
+                    _ => ParseError::EOF,
+                });
+            };
+            match open.prefix() {
+                Some(prefix) => {
+                    problems.push((ParseError::MissingDatum(prefix), *opener));
+                    stack.pop();
+                    continue;
+                }
+                None => {
+                    problems.push((ParseError::MissingCLoseParenthesis, *opener));
+                    (Token::CloseParenthesis, buffer.span().unwrap_or(*opener))
+                }
+            }
+        } else {
+            let token = *buffer.pop();
+            (token, buffer.span().expect("a token was just popped"))
+        };
+        let mut datum = match token {
+            Token::Identifier(identifier) if identifier.starts_with('|') => {
+                match parse_symbol(identifier) {
+                    Ok(symbol) => symbol.as_symbol().into(),
+                    Err(error) => misread(&mut problems, error, token, span)?,
+                }
+            }
+            Token::Identifier(identifier) if buffer.fold_case() => identifier
+                .chars()
+                .map(foldcase)
+                .collect::<String>()
+                .as_symbol()
+                .into(),
+            Token::Identifier(identifier) => identifier.as_symbol().into(),
+            Token::Boolean(value) => match value {
+                "#t" | "#true" => true.into(),
+                "#f" | "#false" => false.into(),
+                _ => unreachable!(),
+            },
+            Token::Number(number) => match Number::try_from(number) {
+                Ok(number) => number.into(),
+                Err(error) => misread(&mut problems, error, token, span)?,
+            },
+            Token::Character(character) => match parse_character(character, buffer.fold_case()) {
+                Ok(character) => character.into(),
+                Err(error) => misread(&mut problems, error, token, span)?,
+            },
+            Token::String(string) => match parse_string(string) {
+                Ok(string) => string.into(),
+                Err(error) => misread(&mut problems, error, token, span)?,
+            },
+            Token::Comment(_) => continue,
+            Token::Directive(directive) => {
+                buffer.set_fold_case(directive == "#!fold-case");
+                continue;
+            }
+            Token::DatumComment => {
+                stack.push((Open::DatumComment, span));
+                continue;
+            }
+            Token::DatumLabel(label) => {
+                let label = label_number(label);
+                if labels.contains_key(&label)
+                    || stack
+                        .iter()
+                        .any(|(open, _)| matches!(open, Open::Label(open) if *open == label))
+                {
+                    skip(&mut problems, ParseError::DuplicateLabel(label), span)?;
+                } else {
+                    stack.push((Open::Label(label), span));
+                }
+                continue;
+            }
+            Token::DatumReference(reference) => {
+                let label = label_number(reference);
+                let open = stack
+                    .iter()
+                    .rposition(|(open, _)| matches!(open, Open::Label(open) if *open == label));
+                match (labels.get(&label), open) {
+                    (Some(datum), _) => Expression::from(Link::clone(datum)),
+                    // the datum labelled holds the reference when a pair or
+                    // vector of it does
+                    (None, Some(open)) => match stack[open + 1..]
+                        .iter()
+                        .rfind(|(open, _)| !matches!(open, Open::Label(_)))
+                    {
+                        Some((
+                            Open::List { .. }
+                            | Open::Vector(_)
+                            | Open::Abbreviation(_)
+                            | Open::DatumComment,
+                            _,
+                        )) => placeholders
+                            .entry(label)
+                            .or_insert_with(Link::placeholder)
+                            .clone()
+                            .into(),
+                        _ => misread(&mut problems, ParseError::CircularLabel(label), token, span)?,
+                    },
+                    (None, None) => {
+                        misread(&mut problems, ParseError::UnknownLabel(label), token, span)?
                     }
-                    'x' => {
-                        if start_escape {
-                            start_hex = true;
-                        } else {
-                            result.push('x');
-                        }
+                }
+            }
+            Token::OpenParenthesis => {
+                stack.push((
+                    Open::List {
+                        items: vec![],
+                        dotted: false,
+                        tail: None,
+                    },
+                    span,
+                ));
+                continue;
+            }
+            // vector is self-evaluating in R7RS
+            Token::VectorOpen => {
+                stack.push((Open::Vector(vec![]), span));
+                continue;
+            }
+            Token::ByteVectorOpen => {
+                stack.push((Open::NumericVector(NumericKind::U8, vec![]), span));
+                continue;
+            }
+            Token::NumericVectorOpen(prefix) => {
+                let kind = NumericKind::from_tag(&prefix[1..prefix.len() - 1])
+                    .expect("the lexer only accepts known tags");
+                stack.push((Open::NumericVector(kind, vec![]), span));
+                continue;
+            }
+            Token::Quote => {
+                stack.push((Open::Abbreviation("quote"), span));
+                continue;
+            }
+            Token::BackQuote => {
+                stack.push((Open::Abbreviation("quasiquote"), span));
+                continue;
+            }
+            Token::Comma => {
+                stack.push((Open::Abbreviation("unquote"), span));
+                continue;
+            }
+            Token::CommaAt => {
+                stack.push((Open::Abbreviation("unquote-splicing"), span));
+                continue;
+            }
+            Token::Dot => {
+                match stack.last_mut() {
+                    Some((
+                        Open::List {
+                            items,
+                            dotted: dotted @ false,
+                            ..
+                        },
+                        _,
+                    )) if !items.is_empty() => *dotted = true,
+                    Some((Open::List { tail: Some(_), .. }, _)) => {
+                        skip(&mut problems, ParseError::TooMoreObjects, span)?
                     }
-                    ';' => {
-                        if start_hex {
-                            start_hex = false;
-                            start_escape = false;
-                            let hex = u32::from_str_radix(&hex_buffer, 16).map_err(|_| {
-                                ParseError::InvalidCharacterEscape(format!("\\x{}", hex_buffer))
-                            })?;
-                            result.push(char::from_u32(hex).ok_or(
-                                ParseError::InvalidCharacterEscape(format!("\\x{}", hex_buffer)),
-                            )?);
-                            hex_buffer.clear();
-                        } else {
-                            result.push(';');
-                        }
+                    _ => skip(&mut problems, ParseError::InvalidDot, span)?,
+                }
+                continue;
+            }
+            Token::CloseParenthesis => match stack.pop() {
+                Some((
+                    Open::List {
+                        items,
+                        dotted: true,
+                        tail: None,
+                    },
+                    start,
+                )) => {
+                    let Some(problems) = problems.as_deref_mut() else {
+                        return Err(ParseError::MissingOpenParenthesis);
+                    };
+                    problems.push((ParseError::InvalidDot, span));
+                    list(items, Link::Nil, buffer.locate(start.to(span))).into()
+                }
+                Some((Open::List { items, tail, .. }, start)) => list(
+                    items,
+                    tail.unwrap_or(Link::Nil),
+                    buffer.locate(start.to(span)),
+                )
+                .into(),
+                Some((Open::Vector(vector), _)) => vector.into(),
+                Some((Open::NumericVector(kind, items), _)) => match numeric_vector(kind, &items) {
+                    Ok(vector) => vector.into(),
+                    Err(error) => {
+                        skip(&mut problems, error, span)?;
+                        items.into()
                     }
-                    c => {
-                        if start_hex {
-                            if c.is_digit(16) {
-                                hex_buffer.push(c);
-                            } else {
-                                return Err(ParseError::InvalidCharacterEscape(format!(
-                                    "\\x{}{}",
-                                    hex_buffer, c
-                                )));
-                            }
-                        } else if start_escape {
-                            return Err(ParseError::InvalidCharacterEscape(format!("\\{}", c)));
-                        } else {
-                            result.push(c);
+                },
+                Some((open, start)) => {
+                    let prefix = open.prefix().expect("containers are handled above");
+                    skip(&mut problems, ParseError::MissingDatum(prefix), start)?;
+                    // the `)` is read again, to close what the prefix is in
+                    buffer.unpop();
+                    continue;
+                }
+                None => {
+                    skip(&mut problems, ParseError::MissingOpenParenthesis, span)?;
+                    continue;
+                }
+            },
+        };
+        loop {
+            match stack.last_mut() {
+                None => return Ok(datum),
+                Some((Open::DatumComment, _)) => {
+                    stack.pop();
+                    break;
+                }
+                Some((Open::Abbreviation(name), start)) => {
+                    datum = list(
+                        vec![name.as_symbol().into(), datum.content],
+                        Link::Nil,
+                        buffer.locate(start.to(span)),
+                    )
+                    .into();
+                    stack.pop();
+                }
+                Some((Open::Label(_), _)) => {
+                    if let Some((Open::Label(label), _)) = stack.pop() {
+                        if let Some(placeholder) = placeholders.remove(&label) {
+                            placeholder.fill(datum.content.clone());
                         }
+                        labels.insert(label, datum.content.clone());
                     }
                 }
+                Some((
+                    Open::List {
+                        dotted: true,
+                        tail: tail @ None,
+                        ..
+                    },
+                    _,
+                )) => {
+                    *tail = Some(datum.content);
+                    break;
+                }
+                Some((Open::List { dotted: true, .. }, _)) => {
+                    skip(&mut problems, ParseError::TooMoreObjects, span)?;
+                    break;
+                }
+                Some((Open::List { items, .. }, _))
+                | Some((Open::Vector(items), _))
+                | Some((Open::NumericVector(_, items), _)) => {
+                    items.push(datum.content);
+                    break;
+                }
             }
-            Ok(result.into())
         }
-        Token::Comment(_) => parse(buffer),
-        Token::OpenParenthesis => parse_pair(buffer),
-        Token::CloseParenthesis => Err(ParseError::MissingOpenParenthesis),
-        Token::VectorOpen => {
-            // vector is self-evaluating in R7RS
-            let vector = vec![];
-            parse_vector(buffer, vector)
+    }
+}
+
+/// Records `error` if the parse is recovering and reads `token` as a symbol
+/// of its text, or else returns the error.
+fn misread(
+    problems: &mut Option<&mut Vec<(ParseError, Span)>>,
+    error: ParseError,
+    token: Token,
+    span: Span,
+) -> Result<Expression> {
+    skip(problems, error, span)?;
+    Ok(token.as_str().as_symbol().into())
+}
+
+/// Records `error` if the parse is recovering, so that what raised it is
+/// skipped, or else returns it.
+fn skip(
+    problems: &mut Option<&mut Vec<(ParseError, Span)>>,
+    error: ParseError,
+    span: Span,
+) -> Result<()> {
+    match problems {
+        Some(problems) => {
+            problems.push((error, span));
+            Ok(())
         }
-        Token::ByteVectorOpen => unimplemented!(),
-        Token::Quote => Ok(Link::new_pair(
-            "quote".as_symbol().into(),
-            Link::new_pair(parse(buffer)?.content, Link::Nil),
-        )
-        .into()),
-        Token::BackQuote => Ok(Link::new_pair(
-            "quasiquote".as_symbol().into(),
-            Link::new_pair(parse(buffer)?.content, Link::Nil),
-        )
-        .into()),
-        Token::Comma => Ok(Link::new_pair(
-            "unquote".as_symbol().into(),
-            Link::new_pair(parse(buffer)?.content, Link::Nil),
-        )
-        .into()),
-        Token::CommaAt => Ok(Link::new_pair(
-            "unquote-splicing".as_symbol().into(),
-            Link::new_pair(parse(buffer)?.content, Link::Nil),
-        )
-        .into()),
-        Token::Dot => Err(ParseError::InvalidDot),
+        None => Err(error),
+    }
+}
+
+/// The number of the label `#n=` or reference `#n#`, without leading zeros so
+/// that `#01#` refers to `#1=`.
+fn label_number(token: &str) -> String {
+    let digits = token[1..token.len() - 1].trim_start_matches('0');
+    if digits.is_empty() { "0" } else { digits }.to_string()
+}
+
+/// Builds the list of `items` ending in `tail`. Its first pair, the one a
+/// form is evaluated from, is given the location.
+fn list(items: Vec<Link>, tail: Link, location: Option<Location>) -> Link {
+    let mut items = items.into_iter();
+    let Some(first) = items.next() else {
+        return tail;
+    };
+    let rest = items.rev().fold(tail, |cdr, car| Link::new_pair(car, cdr));
+    Link::new_located_pair(first, rest, location.map(Rc::new))
+}
+
+fn numeric_vector(kind: NumericKind, items: &[Link]) -> Result<NumericVector> {
+    let invalid = |item: &Link| ParseError::InvalidVectorElement(item.to_string(), kind.tag());
+    let mut vector = NumericVector::new(kind, items.len(), &Number::Integer(0))
+        .expect("zero is an element of every kind");
+    for (index, item) in items.iter().enumerate() {
+        let number = item.as_number().ok_or_else(|| invalid(item))?;
+        vector.set(index, number).map_err(|_| invalid(item))?;
     }
+    Ok(vector)
 }
 
-fn parse_pair<'a>(buffer: &mut TokenBuffer<'a>) -> Result<Expression> {
-    if buffer.is_empty() {
-        Err(ParseError::MissingCLoseParenthesis)
+/// Reads `#\a`, `#\space` or `#\x3bb`. A lone `x` is the letter itself.
+/// With `fold_case`, names are matched regardless of case.
+fn parse_character(literal: &str, fold_case: bool) -> Result<char> {
+    let text = &literal[2..];
+    let mut chars = text.chars();
+    if let (Some(character), None) = (chars.next(), chars.next()) {
+        return Ok(character);
+    }
+    let name: String = if fold_case {
+        text.chars().map(foldcase).collect()
     } else {
-        match *buffer.peek() {
-            Token::CloseParenthesis => {
-                buffer.pop();
-                Ok(Link::Nil.into())
-            }
-            Token::Dot => {
-                buffer.pop();
-                let rest = parse(buffer)?;
-                if buffer.is_empty() {
-                    Err(ParseError::MissingCLoseParenthesis)
-                } else {
-                    match *buffer.pop() {
-                        Token::CloseParenthesis => Ok(rest),
-                        _ => Err(ParseError::TooMoreObjects),
-                    }
-                }
-            }
-            _ => {
-                let first = parse(buffer)?;
-                let rest = parse_pair(buffer)?;
-                Ok(Link::new_pair(first.content, rest.content).into())
-            }
-        }
+        text.to_string()
+    };
+    if let Some((_, character)) = CHARACTER_NAMES.iter().find(|(known, _)| *known == name) {
+        return Ok(*character);
     }
+    text.strip_prefix(['x', 'X'])
+        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+        .and_then(char::from_u32)
+        .ok_or_else(|| ParseError::InvalidCharacter(literal.to_string()))
 }
 
-fn parse_vector<'a>(buffer: &mut TokenBuffer<'a>, mut vector: Vec<Link>) -> Result<Expression> {
-    loop {
-        if buffer.is_empty() {
-            return Err(ParseError::MissingCLoseParenthesis);
-        } else {
-            match *buffer.peek() {
-                Token::CloseParenthesis => {
-                    buffer.pop();
-                    return Ok(vector.into());
+/// Reads `|hello world|`, in which the escapes of strings but line
+/// continuations may appear.
+fn parse_symbol(literal: &str) -> Result<String> {
+    unescape(&literal[1..literal.len() - 1], false)
+}
+
+/// Reads a string literal. A backslash at the end of a line joins it to the
+/// next, leaving out the whitespace around the line break.
+fn parse_string(literal: &str) -> Result<String> {
+    unescape(&literal[1..literal.len() - 1], true)
+}
+
+/// Replaces the escapes `\a`, `\b`, `\t`, `\n`, `\r`, `\"`, `\\`, `\|` and
+/// `\xHH;` in the text between the delimiters of a string or identifier.
+fn unescape(text: &str, line_continuations: bool) -> Result<String> {
+    let mut result = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(character) = chars.next() {
+        if character != '\\' {
+            result.push(character);
+            continue;
+        }
+        let escaped = match chars.next() {
+            Some('a') => '\x07',
+            Some('b') => '\x08',
+            Some('t') => '\t',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some(character @ ('"' | '\\' | '|')) => character,
+            Some('x') => {
+                let mut hex = String::new();
+                while let Some(digit) = chars.next_if(char::is_ascii_hexdigit) {
+                    hex.push(digit);
+                }
+                // the `;` is required, so that digits after the escape are not
+                // taken into it
+                let terminated = chars.next_if_eq(&';').is_some();
+                u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .filter(|_| terminated)
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| {
+                        let end = if terminated { ";" } else { "" };
+                        ParseError::InvalidCharacterEscape(format!("\\x{}{}", hex, end))
+                    })?
+            }
+            Some(first @ (' ' | '\t' | '\r' | '\n')) if line_continuations => {
+                let mut newline = first == '\n';
+                while let Some(&next) = chars.peek() {
+                    match next {
+                        '\n' if newline => break,
+                        '\n' => newline = true,
+                        ' ' | '\t' | '\r' => {}
+                        _ => break,
+                    }
+                    chars.next();
                 }
-                _ => {
-                    let first = parse(buffer)?;
-                    vector.push(first.content);
+                if !newline {
+                    return Err(ParseError::InvalidCharacterEscape(format!("\\{}", first)));
                 }
+                continue;
             }
-        }
+            other => {
+                return Err(ParseError::InvalidCharacterEscape(format!(
+                    "\\{}",
+                    other.map(String::from).unwrap_or_default()
+                )))
+            }
+        };
+        result.push(escaped);
     }
+    Ok(result)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::tokenize;
+    use crate::{error::TokenError, tokenize};
 
     use super::*;
 
@@ -283,4 +599,268 @@ mod tests {
     fn test_quote() {
         println!("quote parse: {:?}", parse(&mut tokenize("'x").unwrap()));
     }
+
+    #[test]
+    fn test_long_and_deep_data() {
+        let length = 100_000;
+        let long = format!("({})", "1 ".repeat(length));
+        let expression = parse(&mut tokenize(&long).unwrap()).unwrap();
+        assert_eq!(expression.as_link().len(), length);
+        assert_eq!(expression.to_string().len(), 2 * length + 1);
+        assert_eq!(expression, parse(&mut tokenize(&long).unwrap()).unwrap());
+
+        let deep = format!("{}x{}", "(#(".repeat(length), "))".repeat(length));
+        let expression = parse(&mut tokenize(&deep).unwrap()).unwrap();
+        assert_eq!(expression.to_string(), deep);
+        assert_eq!(expression, parse(&mut tokenize(&deep).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn test_malformed_lists() {
+        assert_eq!(
+            parse(&mut tokenize("(1 . 2 . 3)").unwrap()),
+            Err(ParseError::TooMoreObjects)
+        );
+        assert_eq!(
+            parse(&mut tokenize("(1 . )").unwrap()),
+            Err(ParseError::MissingOpenParenthesis)
+        );
+        assert_eq!(
+            parse(&mut tokenize("#(1 . 2)").unwrap()),
+            Err(ParseError::InvalidDot)
+        );
+        assert_eq!(
+            parse(&mut tokenize("(1 '").unwrap()),
+            Err(ParseError::EOF)
+        );
+        assert_eq!(
+            parse(&mut tokenize("'(1 #(2)").unwrap()),
+            Err(ParseError::MissingCLoseParenthesis)
+        );
+    }
+
+    #[test]
+    fn test_numeric_vectors() {
+        let parsed =
+            |src: &str| parse(&mut tokenize(src).unwrap()).map(|expression| expression.to_string());
+        assert_eq!(parsed("#u8(1 2 255)"), Ok("#u8(1 2 255)".to_string()));
+        assert_eq!(parsed("#F64(1 -0.5)"), Ok("#f64(1.0 -0.5)".to_string()));
+        assert_eq!(
+            parsed("(#s16() #f32(0.1))"),
+            Ok("(#s16() #f32(0.1))".to_string())
+        );
+        assert_eq!(
+            parsed("#u8(256)"),
+            Err(ParseError::InvalidVectorElement("256".to_string(), "u8"))
+        );
+        assert_eq!(
+            parsed("#s8(1 a)"),
+            Err(ParseError::InvalidVectorElement("a".to_string(), "s8"))
+        );
+        assert!(tokenize("#f16(1)").is_err());
+    }
+
+    #[test]
+    fn test_characters() {
+        let parsed = |src: &str| parse(&mut tokenize(src).unwrap());
+        let character = |src: &str| parsed(src).unwrap().as_character();
+        assert_eq!(character(r"#\a"), Some('a'));
+        assert_eq!(character(r"#\("), Some('('));
+        assert_eq!(character(r"#\ "), Some(' '));
+        assert_eq!(character(r"#\space"), Some(' '));
+        assert_eq!(character(r"#\x"), Some('x'));
+        assert_eq!(character(r"#\x3bb"), Some('λ'));
+        assert_eq!(character(r"#\λ"), Some('λ'));
+        assert_eq!(parsed(r"(#\a #\))").unwrap().to_string(), r"(#\a #\))");
+        assert_eq!(
+            parsed(r"(#\newline #\x7 #\x1)").unwrap().to_string(),
+            r"(#\newline #\alarm #\x1)"
+        );
+        assert_eq!(
+            parsed(r"#\spacey"),
+            Err(ParseError::InvalidCharacter(r"#\spacey".to_string()))
+        );
+        assert!(tokenize(r"#\").is_err());
+    }
+
+    #[test]
+    fn test_comments() {
+        let parsed =
+            |src: &str| parse(&mut tokenize(src).unwrap()).map(|expression| expression.to_string());
+        assert_eq!(parsed("#| a |# 1"), Ok("1".to_string()));
+        assert_eq!(parsed("#| a #| b |# c |# 2"), Ok("2".to_string()));
+        assert_eq!(parsed("(1 #|(|#2)"), Ok("(1 2)".to_string()));
+        assert_eq!(parsed("(1 #;(2 #(3)) 4)"), Ok("(1 4)".to_string()));
+        assert_eq!(parsed("#;1 #;#(2) 3"), Ok("3".to_string()));
+        assert_eq!(parsed("(#; #; 1 2 3)"), Ok("(3)".to_string()));
+        assert_eq!(parsed("(1 . #;2 3)"), Ok("(1 . 3)".to_string()));
+        assert_eq!(parsed("'#;a b"), Ok("(quote b)".to_string()));
+        assert_eq!(parsed("#;1"), Err(ParseError::EOF));
+        assert_eq!(
+            tokenize("#| a #| b |#").unwrap_err(),
+            TokenError::MissingCloseComment
+        );
+    }
+
+    #[test]
+    fn test_identifiers() {
+        let parsed =
+            |src: &str| parse(&mut tokenize(src).unwrap()).map(|expression| expression.to_string());
+        assert_eq!(
+            parsed("(λ café ->list +a .foo .. ... x₂ +.a -.foo +..)"),
+            Ok("(λ café ->list +a .foo .. ... x₂ +.a -.foo +..)".to_string())
+        );
+        assert_eq!(parsed("JSON"), Ok("JSON".to_string()));
+        assert_eq!(parsed("|a\\x41;|"), Ok("aA".to_string()));
+        assert_eq!(parsed("'|x|"), Ok("(quote x)".to_string()));
+        for symbol in [
+            "|hello world|",
+            "||",
+            "|12|",
+            "|.|",
+            "|+5|",
+            "|a\\|b|",
+            "|#t|",
+            "|a;b|",
+            "|\\x7;|",
+        ] {
+            assert_eq!(parsed(symbol), Ok(symbol.to_string()));
+        }
+        assert_eq!(parsed("(a|b c|)"), Ok("(a |b c|)".to_string()));
+        assert_eq!(
+            parsed("|\\q|"),
+            Err(ParseError::InvalidCharacterEscape("\\q".to_string()))
+        );
+        assert_eq!(
+            tokenize("|abc").unwrap_err(),
+            TokenError::MissingCloseVerticalLine
+        );
+        assert!(tokenize("٣x").is_err());
+    }
+
+    #[test]
+    fn test_datum_labels() {
+        let parsed =
+            |src: &str| parse(&mut tokenize(src).unwrap()).map(|expression| expression.content);
+        let list = parsed("(#0=(a b) #00# #1=c . #1#)").unwrap();
+        assert_eq!(list.to_string(), "((a b) (a b) c . c)");
+        let items: Vec<_> = list.iter().collect();
+        assert!(matches!(
+            (items[0], items[1]),
+            (Link::More(first), Link::More(second)) if Rc::ptr_eq(first, second)
+        ));
+        assert_eq!(
+            parsed("#1=#(#2=x '#2#)").unwrap().to_string(),
+            "#(x (quote x))"
+        );
+        let cycle = parsed("#0=(a . #0#)").unwrap();
+        assert_eq!(cycle.to_string(), "#0=(a . #0#)");
+        assert!(matches!(
+            (cycle.as_expression_content(), cycle.as_pair().unwrap().cdr.as_expression_content()),
+            (Some(first), Some(second)) if std::ptr::eq(first, second)
+        ));
+        assert_eq!(cycle, parsed("(a . #0=(a . #0#))").unwrap());
+        assert_eq!(
+            parsed("#0=#(1 '#0# #1=(#1#))").unwrap().to_string(),
+            "#0=#(1 (quote #0#) #1=(#1#))"
+        );
+        assert_eq!(
+            parsed("#0=#0#"),
+            Err(ParseError::CircularLabel("0".to_string()))
+        );
+        assert_eq!(
+            parsed("#0=#u8(#0#)"),
+            Err(ParseError::CircularLabel("0".to_string()))
+        );
+        assert_eq!(
+            parsed("(#1# #1=a)"),
+            Err(ParseError::UnknownLabel("1".to_string()))
+        );
+        assert_eq!(
+            parsed("(#1=a #1=b)"),
+            Err(ParseError::DuplicateLabel("1".to_string()))
+        );
+        assert_eq!(
+            parsed("(a #1=)"),
+            Err(ParseError::MissingDatum("#1=".to_string()))
+        );
+        assert_eq!(parsed("#1="), Err(ParseError::EOF));
+        assert!(tokenize("#1").is_err());
+    }
+
+    #[test]
+    fn test_strings() {
+        let parsed = |src: &str| {
+            parse(&mut tokenize(src).unwrap())
+                .map(|expression| expression.content.as_string().unwrap().to_string())
+        };
+        assert_eq!(
+            parsed(r#""\a\b\t\n\r\"\\\|\x3bb;""#),
+            Ok("\x07\x08\t\n\r\"\\|λ".to_string())
+        );
+        assert_eq!(
+            parsed("\"one \\   \n   two\\\r\n\tthree\""),
+            Ok("one twothree".to_string())
+        );
+        assert_eq!(parsed("\"a\\\n\n b\""), Ok("a\n b".to_string()));
+        for (src, escape) in [
+            (r#""\q""#, r"\q"),
+            (r#""\x;""#, r"\x;"),
+            (r#""\x+41;""#, r"\x"),
+            (r#""\x41""#, r"\x41"),
+            (r#""\x41abc""#, r"\x41abc"),
+            (r#""\x110000;""#, r"\x110000;"),
+            ("\"a\\ b\"", r"\ "),
+        ] {
+            assert_eq!(
+                parsed(src),
+                Err(ParseError::InvalidCharacterEscape(escape.to_string()))
+            );
+        }
+        let written = "\"say \\\"hi\\\"\\\\\\n\\t\\x7;\"";
+        assert_eq!(
+            parse(&mut tokenize(written).unwrap()).unwrap().to_string(),
+            written
+        );
+    }
+
+    #[test]
+    fn test_recovering() {
+        let text = "(define x 1.2.3)\n(car '(a . b c) . \"\\q\")\n)) [\n(f #(1 2) '(g ')";
+        let (datums, errors) = parse_recovering(Source::new("main.scm", text));
+        let datums: Vec<_> = datums.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            datums,
+            [
+                "(define x |1.2.3|)",
+                "(car (quote (a . b)) . |\"\\\\q\"|)",
+                "(f #(1 2) (quote (g)))",
+            ]
+        );
+        let errors: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            errors,
+            [
+                "main.scm:1:11: invalid number 1.2.3: unexpected character",
+                "main.scm:2:14: only one object is allowed after a dot",
+                "main.scm:2:19: invalid escape in string: \\q",
+                "main.scm:3:1: missing opening parenthesis",
+                "main.scm:3:2: missing opening parenthesis",
+                "main.scm:3:4: invalid character: [",
+                "main.scm:4:1: missing closing parenthesis",
+                "main.scm:4:15: ' is not followed by a datum",
+            ]
+        );
+        let (datums, errors) = parse_recovering(Source::new("<test>", "(a . ) #(1 . 2) '"));
+        assert_eq!(datums.len(), 2);
+        let errors: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            errors,
+            [
+                "<test>:1:6: dot is only allowed in the pair",
+                "<test>:1:12: dot is only allowed in the pair",
+                "<test>:1:17: ' is not followed by a datum",
+            ]
+        );
+    }
 }
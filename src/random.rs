@@ -0,0 +1,195 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use num_bigint::BigInt;
+use num_traits::{One, Signed, ToPrimitive};
+
+/// A SRFI 27 random source: a xoshiro256** generator. Clones share the same
+/// state, so a procedure made from a source advances the source itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RandomSource {
+    pub(crate) state: Rc<RefCell<[u64; 4]>>,
+}
+
+/// The state every new source starts from, so runs are reproducible.
+const DEFAULT_SEED: u64 = 0x5eed;
+
+thread_local! {
+    /// Distinguishes successive `random-source-randomize!` calls. No entropy
+    /// is read from the system, so randomized sources are only distinct, not
+    /// unpredictable.
+    static RANDOMIZE_COUNTER: Cell<u64> = const { Cell::new(0) };
+}
+
+fn splitmix64(seed: &mut u64) -> u64 {
+    *seed = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut value = *seed;
+    value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    value ^ (value >> 31)
+}
+
+fn seeded(mut seed: u64) -> [u64; 4] {
+    [
+        splitmix64(&mut seed),
+        splitmix64(&mut seed),
+        splitmix64(&mut seed),
+        splitmix64(&mut seed),
+    ]
+}
+
+impl RandomSource {
+    pub fn new() -> Self {
+        Self {
+            state: Rc::new(RefCell::new(seeded(DEFAULT_SEED))),
+        }
+    }
+
+    pub fn next_u64(&self) -> u64 {
+        let mut state = self.state.borrow_mut();
+        let result = state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let shifted = state[1] << 17;
+        state[2] ^= state[0];
+        state[3] ^= state[1];
+        state[1] ^= state[2];
+        state[0] ^= state[3];
+        state[2] ^= shifted;
+        state[3] = state[3].rotate_left(45);
+        result
+    }
+
+    /// A uniformly distributed integer in `[0, bound)`; `bound` must be
+    /// positive.
+    pub fn integer(&self, bound: &BigInt) -> BigInt {
+        debug_assert!(bound.is_positive());
+        if let Some(bound) = bound.to_u64() {
+            // Reject the low values that would make some results more likely.
+            let threshold = bound.wrapping_neg() % bound;
+            loop {
+                let value = self.next_u64();
+                if value >= threshold {
+                    return BigInt::from(value % bound);
+                }
+            }
+        }
+        let bits = bound.bits();
+        let words = bits.div_ceil(64);
+        let mask = (BigInt::one() << bits) - 1;
+        loop {
+            let mut value = BigInt::default();
+            for _ in 0..words {
+                value = (value << 64) | BigInt::from(self.next_u64());
+            }
+            value &= &mask;
+            if &value < bound {
+                return value;
+            }
+        }
+    }
+
+    /// A uniformly distributed real in the open interval `(0, 1)`.
+    pub fn real(&self) -> f64 {
+        ((self.next_u64() >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+    }
+
+    pub fn get_state(&self) -> [u64; 4] {
+        *self.state.borrow()
+    }
+
+    /// Restores a state from `get_state`. The all-zero state is rejected
+    /// because the generator would never leave it.
+    pub fn set_state(&self, state: [u64; 4]) -> Result<(), [u64; 4]> {
+        if state == [0; 4] {
+            return Err(state);
+        }
+        *self.state.borrow_mut() = state;
+        Ok(())
+    }
+
+    /// Moves to a state determined only by `i` and `j`.
+    pub fn pseudo_randomize(&self, i: &BigInt, j: &BigInt) {
+        let mut seed = 0;
+        // A separator word keeps (i, j) pairs with the same digits apart.
+        for word in i
+            .iter_u64_digits()
+            .chain([u64::MAX])
+            .chain(j.iter_u64_digits())
+        {
+            let mut next = seed ^ word;
+            seed = splitmix64(&mut next);
+        }
+        *self.state.borrow_mut() = seeded(seed);
+    }
+
+    /// Moves to a state that differs from earlier randomized states.
+    pub fn randomize(&self) {
+        let count = RANDOMIZE_COUNTER.with(|counter| {
+            counter.set(counter.get() + 1);
+            counter.get()
+        });
+        *self.state.borrow_mut() = seeded(self.next_u64() ^ count);
+    }
+}
+
+impl Default for RandomSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reproducible() {
+        let (first, second) = (RandomSource::new(), RandomSource::new());
+        for _ in 0..10 {
+            assert_eq!(first.next_u64(), second.next_u64());
+        }
+        let (one, two) = (BigInt::from(1), BigInt::from(2));
+        first.pseudo_randomize(&one, &two);
+        second.pseudo_randomize(&one, &two);
+        assert_eq!(first.get_state(), second.get_state());
+        second.pseudo_randomize(&two, &one);
+        assert_ne!(first.get_state(), second.get_state());
+    }
+
+    #[test]
+    fn test_shared_state() {
+        let source = RandomSource::new();
+        let shared = source.clone();
+        let state = source.get_state();
+        shared.next_u64();
+        assert_ne!(source.get_state(), state);
+        let value = source.next_u64();
+        source.set_state(state).unwrap();
+        shared.next_u64();
+        assert_eq!(source.next_u64(), value);
+        assert!(source.set_state([0; 4]).is_err());
+    }
+
+    #[test]
+    fn test_ranges() {
+        let source = RandomSource::new();
+        let big = BigInt::one() << 100;
+        for _ in 0..1000 {
+            let real = source.real();
+            assert!(real > 0.0 && real < 1.0);
+            assert!(source.integer(&BigInt::from(7)) < BigInt::from(7));
+            let value = source.integer(&big);
+            assert!(!value.is_negative() && value < big);
+        }
+        assert_eq!(source.integer(&BigInt::one()), BigInt::default());
+    }
+
+    #[test]
+    fn test_randomize() {
+        let (first, second) = (RandomSource::new(), RandomSource::new());
+        first.randomize();
+        second.randomize();
+        assert_ne!(first.get_state(), second.get_state());
+    }
+}
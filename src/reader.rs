@@ -0,0 +1,392 @@
+use std::{collections::VecDeque, io::BufRead, sync::Arc};
+
+use crate::{
+    data_model::Expression,
+    error::{Error, TokenError},
+    lexer::{scan_tokens, tokenize_source, Token, TokenBuffer},
+    parser::parse_source,
+    span::Source,
+};
+
+/// Reads datums one at a time from text or a [`BufRead`]. Input is read a
+/// line at a time, and only while the datum being read is unfinished.
+///
+/// Each line is scanned once as it is read, for where the datums end; the
+/// text holding finished datums is then parsed in one pass, and only an
+/// unfinished datum at its end is kept for the next lines.
+///
+/// At the end of the input the reader yields an error for which
+/// [`Error::is_incomplete`] holds if a datum was left unfinished, so that a
+/// host can ask for more input instead of reporting it.
+pub struct Reader<R> {
+    input: R,
+    name: String,
+    /// Input read but not parsed yet, from the start of its first line.
+    text: String,
+    /// The byte offset in `text` of the next datum.
+    offset: usize,
+    /// The line `text` starts at.
+    line: usize,
+    /// The byte offset in `text` that scanning resumes at.
+    scanned: usize,
+    /// The depth of nesting in lists and vectors that scanning got to.
+    depth: usize,
+    /// Where a string, block comment or `|identifier|` left open starts, and
+    /// what closes it. It is scanned again once a line holds that.
+    open_token: Option<(usize, &'static str)>,
+    /// Whether `text` holds a finished datum, or an error to report.
+    finished: bool,
+    /// The datums and errors parsed but not yielded yet.
+    parsed: VecDeque<Result<Expression, Error>>,
+    /// Whether identifiers are folded to lower case, which `#!fold-case` and
+    /// `#!no-fold-case` change for the rest of the input.
+    fold_case: bool,
+    exhausted: bool,
+}
+
+impl<R: BufRead> Reader<R> {
+    /// Reads from `input`, locating datums and errors under `name`.
+    pub fn new(name: impl Into<String>, input: R) -> Self {
+        Self {
+            input,
+            name: name.into(),
+            text: String::new(),
+            offset: 0,
+            line: 1,
+            scanned: 0,
+            depth: 0,
+            open_token: None,
+            finished: false,
+            parsed: VecDeque::new(),
+            fold_case: false,
+            exhausted: false,
+        }
+    }
+
+    /// Starts folding identifiers and character names to lower case, as
+    /// R5RS did, rather than reading them as written.
+    pub fn with_fold_case(mut self, fold_case: bool) -> Self {
+        self.fold_case = fold_case;
+        self
+    }
+
+    /// Whether identifiers are being folded to lower case, as the directives
+    /// read so far leave it.
+    pub fn fold_case(&self) -> bool {
+        self.fold_case
+    }
+
+    /// Scans the text after `scanned` for the end of a datum: an atom or a
+    /// closing parenthesis at the top level. A stray closing parenthesis or
+    /// dot, or an invalid token, is an error that ends one as well.
+    fn scan(&mut self) {
+        if let Some((start, close)) = self.open_token {
+            if !self.text[self.scanned..].contains(close) {
+                self.scanned = self.text.len();
+                return;
+            }
+            self.open_token = None;
+            self.scanned = start;
+        }
+        let (depth, finished) = (&mut self.depth, &mut self.finished);
+        let result = scan_tokens(&self.text, self.scanned, |token| match token {
+            Token::Comment(_)
+            | Token::Directive(_)
+            | Token::DatumComment
+            | Token::Quote
+            | Token::BackQuote
+            | Token::Comma
+            | Token::CommaAt
+            | Token::DatumLabel(_) => {}
+            Token::OpenParenthesis
+            | Token::VectorOpen
+            | Token::ByteVectorOpen
+            | Token::NumericVectorOpen(_) => *depth += 1,
+            Token::CloseParenthesis if *depth > 0 => {
+                *depth -= 1;
+                *finished |= *depth == 0;
+            }
+            _ => *finished |= *depth == 0,
+        });
+        self.scanned = self.text.len();
+        match result {
+            Ok(()) => {}
+            Err((TokenError::MissingCloseQuote, start)) => self.open_token = Some((start, "\"")),
+            Err((TokenError::MissingCloseComment, start)) => self.open_token = Some((start, "|#")),
+            Err((TokenError::MissingCloseVerticalLine, start)) => {
+                self.open_token = Some((start, "|"))
+            }
+            Err(_) => self.finished = true,
+        }
+    }
+
+    /// Parses the text read so far into `parsed`, keeping only an unfinished
+    /// datum at its end while there is more input to finish it.
+    fn parse(&mut self) {
+        let source = Arc::new(Source {
+            name: self.name.clone(),
+            text: std::mem::take(&mut self.text),
+            line: self.line,
+        });
+        let length = source.text.len();
+        let mut offset = self.offset;
+        let rest = loop {
+            let (mut tokens, token_error) = match tokenize_source(&source, offset, length) {
+                Ok(tokens) => (tokens, None),
+                Err(error) => {
+                    // the datums before the invalid token are read all the same
+                    let start = error
+                        .location()
+                        .expect("token errors are located")
+                        .span
+                        .start;
+                    let tokens = tokenize_source(&source, offset, start)
+                        .expect("the tokens before the first invalid one are valid");
+                    (tokens, Some((error, start)))
+                }
+            };
+            let unfinished = self.parse_tokens(&mut tokens, &source.text);
+            match (unfinished, token_error) {
+                (None, None) => break None,
+                (Some((start, _)), None) if !self.exhausted => break Some(start),
+                (Some((_, error)), None) => {
+                    self.parsed.push_back(Err(error));
+                    break None;
+                }
+                (unfinished, Some((error, start))) if error.is_incomplete() => {
+                    if !self.exhausted {
+                        break Some(unfinished.map_or(start, |(start, _)| start));
+                    }
+                    // an unfinished datum before the token holds it
+                    self.parsed.push_back(Err(error));
+                    break None;
+                }
+                (_, Some((error, start))) => {
+                    self.parsed.push_back(Err(error));
+                    offset = line_end(&source.text, start);
+                }
+            }
+        };
+        let rest = rest.unwrap_or(length);
+        let line_start = source.text[..rest].rfind('\n').map_or(0, |index| index + 1);
+        self.line += source.text[..line_start].matches('\n').count();
+        self.text = source.text[line_start..].to_string();
+        self.offset = rest - line_start;
+        self.scanned = self.offset;
+        self.depth = 0;
+        self.open_token = None;
+        self.scan();
+        // what is left was parsed as unfinished, even where a datum that is
+        // commented out ends in it
+        self.finished = false;
+    }
+
+    /// Parses the datums of `tokens` into `parsed`, getting past the errors
+    /// of those that are wrong by skipping the rest of their line. A datum
+    /// left unfinished is returned with its offset and error instead.
+    fn parse_tokens(&mut self, tokens: &mut TokenBuffer, text: &str) -> Option<(usize, Error)> {
+        tokens.set_fold_case(self.fold_case);
+        loop {
+            if !tokens.has_datum() {
+                // only the directives are left to read
+                while !tokens.is_empty() {
+                    if let Token::Directive(directive) = *tokens.pop() {
+                        tokens.set_fold_case(directive == "#!fold-case");
+                    }
+                }
+                self.fold_case = tokens.fold_case();
+                return None;
+            }
+            let start = tokens.next_start().expect("a datum is left");
+            let fold_case = tokens.fold_case();
+            match parse_source(tokens) {
+                Ok(expression) => self.parsed.push_back(Ok(expression)),
+                Err(error) if error.is_incomplete() => {
+                    self.fold_case = fold_case;
+                    return Some((start, error));
+                }
+                Err(error) => {
+                    let end = tokens.span().map_or(start, |span| span.start);
+                    tokens.skip_to(line_end(text, end));
+                    self.parsed.push_back(Err(error));
+                }
+            }
+        }
+    }
+}
+
+/// The byte offset after the line of `text` that `offset` is in.
+fn line_end(text: &str, offset: usize) -> usize {
+    text[offset..]
+        .find('\n')
+        .map_or(text.len(), |index| offset + index + 1)
+}
+
+impl Reader<&'static [u8]> {
+    /// Reads `text`, all of which is at hand, in a single pass.
+    pub fn from_text(name: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Self::new(name, &[])
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Reader<R> {
+    type Item = Result<Expression, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(result) = self.parsed.pop_front() {
+                return Some(result);
+            }
+            if self.exhausted && self.text.len() == self.offset {
+                return None;
+            }
+            if self.finished || self.exhausted {
+                self.parse();
+                continue;
+            }
+            match self.input.read_line(&mut self.text) {
+                Ok(0) => self.exhausted = true,
+                Ok(_) => self.scan(),
+                Err(error) => {
+                    self.exhausted = true;
+                    return Some(Err(error.into()));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+
+    fn read_all(text: &str) -> Vec<Result<String, Error>> {
+        Reader::from_text("<test>", text)
+            .map(|result| result.map(|expression| expression.to_string()))
+            .collect()
+    }
+
+    /// Reads `text` a line at a time, as from a file.
+    fn read_lines(text: &str) -> Vec<Result<String, Error>> {
+        Reader::new("<test>", text.as_bytes())
+            .map(|result| result.map(|expression| expression.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_datums() {
+        assert_eq!(
+            read_all("(define x 1) (display x)\n; done\n'y #;(z) 2"),
+            [
+                Ok("(define x 1)".to_string()),
+                Ok("(display x)".to_string()),
+                Ok("(quote y)".to_string()),
+                Ok("2".to_string()),
+            ]
+        );
+        assert_eq!(read_all(" ; nothing\n#| at all |#\n"), []);
+    }
+
+    #[test]
+    fn test_trailing_datum_comment() {
+        assert_eq!(read_all("(a) #;(b)"), [Ok("(a)".to_string())]);
+        assert_eq!(read_all("#;5"), []);
+        assert_eq!(read_all("#;#;1 '2 ; done"), []);
+        let input = "(a)\n#;(b\n  c) ; gone\n#;#0='d\n".as_bytes();
+        let results: Vec<_> = Reader::new("<test>", input)
+            .map(|result| result.map(|expression| expression.to_string()))
+            .collect();
+        assert_eq!(results, [Ok("(a)".to_string())]);
+    }
+
+    #[test]
+    fn test_fold_case() {
+        assert_eq!(
+            read_all("'JSON #!fold-case 'JSON #\\SPACE\n#!no-fold-case 'JSON"),
+            [
+                Ok("(quote JSON)".to_string()),
+                Ok("(quote json)".to_string()),
+                Ok("#\\space".to_string()),
+                Ok("(quote JSON)".to_string()),
+            ]
+        );
+        let mut reader = Reader::from_text("<test>", "(Car 'ABC)").with_fold_case(true);
+        assert_eq!(
+            reader.next().unwrap().unwrap().to_string(),
+            "(car (quote abc))"
+        );
+        assert!(read_all("#!fold-cases")[0].is_err());
+    }
+
+    #[test]
+    fn test_locations() {
+        let mut reader = Reader::from_text("main.scm", "(a)\n  (b) (c\n  d)");
+        for (line, column) in [(1, 1), (2, 3), (2, 7)] {
+            let expression = reader.next().unwrap().unwrap();
+            let pair = expression.content.as_pair().unwrap();
+            let span = pair.location.as_ref().unwrap().span;
+            assert_eq!((span.line, span.column), (line, column));
+        }
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn test_incomplete() {
+        let results = read_all("(a) (b\n \"c");
+        assert_eq!(results[0], Ok("(a)".to_string()));
+        let error = results[1].as_ref().unwrap_err();
+        assert!(error.is_incomplete());
+        assert_eq!(error.location().unwrap().to_string(), "<test>:2:2");
+        for text in ["(a", "'", "#;", "\"a", "#| a", "#;(b", "#;#;1"] {
+            assert!(read_all(text)[0].as_ref().unwrap_err().is_incomplete());
+        }
+        let results = read_all("(a))\n(b)");
+        let error = results[1].as_ref().unwrap_err();
+        assert!(!error.is_incomplete());
+        assert_eq!(error.to_string(), "<test>:1:4: missing opening parenthesis");
+        assert_eq!(results[2], Ok("(b)".to_string()));
+    }
+
+    #[test]
+    fn test_lines() {
+        for text in [
+            "(a\n b) c\n(d)",
+            "#; a (b\n c)\n(d \"e\n\\\"f\")\n",
+            "#| (a\n |# (b #| c\n |#) (d))\n",
+            "(a))\n(b\n c)) (d)\n(e",
+            "(a) #\\bad (b)\n(c)",
+            "#!fold-case\n(A |B\nC|)\n#!no-fold-case X",
+        ] {
+            let display = |results: Vec<Result<String, Error>>| {
+                results
+                    .into_iter()
+                    .map(|result| result.unwrap_or_else(|error| error.to_string()))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(display(read_lines(text)), display(read_all(text)));
+        }
+        assert_eq!(read_lines("(a) #\\bad (b)\n(c)").len(), 3);
+    }
+
+    #[test]
+    fn test_large_datums() {
+        // Text that was read is not read again for more of it, which for
+        // these would take minutes.
+        let count = 50_000;
+        let list = format!("(\n{})", "x\n".repeat(count));
+        let defines = "(define x 1) ".repeat(count);
+        let start = Instant::now();
+        for text in [&list, &defines] {
+            let datums = Reader::from_text("<test>", text.as_str()).count();
+            assert_eq!(Reader::new("<test>", text.as_bytes()).count(), datums);
+        }
+        let string = format!("\"{}\"", "x\n".repeat(count));
+        assert_eq!(read_lines(&string).len(), 1);
+        assert!(start.elapsed() < Duration::from_secs(20));
+    }
+}
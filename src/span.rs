@@ -0,0 +1,153 @@
+use std::{fmt::Display, sync::Arc};
+
+/// A range of source text: byte offsets, and the line and column of its
+/// start. Lines and columns count from 1, and columns count characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    /// The span from the start of `self` to the end of `other`.
+    pub(crate) fn to(self, other: Span) -> Span {
+        Span {
+            end: other.end,
+            ..self
+        }
+    }
+}
+
+/// Source text and the name it is reported under, usually a file name.
+#[derive(Debug, PartialEq)]
+pub struct Source {
+    pub name: String,
+    pub text: String,
+    /// The line the text starts at, which is not the first for text read a
+    /// piece at a time.
+    pub line: usize,
+}
+
+impl Source {
+    pub fn new(name: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            text: text.into(),
+            line: 1,
+        }
+    }
+}
+
+/// A span together with the source it is in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Location {
+    pub source: Arc<Source>,
+    pub span: Span,
+}
+
+impl Location {
+    /// Renders the location and the first line of the span with carets under
+    /// the span:
+    ///
+    /// ```text
+    ///  --> main.scm:2:3
+    ///   |
+    /// 2 |   (foo 1)
+    ///   |   ^^^^^^^
+    /// ```
+    pub fn snippet(&self) -> String {
+        let Span {
+            start, end, line, ..
+        } = self.span;
+        let text = &self.source.text;
+        let line_start = text[..start].rfind('\n').map_or(0, |index| index + 1);
+        let line_end = text[start..]
+            .find('\n')
+            .map_or(text.len(), |index| start + index);
+        let source_line = text[line_start..line_end].trim_end_matches('\r');
+        // Tabs are kept so that the carets line up with the text above them.
+        let indent: String = text[line_start..start]
+            .chars()
+            .map(|character| if character == '\t' { '\t' } else { ' ' })
+            .collect();
+        let width = text[start..end.min(line_end).max(start)]
+            .chars()
+            .count()
+            .max(1);
+        let gutter = " ".repeat(line.to_string().len());
+        format!(
+            "{gutter}--> {self}\n{gutter} |\n{line} | {source_line}\n{gutter} | {indent}{}",
+            "^".repeat(width)
+        )
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}",
+            self.source.name, self.span.line, self.span.column
+        )
+    }
+}
+
+/// Turns byte offsets of a text into spans. Offsets passed to
+/// [`span`](Self::span) must not decrease, so that the text is scanned once.
+pub(crate) struct Locator<'a> {
+    text: &'a str,
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Locator<'a> {
+    pub(crate) fn new(text: &'a str, line: usize) -> Self {
+        Self {
+            text,
+            offset: 0,
+            line,
+            column: 1,
+        }
+    }
+
+    pub(crate) fn span(&mut self, start: usize, end: usize) -> Span {
+        for character in self.text[self.offset..start].chars() {
+            if character == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+        self.offset = start;
+        Span {
+            start,
+            end,
+            line: self.line,
+            column: self.column,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippet() {
+        let source = Arc::new(Source::new("main.scm", "(define x 1)\n\t(foo λ 2)\n"));
+        let mut locator = Locator::new(&source.text, 1);
+        assert_eq!(locator.span(0, 12).column, 1);
+        let span = locator.span(14, 24);
+        assert_eq!((span.line, span.column), (2, 2));
+        let location = Location { source, span };
+        assert_eq!(location.to_string(), "main.scm:2:2");
+        assert_eq!(
+            location.snippet(),
+            " --> main.scm:2:2\n  |\n2 | \t(foo λ 2)\n  | \t^^^^^^^^^"
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use wasm_bindgen::prelude::*;
 
 use crate::{
@@ -8,7 +10,17 @@ use crate::{
     canvas::{Canvas, Path},
     create_global_frame,
     data_model::{Frame, GraphicProcedure},
-    interpret,
+    error::Error,
+    limits::DEFAULT_MAX_STACK,
+    output, parse_recovering, Limits, Source,
+};
+
+const DEFAULT_LIMITS: Limits = Limits {
+    max_steps: None,
+    max_depth: None,
+    max_heap: None,
+    timeout: Some(Duration::from_secs(10)),
+    max_stack: Some(DEFAULT_MAX_STACK),
 };
 
 #[wasm_bindgen]
@@ -124,9 +136,47 @@ impl Output {
     }
 }
 
+/// A syntax error found by [`check_syntax`], for the editor to mark.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    message: String,
+
+    #[wasm_bindgen(readonly)]
+    pub line: usize,
+
+    #[wasm_bindgen(readonly)]
+    pub column: usize,
+}
+
+#[wasm_bindgen]
+impl Diagnostic {
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+}
+
+/// Finds every syntax error in `input` without evaluating it.
+#[wasm_bindgen(js_name = "checkSyntax")]
+pub fn check_syntax(input: String) -> Vec<Diagnostic> {
+    let (_, errors) = parse_recovering(Source::new("<input>", input));
+    errors
+        .into_iter()
+        .filter_map(|error| match error {
+            Error::Located(error, location) => Some(Diagnostic {
+                message: error.to_string(),
+                line: location.span.line,
+                column: location.span.column,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
 #[wasm_bindgen]
 pub struct Interpreter {
-    frame: Frame,
+    interpreter: crate::interpreter::Interpreter,
     canvas: Canvas,
 }
 
@@ -135,21 +185,86 @@ impl Interpreter {
     #[wasm_bindgen(constructor)]
     pub fn new() -> Self {
         let (frame, canvas) = create_wasm_global_env();
-        Self { frame, canvas }
+        let mut interpreter = crate::interpreter::Interpreter::with_frame(frame);
+        interpreter.set_limits(DEFAULT_LIMITS);
+        Self {
+            interpreter,
+            canvas,
+        }
+    }
+
+    #[wasm_bindgen(js_name = "setMaxSteps")]
+    pub fn set_max_steps(&mut self, steps: Option<u32>) {
+        let mut limits = *self.interpreter.limits();
+        limits.max_steps = steps.map(u64::from);
+        self.interpreter.set_limits(limits);
+    }
+
+    #[wasm_bindgen(js_name = "setMaxDepth")]
+    pub fn set_max_depth(&mut self, depth: Option<u32>) {
+        let mut limits = *self.interpreter.limits();
+        limits.max_depth = depth.map(|depth| depth as usize);
+        self.interpreter.set_limits(limits);
+    }
+
+    #[wasm_bindgen(js_name = "setMaxHeap")]
+    pub fn set_max_heap(&mut self, bytes: Option<u32>) {
+        let mut limits = *self.interpreter.limits();
+        limits.max_heap = bytes.map(|bytes| bytes as usize);
+        self.interpreter.set_limits(limits);
+    }
+
+    #[wasm_bindgen(js_name = "setMaxStack")]
+    pub fn set_max_stack(&mut self, bytes: Option<u32>) {
+        let mut limits = *self.interpreter.limits();
+        limits.max_stack = bytes.map(|bytes| bytes as usize);
+        self.interpreter.set_limits(limits);
+    }
+
+    /// Fails, leaving the timeout as it was, for a negative, infinite or NaN
+    /// number of milliseconds.
+    #[wasm_bindgen(js_name = "setTimeout")]
+    pub fn set_timeout(&mut self, milliseconds: Option<f64>) -> Result<(), String> {
+        let mut limits = *self.interpreter.limits();
+        limits.timeout = milliseconds
+            .map(|ms| {
+                Duration::try_from_secs_f64(ms / 1000.0)
+                    .map_err(|error| format!("invalid timeout of {} ms: {}", ms, error))
+            })
+            .transpose()?;
+        self.interpreter.set_limits(limits);
+        Ok(())
     }
 
     pub fn eval(&mut self, input: String) -> Result<Output, String> {
-        match interpret(&input, &mut self.frame) {
+        let (result, written) = output::capture(|| self.interpreter.eval(&input));
+        match result {
             Ok(value) => Ok(Output {
-                console: value.to_string(),
+                console: format!("{}{}", written, value),
                 canvas: self.canvas.export(),
             }),
-            Err(err) => Err(err.to_string()),
+            Err(err) => Err(format!("{}{}", written, err.render())),
         }
     }
 
+    #[wasm_bindgen(js_name = "saveImage")]
+    pub fn save_image(&self) -> Result<Vec<u8>, String> {
+        let mut image = vec![];
+        self.interpreter
+            .save_image(&mut image)
+            .map_err(|err| err.to_string())?;
+        Ok(image)
+    }
+
+    #[wasm_bindgen(js_name = "loadImage")]
+    pub fn load_image(&mut self, image: &[u8]) -> Result<(), String> {
+        self.interpreter
+            .load_image(image)
+            .map_err(|err| err.to_string())
+    }
+
     pub fn eval_file(&mut self, input: String) -> Result<Output, String> {
-        self.eval(format!("(begin {})", input))
+        self.eval(input)
     }
 }
 